@@ -0,0 +1,51 @@
+use anyhow::Context;
+use olivia::{
+    config::SignerConfig,
+    signer::{unix, KeyChainSigner},
+    signing_ledger::SigningLedger,
+};
+use std::{path::PathBuf, sync::Arc};
+use structopt::StructOpt;
+use tokio::net::UnixListener;
+
+#[macro_use]
+extern crate slog;
+
+/// Holds the oracle's seed and signs for it over a Unix socket
+#[derive(Debug, StructOpt)]
+#[structopt(name = "olivia-signer")]
+struct Opt {
+    #[structopt(short, long, parse(from_os_str), name = "yaml config file")]
+    config: PathBuf,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+    let config: SignerConfig = {
+        let file_name = opt.config.to_str().unwrap_or("config file").to_owned();
+        let content = std::fs::read_to_string(&opt.config)?;
+        serde_yaml::from_str(&content)
+            .context(format!("{} is an invalid configuration file", file_name))?
+    };
+    let logger = slog::Logger::root(config.loggers.to_slog_drain()?, o!());
+    let seed = config.load_seed()?;
+    let ledger = SigningLedger::open(&config.signing_ledger)?;
+
+    // a socket left behind by a previous run would make binding fail
+    if config.socket.exists() {
+        std::fs::remove_file(&config.socket)?;
+    }
+    let listener = UnixListener::bind(&config.socket)
+        .context(format!("unable to listen on {}", config.socket.display()))?;
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&config.socket, std::fs::Permissions::from_mode(0o600))?;
+    }
+    info!(logger, "listening"; "socket" => config.socket.display().to_string());
+
+    let signer = KeyChainSigner::with_ledger(seed, ledger);
+    olivia::with_group!(config.group, C => {
+        unix::serve::<C>(listener, Arc::new(signer), logger).await
+    })
+}
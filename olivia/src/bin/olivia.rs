@@ -11,7 +11,7 @@ extern crate tokio;
 #[structopt(name = "olivia")]
 struct Opt {
     #[structopt(short, long, parse(from_os_str), name = "yaml config file")]
-    config: PathBuf,
+    config: Option<PathBuf>,
     #[structopt(subcommand)]
     cmd: Command,
 }
@@ -27,6 +27,14 @@ pub enum Command {
     },
     /// Database commands
     Db(Db),
+    /// Start a new key epoch. Restart any running oracle afterwards so it announces with the new
+    /// keys. The new keys come from the same seed so this doesn't help if the seed has leaked.
+    RotateKeys,
+    /// Seed file commands
+    Seed(cli::seed::SeedCommand),
+    /// Recover an oracle's olivia-v1 secret key from two attestations to different outcomes of
+    /// the same event. Doesn't need a config file.
+    FraudProof(cli::fraud_proof::FraudProofArgs),
 }
 
 #[derive(Debug, StructOpt)]
@@ -37,10 +45,16 @@ pub enum Db {
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let opt = Opt::from_args();
+    if let Command::FraudProof(args) = opt.cmd {
+        return cli::fraud_proof::fraud_proof(args);
+    }
     let config: Config = {
         use std::{fs::File, io::Read};
-        let file_name = opt.config.to_str().unwrap_or("config file").to_owned();
-        let mut file = File::open(opt.config)?;
+        let config_file = opt
+            .config
+            .ok_or(anyhow::anyhow!("--config is needed for this command"))?;
+        let file_name = config_file.to_str().unwrap_or("config file").to_owned();
+        let mut file = File::open(config_file)?;
         let mut content = String::new();
         file.read_to_string(&mut content)?;
         serde_yaml::from_str(&content)
@@ -48,12 +62,17 @@ async fn main() -> anyhow::Result<()> {
     };
 
     match opt.cmd {
-        Command::Add(entity) => cli::add::add(config, entity).await,
-        Command::Run => cli::run::run(config).await,
-        Command::Derive { event } => cli::derive::derive(config, EventId::from_str(&event)?),
+        Command::Add(entity) => cli::add::add(config.resolve_seed()?, entity).await,
+        Command::Run => cli::run::run(config.resolve_seed()?).await,
+        Command::Derive { event } => {
+            cli::derive::derive(config.resolve_seed()?, EventId::from_str(&event)?)
+        }
         Command::Db(db) => match db {
             Db::Init => cli::db_cmd::init(config).await,
         },
+        Command::RotateKeys => cli::rotate_keys::rotate_keys(config.resolve_seed()?).await,
+        Command::Seed(cmd) => cli::seed::seed(config, cmd),
         Command::CheckConfig => Ok(()),
+        Command::FraudProof(_) => unreachable!("handled before reading the config"),
     }
 }
@@ -1,7 +1,7 @@
-use crate::{config::Config, Oracle};
+use crate::config::{Config, GroupConfig};
 use olivia_core::{
     chrono::{self, NaiveDateTime},
-    Event, EventId, Outcome, StampedOutcome,
+    Event, EventId, Group, Outcome, StampedOutcome,
 };
 
 #[derive(Debug, structopt::StructOpt)]
@@ -17,34 +17,49 @@ pub enum Entity {
     },
 }
 
-pub async fn add(config: Config, entity: Entity) -> anyhow::Result<()> {
-    let secret_seed = config.secret_seed.ok_or(anyhow::anyhow!(
-        "Cannot use the add command when oracle is in read-only mode"
-    ))?;
-    let db = config.database.connect_database().await?;
-    let oracle = Oracle::new(secret_seed, db.clone()).await?;
+#[derive(Clone)]
+enum Addition {
+    Event(Event),
+    Outcome(StampedOutcome),
+}
 
-    match entity {
+/// Adds the entity in every group
+pub async fn add(config: Config, entity: Entity) -> anyhow::Result<()> {
+    let addition = match entity {
         Entity::Event {
             event_id,
             expected_outcome_time,
-        } => {
-            oracle
-                .add_event(Event {
-                    id: event_id,
-                    expected_outcome_time,
-                })
-                .await?
-        }
-        Entity::Outcome { event_id, outcome } => {
-            let outcome = Outcome::try_from_id_and_outcome(event_id, &outcome)?;
-            oracle
-                .complete_event(StampedOutcome {
-                    time: chrono::Utc::now().naive_utc(),
-                    outcome,
-                })
-                .await?;
-        }
+        } => Addition::Event(Event {
+            id: event_id,
+            expected_outcome_time,
+        }),
+        Entity::Outcome { event_id, outcome } => Addition::Outcome(StampedOutcome {
+            time: chrono::Utc::now().naive_utc(),
+            outcome: Outcome::try_from_id_and_outcome(event_id, &outcome)?,
+        }),
+    };
+    for group in config.checked_groups()? {
+        crate::with_group!(group.group, C => add_to_group::<C>(&config, group, addition.clone()).await?)
+    }
+    Ok(())
+}
+
+async fn add_to_group<C: Group>(
+    config: &Config,
+    group: &GroupConfig,
+    addition: Addition,
+) -> anyhow::Result<()> {
+    let db = config.group_database(group).connect_database::<C>().await?;
+    let oracle = config
+        .build_oracle(group, db)
+        .await?
+        .ok_or(anyhow::anyhow!(
+            "Cannot use the add command when oracle is in read-only mode"
+        ))?;
+
+    match addition {
+        Addition::Event(event) => oracle.add_event(event).await?,
+        Addition::Outcome(stamped) => oracle.complete_event(stamped).await?,
     }
 
     Ok(())
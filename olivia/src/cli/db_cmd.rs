@@ -4,13 +4,21 @@ use crate::{
 };
 use anyhow::anyhow;
 
+/// Sets up the postgres database of every group
 pub async fn init(config: Config) -> anyhow::Result<()> {
-    match config.database {
-        DbConfig::Postgres { url } => {
-            let db = PgBackendWrite::connect(&url).await?;
-            db.setup().await?;
+    for group in config.checked_groups()? {
+        match config.group_database(group) {
+            DbConfig::Postgres { url } => {
+                let db = PgBackendWrite::connect(url).await?;
+                db.setup().await?;
+            }
+            _ => {
+                return Err(anyhow!(
+                    "can only run init on a postgres database ({:?} isn't using one)",
+                    group.group
+                ))
+            }
         }
-        _ => return Err(anyhow!("can only run init on a postgres database")),
     }
     Ok(())
 }
@@ -1,13 +1,17 @@
 use crate::{config::Config, keychain::KeyChain};
-use olivia_core::EventId;
+use olivia_core::{EventId, Group};
 
 pub fn derive(config: Config, event: EventId) -> anyhow::Result<()> {
     let secret_seed = config
         .secret_seed
+        .clone()
         .ok_or(anyhow::anyhow!("config file needs secret_seed to run"))?;
-    let keychain = KeyChain::<olivia_secp256k1::Secp256k1>::new(secret_seed);
-    let nonce = keychain.nonces_for_event(&event);
-
-    println!("secp256k1: {:?}", nonce);
+    for group in config.checked_groups()? {
+        crate::with_group!(group.group, C => {
+            let keychain = KeyChain::<C>::new(secret_seed.clone());
+            let nonce = keychain.nonces_for_event(&event);
+            println!("{}: {:?}", C::name(), nonce);
+        });
+    }
     Ok(())
 }
@@ -0,0 +1,81 @@
+use crate::config::GroupKind;
+use anyhow::{anyhow, Context};
+use olivia_core::{
+    http::{EventResponse, RootResponse},
+    FraudProof, Group,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, structopt::StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct FraudProofArgs {
+    /// The oracle's response at `/` as JSON
+    #[structopt(parse(from_os_str))]
+    oracle: PathBuf,
+    /// A response for the event as JSON
+    #[structopt(parse(from_os_str))]
+    first: PathBuf,
+    /// Another response for the event attesting to a different outcome
+    #[structopt(parse(from_os_str))]
+    second: PathBuf,
+}
+
+/// Prints the oracle's olivia-v1 secret key if the two event responses attest to different
+/// outcomes.
+pub fn fraud_proof(args: FraudProofArgs) -> anyhow::Result<()> {
+    let oracle: serde_json::Value = read_json(&args.oracle)?;
+    let group: GroupKind = serde_json::from_value(oracle["public-keys"]["group"].clone())
+        .context("the oracle's response doesn't have a known group")?;
+
+    crate::with_group!(group, C => {
+        let proof = check::<C>(
+            serde_json::from_value(oracle).context(format!(
+                "{} is not a valid response",
+                args.oracle.display()
+            ))?,
+            [read_json(&args.first)?, read_json(&args.second)?],
+        )?;
+        println!("{}", serde_json::to_string_pretty(&proof)?);
+    });
+
+    Ok(())
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> anyhow::Result<T> {
+    let content = fs::read_to_string(path).context(format!("reading {}", path.display()))?;
+    serde_json::from_str(&content).context(format!("{} is not a valid response", path.display()))
+}
+
+pub fn check<C: Group>(
+    oracle: RootResponse<C>,
+    events: [EventResponse<C>; 2],
+) -> anyhow::Result<FraudProof<C>> {
+    let [first, second] = events;
+    if first.announcement != second.announcement {
+        return Err(anyhow!("the responses are for different announcements"));
+    }
+
+    // oracles from before keys could be rotated only have their current keys
+    let epoch_keys = match oracle.key_epochs.is_empty() {
+        true => vec![&oracle.public_keys],
+        false => oracle
+            .key_epochs
+            .iter()
+            .map(|key_epoch| &key_epoch.keys)
+            .collect(),
+    };
+
+    let attestations = match (&first.attestation, &second.attestation) {
+        (Some(first), Some(second)) => [first, second],
+        _ => return Err(anyhow!("both responses need an attestation")),
+    };
+
+    Ok(FraudProof::from_attestations(
+        epoch_keys,
+        &first.announcement,
+        attestations,
+    )?)
+}
@@ -1,4 +1,7 @@
 pub mod add;
 pub mod db_cmd;
 pub mod derive;
+pub mod fraud_proof;
+pub mod rotate_keys;
 pub mod run;
+pub mod seed;
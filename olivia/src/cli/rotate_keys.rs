@@ -0,0 +1,24 @@
+use crate::config::{Config, GroupConfig};
+use olivia_core::Group;
+
+/// Starts a new key epoch in every group
+pub async fn rotate_keys(config: Config) -> anyhow::Result<()> {
+    for group in config.checked_groups()? {
+        crate::with_group!(group.group, C => rotate_group_keys::<C>(&config, group).await?)
+    }
+    Ok(())
+}
+
+async fn rotate_group_keys<C: Group>(config: &Config, group: &GroupConfig) -> anyhow::Result<()> {
+    let db = config.group_database(group).connect_database::<C>().await?;
+    let mut oracle = config
+        .build_oracle(group, db)
+        .await?
+        .ok_or(anyhow::anyhow!(
+            "Cannot rotate keys when oracle is in read-only mode"
+        ))?;
+    let key_epoch = oracle.rotate_keys().await?;
+
+    println!("{}", serde_json::to_string_pretty(&key_epoch)?);
+    Ok(())
+}
@@ -1,22 +1,54 @@
-use crate::{config::Config, oracle::Oracle, oracle_loop::OracleLoop};
+use crate::{
+    config::Config,
+    db::{DbReadEvent, GroupsDb},
+    oracle_loop::{AnyGroupOracle, GroupOracle, OracleLoop},
+    rest_api,
+};
 use core::{
     future::{self, Future},
     pin::Pin,
 };
+use olivia_core::Group;
+use std::sync::Arc;
 
 pub async fn run(config: Config) -> anyhow::Result<()> {
     let logger = slog::Logger::root(config.loggers.to_slog_drain()?, o!());
-    let db = config.database.connect_database().await?;
+    let groups = config.checked_groups()?;
+
+    let mut oracles: Vec<Box<dyn AnyGroupOracle>> = vec![];
+    let mut oracle_dbs = vec![];
+    let mut routes = vec![];
+    for group in groups {
+        let database = config.group_database(group);
+        crate::with_group!(group.group, C => {
+            let db = database.connect_database::<C>().await?;
+            routes.push((
+                C::name(),
+                rest_api::boxed_routes(
+                    database.connect_database_read_group::<C>().await?,
+                    logger.new(o!("type" => "http")),
+                ),
+            ));
+            if let Some(oracle) = config.build_oracle::<C>(group, db.clone()).await? {
+                if oracle.key_namespaces_changed() {
+                    warn!(
+                        logger,
+                        "key-namespaces differ from the current key epoch and will only be used after rotate-keys";
+                        "group" => C::name()
+                    );
+                }
+                oracle_dbs.push(database.connect_database_read::<C>().await?);
+                oracles.push(Box::new(GroupOracle { oracle, db }));
+            }
+        });
+    }
 
     let rest_server: Pin<Box<dyn Future<Output = _>>> = match &config.rest_api {
         Some(rest_config) => {
             let logger = logger.new(o!("type" => "http"));
             info!(logger, "starting http server on {}", rest_config.listen);
-            let rest_api_server = warp::serve(crate::rest_api::routes(
-                config.database.connect_database_read_group().await?,
-                logger.clone(),
-            ))
-            .run(rest_config.listen);
+            let rest_api_server =
+                warp::serve(rest_api::group_routes(routes)).run(rest_config.listen);
 
             Box::pin(tokio::spawn(async move {
                 rest_api_server.await;
@@ -26,33 +58,42 @@ pub async fn run(config: Config) -> anyhow::Result<()> {
         None => Box::pin(future::ready(Ok(()))),
     };
 
-    let oracle_loop: Pin<Box<dyn Future<Output = _>>> = match &config.secret_seed {
-        Some(secret_seed) => {
-            let read_conn = config.database.connect_database_read().await?;
-            let events = config.build_event_streams(read_conn.clone(), logger.clone())?;
-            let outcomes = config.build_outcome_streams(
-                read_conn,
-                &secret_seed.child(b"outcome-seed"),
-                logger.clone(),
-            )?;
-
-            let nodes = config.build_node_streams(logger.clone())?;
-
-            let oracle = Oracle::new(secret_seed.clone(), db.clone()).await?;
-
-            Box::pin(tokio::spawn(
-                OracleLoop {
-                    events,
-                    outcomes,
-                    nodes,
-                    oracle,
-                    db,
-                    logger: logger.clone(),
-                }
-                .start(),
-            ))
+    let oracle_loop: Pin<Box<dyn Future<Output = _>>> = if oracles.is_empty() {
+        Box::pin(future::ready(Ok(())))
+    } else {
+        // the event and outcome sources look at every group's events so a group that failed to
+        // add an event or attest to an outcome gets it again
+        let read_conn: Arc<dyn DbReadEvent> = Arc::new(GroupsDb::new(oracle_dbs));
+        let events = config.build_event_streams(read_conn.clone(), logger.clone())?;
+        let outcome_seed = config
+            .secret_seed
+            .as_ref()
+            .map(|secret_seed| secret_seed.child(b"outcome-seed"));
+        let outcomes =
+            config.build_outcome_streams(read_conn, outcome_seed.as_ref(), logger.clone())?;
+
+        let nodes = config.build_node_streams(logger.clone())?;
+
+        let seed_signed = groups
+            .iter()
+            .any(|group| config.group_signer_socket(group).is_none());
+        if seed_signed && config.signing_ledger.is_none() {
+            warn!(
+                logger,
+                "no signing-ledger is set so only the database prevents attesting to two outcomes for an event"
+            );
         }
-        None => Box::pin(future::ready(Ok(()))),
+
+        Box::pin(tokio::spawn(
+            OracleLoop {
+                events,
+                outcomes,
+                nodes,
+                oracles,
+                logger: logger.clone(),
+            }
+            .start(),
+        ))
     };
 
     let _ = tokio::join!(rest_server, oracle_loop);
@@ -0,0 +1,88 @@
+use crate::{
+    config::{read_secret, Config, SeedConfig},
+    seed::Seed,
+    seed_file,
+};
+use anyhow::anyhow;
+use rand::RngCore;
+use std::path::PathBuf;
+
+#[derive(Debug, structopt::StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub enum SeedCommand {
+    /// Generate a new seed from a BIP39 mnemonic and write it to the seed file in the config
+    Generate,
+    /// Encrypt the seed file in the config under a new passphrase read from
+    /// OLIVIA_NEW_SEED_PASSPHRASE or prompted for
+    ReEncrypt,
+}
+
+pub fn seed(config: Config, cmd: SeedCommand) -> anyhow::Result<()> {
+    let (path, passphrase_env) = match config.seed {
+        Some(SeedConfig::File {
+            path,
+            passphrase_env,
+        }) => (path, passphrase_env),
+        _ => {
+            return Err(anyhow!(
+                "config needs a seed of type file to use seed commands"
+            ))
+        }
+    };
+
+    match cmd {
+        SeedCommand::Generate => generate(path, &passphrase_env),
+        SeedCommand::ReEncrypt => re_encrypt(path, &passphrase_env),
+    }
+}
+
+fn generate(path: PathBuf, passphrase_env: &str) -> anyhow::Result<()> {
+    if path.exists() {
+        return Err(anyhow!("{} already exists", path.display()));
+    }
+    let mut entropy = [0u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut entropy);
+    let mnemonic = bip39::Mnemonic::from_entropy(&entropy)?;
+    let seed = Seed::from_mnemonic(&mnemonic.to_string())?;
+    let passphrase = read_new_passphrase(passphrase_env)?;
+    let file = seed_file::encrypt(
+        &seed,
+        &passphrase,
+        Default::default(),
+        &mut rand::rngs::OsRng,
+    )?;
+    seed_file::write(&path, &file)?;
+
+    eprintln!(
+        "wrote seed to {}. Write down the mnemonic below, it can restore the seed:",
+        path.display()
+    );
+    println!("{}", mnemonic);
+    Ok(())
+}
+
+fn re_encrypt(path: PathBuf, passphrase_env: &str) -> anyhow::Result<()> {
+    let passphrase = read_secret(passphrase_env, "current seed file passphrase: ")?;
+    let seed = seed_file::read(&path, &passphrase)?;
+    let new_passphrase = read_new_passphrase("OLIVIA_NEW_SEED_PASSPHRASE")?;
+    let file = seed_file::encrypt(
+        &seed,
+        &new_passphrase,
+        Default::default(),
+        &mut rand::rngs::OsRng,
+    )?;
+    seed_file::write(&path, &file)?;
+    eprintln!("re-encrypted {}", path.display());
+    Ok(())
+}
+
+fn read_new_passphrase(env: &str) -> anyhow::Result<String> {
+    if let Ok(passphrase) = std::env::var(env) {
+        return Ok(passphrase);
+    }
+    let passphrase = rpassword::prompt_password("new seed file passphrase: ")?;
+    if passphrase != rpassword::prompt_password("repeat passphrase: ")? {
+        return Err(anyhow!("passphrases did not match"));
+    }
+    Ok(passphrase)
+}
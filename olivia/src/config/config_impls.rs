@@ -1,18 +1,118 @@
 use super::*;
 use crate::{
     db::{self, postgres::PgBackendWrite, DbReadEvent, PrefixedDb},
+    seed_file,
+    signer::{unix::UnixSigner, KeyChainSigner, Signer},
+    signing_ledger::SigningLedger,
     sources::{
         self,
         ticker::{RandomOutcomeCreator, ZeroOutcomeCreator},
     },
+    Oracle,
+};
+use anyhow::{anyhow, Context};
+use olivia_core::{chrono, Event, Group, Node, NodeKind, Path, RangeKind, StampedOutcome};
+use sources::{
+    ticker::{DeadlineOutcomeStream, TimeOutcomeStream},
+    Update,
+};
+use std::{
+    any::Any,
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex},
 };
-use olivia_core::{chrono, Event, Node, NodeKind, Path, RangeKind, StampedOutcome};
-use sources::{ticker::TimeOutcomeStream, Update};
-use std::{fs, sync::Arc};
 use tokio_stream as stream;
 use tokio_stream::StreamMap;
 
 impl Config {
+    /// Loads the secret seed from `seed` into `secret_seed`
+    pub fn resolve_seed(mut self) -> anyhow::Result<Self> {
+        match (&self.secret_seed, self.seed.take()) {
+            (Some(_), Some(_)) => Err(anyhow!(
+                "only one of secret-seed and seed can be set in the config"
+            )),
+            (_, None) => Ok(self),
+            (None, Some(seed_config)) => {
+                self.secret_seed = Some(seed_config.load()?);
+                Ok(self)
+            }
+        }
+    }
+
+    /// The configured groups after checking none of them share a database or signer
+    pub fn checked_groups(&self) -> anyhow::Result<&[GroupConfig]> {
+        if self.groups.is_empty() {
+            return Err(anyhow!("groups can't be empty"));
+        }
+        for (i, group) in self.groups.iter().enumerate() {
+            for other in &self.groups[..i] {
+                if other.group == group.group {
+                    return Err(anyhow!("{:?} is in groups more than once", group.group));
+                }
+                if let (DbConfig::Postgres { url }, DbConfig::Postgres { url: other_url }) =
+                    (self.group_database(group), self.group_database(other))
+                {
+                    if url == other_url {
+                        return Err(anyhow!(
+                            "{:?} and {:?} can't use the same postgres database",
+                            other.group,
+                            group.group
+                        ));
+                    }
+                }
+                match (
+                    self.group_signer_socket(group),
+                    self.group_signer_socket(other),
+                ) {
+                    (Some(socket), Some(other_socket)) if socket == other_socket => {
+                        return Err(anyhow!(
+                            "{:?} and {:?} can't use the same signer-socket",
+                            other.group,
+                            group.group
+                        ))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(&self.groups)
+    }
+
+    pub fn group_database<'a>(&'a self, group: &'a GroupConfig) -> &'a DbConfig {
+        group.database.as_ref().unwrap_or(&self.database)
+    }
+
+    pub fn group_signer_socket<'a>(&'a self, group: &'a GroupConfig) -> Option<&'a PathBuf> {
+        group.signer_socket.as_ref().or(self.signer_socket.as_ref())
+    }
+
+    /// The oracle for `group` signing with its `signer-socket` if it's set or otherwise the secret
+    /// seed. There is no oracle if neither is set.
+    pub async fn build_oracle<C: Group>(
+        &self,
+        group: &GroupConfig,
+        db: Arc<dyn db::Db<C>>,
+    ) -> anyhow::Result<Option<Oracle<C>>> {
+        let signer: Arc<dyn Signer<C>> = match (self.group_signer_socket(group), &self.secret_seed)
+        {
+            (Some(socket), _) => Arc::new(UnixSigner::new(socket.clone())),
+            (None, Some(secret_seed)) => {
+                let ledger = match &self.signing_ledger {
+                    Some(path) => SigningLedger::open(path)?,
+                    None => SigningLedger::in_memory(),
+                };
+                Arc::new(KeyChainSigner::with_ledger(secret_seed.clone(), ledger))
+            }
+            (None, None) => return Ok(None),
+        };
+        let oracle = Oracle::new_with_signer(signer, db, self.key_namespaces.clone())
+            .await?
+            .with_overflow_policy(self.overflow_policy);
+        Ok(Some(oracle))
+    }
+
     pub fn build_event_streams(
         &self,
         db: Arc<dyn DbReadEvent>,
@@ -35,9 +135,33 @@ impl Config {
     pub fn build_outcome_streams(
         &self,
         db: Arc<dyn DbReadEvent>,
-        secret_seed: &Seed,
+        secret_seed: Option<&Seed>,
         logger: slog::Logger,
     ) -> anyhow::Result<StreamMap<(Path, usize), sources::Stream<StampedOutcome>>> {
+        let mounted = |is_source: fn(&OutcomeSource) -> bool| {
+            self.outcomes
+                .iter()
+                .filter(move |(_, sources)| {
+                    sources
+                        .iter()
+                        .any(|source| is_source(&source.outcome_source))
+                })
+                .map(|(parent, _)| parent.as_path_ref())
+        };
+        // the deadline source would attest `false` to blocks that were mined before the deadline
+        // but don't have enough confirmations yet
+        for deadline in mounted(|source| matches!(source, OutcomeSource::Deadline { .. })) {
+            for bitcoind in mounted(|source| matches!(source, OutcomeSource::Bitcoind(_))) {
+                if deadline.is_parent_of(bitcoind) || bitcoind.is_parent_of(deadline) {
+                    return Err(anyhow!(
+                        "the deadline outcome source at {} can't be used for the bitcoind events at {}",
+                        deadline,
+                        bitcoind
+                    ));
+                }
+            }
+        }
+
         let mut streams = StreamMap::new();
 
         for (parent, sources) in self.outcomes.clone() {
@@ -45,7 +169,7 @@ impl Config {
             let logger = logger.new(o!("path" => parent.to_string()));
             for (i, source) in sources.into_iter().enumerate() {
                 let stream = source.to_outcome_stream(
-                    secret_seed.child(parent.as_str().as_bytes()),
+                    secret_seed.map(|secret_seed| secret_seed.child(parent.as_str().as_bytes())),
                     logger.clone(),
                     db.clone(),
                 )?;
@@ -206,10 +330,14 @@ impl EventSourceConfig {
 
         if let Some(predicate) = self.predicate.clone() {
             match predicate {
-                PredicateConfig { kind, filter } => {
+                PredicateConfig {
+                    kind,
+                    filter,
+                    width,
+                } => {
                     let pred = sources::predicate::Predicate {
                         outcome_filter: filter,
-                        predicate_kind: kind.into(),
+                        predicate_kind: kind.with_width(width)?,
                     };
                     Ok(Box::pin(async_stream::stream! {
                         loop {
@@ -257,7 +385,7 @@ impl EventSourceConfig {
 impl OutcomeSourceConfig {
     pub fn to_outcome_stream(
         &self,
-        seed: Seed,
+        seed: Option<Seed>,
         logger: slog::Logger,
         db: PrefixedDb,
     ) -> anyhow::Result<sources::Stream<StampedOutcome>> {
@@ -284,20 +412,46 @@ impl OutcomeSourceConfig {
                     logger.new(o!("source_type" => "redis")),
                 )?)
             }
+            Bitcoind(BitcoindConfig {
+                url,
+                user,
+                password,
+                confirmations,
+                poll_interval,
+            }) => {
+                info!(logger, "Getting block outcomes from bitcoind at {}", url);
+                Box::pin(
+                    sources::bitcoind::BitcoindOutcomeStream {
+                        db: db.clone(),
+                        rpc: sources::bitcoind::BitcoindRpc::new(
+                            &url,
+                            user.as_deref(),
+                            password.as_deref(),
+                        )?,
+                        logger: logger.new(o!("source_type" => "bitcoind")),
+                        confirmations,
+                        poll_interval: std::time::Duration::from_secs(poll_interval),
+                    }
+                    .start(),
+                )
+            }
             Random {
                 ends_with,
                 event_kind,
                 max,
-            } => Box::pin(
-                TimeOutcomeStream {
-                    db: db.clone(),
-                    logger: logger.new(o!("source_type" => "random")),
-                    ends_with,
-                    event_kind,
-                    outcome_creator: RandomOutcomeCreator { seed, max },
-                }
-                .start(),
-            ),
+            } => {
+                let seed = seed.ok_or_else(|| anyhow!("random outcomes need a secret-seed"))?;
+                Box::pin(
+                    TimeOutcomeStream {
+                        db: db.clone(),
+                        logger: logger.new(o!("source_type" => "random")),
+                        ends_with,
+                        event_kind,
+                        outcome_creator: RandomOutcomeCreator { seed, max },
+                    }
+                    .start(),
+                )
+            }
             Zero {
                 ends_with,
                 event_kind,
@@ -311,6 +465,14 @@ impl OutcomeSourceConfig {
                 }
                 .start(),
             ),
+            Deadline { ends_with } => Box::pin(
+                DeadlineOutcomeStream {
+                    db: db.clone(),
+                    logger: logger.new(o!("source_type" => "deadline")),
+                    ends_with,
+                }
+                .start(),
+            ),
         };
 
         if self.complete_related {
@@ -346,41 +508,108 @@ impl OutcomeSourceConfig {
 }
 
 lazy_static::lazy_static! {
-    static ref IN_MEMORY: db::in_memory::InMemory<olivia_secp256k1::Secp256k1> = db::in_memory::InMemory::default();
+    /// The in-memory database of each group. Every connection to it shares the same data.
+    static ref IN_MEMORY: Mutex<HashMap<&'static str, Box<dyn Any + Send + Sync>>> = Mutex::new(HashMap::new());
+}
+
+fn in_memory<C: Group>() -> db::in_memory::InMemory<C> {
+    IN_MEMORY
+        .lock()
+        .unwrap()
+        .entry(C::name())
+        .or_insert_with(|| Box::new(db::in_memory::InMemory::<C>::default()))
+        .downcast_ref::<db::in_memory::InMemory<C>>()
+        .expect("in-memory databases are stored under their group's name")
+        .clone()
 }
 
 impl DbConfig {
-    pub async fn connect_database_read_group(
+    pub async fn connect_database_read_group<C: Group>(
         &self,
-    ) -> anyhow::Result<Arc<dyn db::DbReadOracle<olivia_secp256k1::Secp256k1>>> {
+    ) -> anyhow::Result<Arc<dyn db::DbReadOracle<C>>> {
         match self {
-            DbConfig::InMemory => Ok(Arc::new(IN_MEMORY.clone())),
+            DbConfig::InMemory => Ok(Arc::new(in_memory::<C>())),
             DbConfig::Postgres { url } => Ok(Arc::new(db::postgres::connect_read(url).await?)),
         }
     }
 
-    pub async fn connect_database_read(&self) -> anyhow::Result<Arc<dyn db::DbReadEvent>> {
+    /// Reads the events of `C`'s database
+    pub async fn connect_database_read<C: Group>(
+        &self,
+    ) -> anyhow::Result<Arc<dyn db::DbReadEvent>> {
         match self {
-            DbConfig::InMemory => Ok(Arc::new(IN_MEMORY.clone())),
+            DbConfig::InMemory => Ok(Arc::new(in_memory::<C>())),
             DbConfig::Postgres { url } => Ok(Arc::new(db::postgres::connect_read(url).await?)),
         }
     }
 
-    pub async fn connect_database(
-        &self,
-    ) -> anyhow::Result<Arc<dyn db::Db<olivia_secp256k1::Secp256k1>>> {
+    /// Connects to `C`'s database migrating its schema if it's out of date
+    pub async fn connect_database<C: Group>(&self) -> anyhow::Result<Arc<dyn db::Db<C>>> {
+        match self {
+            DbConfig::InMemory => Ok(Arc::new(in_memory::<C>())),
+            DbConfig::Postgres { url } => {
+                let db = PgBackendWrite::connect(url).await?;
+                db.migrate().await?;
+                Ok(Arc::new(db))
+            }
+        }
+    }
+}
+
+impl PredicateKind {
+    fn with_width(self, width: Option<u64>) -> anyhow::Result<olivia_core::PredicateKind> {
+        use olivia_core::BoundKind;
+        let bound = |bound_kind| match width {
+            Some(_) => Err(anyhow::anyhow!("only range predicates have a width")),
+            None => Ok(olivia_core::PredicateKind::Bound(bound_kind)),
+        };
         match self {
-            DbConfig::InMemory => Ok(Arc::new(IN_MEMORY.clone())),
-            DbConfig::Postgres { url } => Ok(Arc::new(PgBackendWrite::connect(url).await?)),
+            PredicateKind::Eq => match width {
+                Some(_) => Err(anyhow::anyhow!("only range predicates have a width")),
+                None => Ok(olivia_core::PredicateKind::Eq),
+            },
+            PredicateKind::Gt => bound(BoundKind::Gt),
+            PredicateKind::Lt => bound(BoundKind::Lt),
+            PredicateKind::Ge => bound(BoundKind::Ge),
+            PredicateKind::Le => bound(BoundKind::Le),
+            PredicateKind::Range => match width {
+                Some(width) => Ok(olivia_core::PredicateKind::Range { width }),
+                None => Err(anyhow::anyhow!("range predicates need a width")),
+            },
+        }
+    }
+}
+
+impl SeedConfig {
+    pub fn load(&self) -> anyhow::Result<Seed> {
+        match self {
+            SeedConfig::Mnemonic { words } => {
+                let words = match words {
+                    Some(words) => words.clone(),
+                    None => read_secret("OLIVIA_SEED_MNEMONIC", "BIP39 mnemonic: ")?,
+                };
+                Ok(Seed::from_mnemonic(&words)?)
+            }
+            SeedConfig::File {
+                path,
+                passphrase_env,
+            } => {
+                let passphrase = read_secret(passphrase_env, "seed file passphrase: ")?;
+                seed_file::read(path, &passphrase)
+                    .with_context(|| format!("unable to read seed file {}", path.display()))
+            }
         }
     }
 }
 
-impl From<PredicateKind> for olivia_core::PredicateKind {
-    fn from(from: PredicateKind) -> Self {
-        match from {
-            PredicateKind::Eq => olivia_core::PredicateKind::Eq,
-            PredicateKind::Gt => olivia_core::PredicateKind::Bound(olivia_core::BoundKind::Gt),
+impl SignerConfig {
+    pub fn load_seed(&self) -> anyhow::Result<Seed> {
+        match (&self.secret_seed, &self.seed) {
+            (Some(secret_seed), None) => Ok(secret_seed.clone()),
+            (None, Some(seed_config)) => seed_config.load(),
+            _ => Err(anyhow!(
+                "exactly one of secret-seed and seed must be set in the signer config"
+            )),
         }
     }
 }
@@ -1,7 +1,7 @@
 use crate::{seed::Seed, sources::predicate::OutcomeFilter};
-use olivia_core::{chrono::NaiveDateTime, Event, EventKind, Path};
+use olivia_core::{chrono::NaiveDateTime, Event, EventKind, OverflowPolicy, Path};
 use redis::IntoConnectionInfo;
-use std::{collections::HashMap, str::FromStr};
+use std::{collections::HashMap, path::PathBuf, str::FromStr};
 
 mod config_impls;
 
@@ -24,7 +24,89 @@ pub struct Config {
     #[serde(default)]
     pub loggers: LoggersConfig,
     pub secret_seed: Option<Seed>,
+    /// Load the secret seed from somewhere other than the config file
+    pub seed: Option<SeedConfig>,
     pub rest_api: Option<RestConfig>,
+    /// What to do with outcomes the event's nonces can't attest to
+    #[serde(default)]
+    pub overflow_policy: OverflowPolicy,
+    /// Paths whose events are attested to with their own olivia-v1 key
+    #[serde(default)]
+    pub key_namespaces: Vec<Path>,
+    /// Sign with an olivia-signer listening on this socket instead of the secret seed
+    pub signer_socket: Option<PathBuf>,
+    /// Where to record every outcome signed with the secret seed
+    pub signing_ledger: Option<PathBuf>,
+    /// The groups the oracle announces and attests in. The first group is served at the root of
+    /// the REST API.
+    #[serde(default = "default_groups")]
+    pub groups: Vec<GroupConfig>,
+}
+
+/// A group the oracle serves. A postgres database can only hold one group's keys so every group
+/// after the first one needs its own `database`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+#[serde(deny_unknown_fields)]
+pub struct GroupConfig {
+    pub group: GroupKind,
+    /// Used instead of the top level `database`
+    pub database: Option<DbConfig>,
+    /// Used instead of the top level `signer-socket`
+    pub signer_socket: Option<PathBuf>,
+}
+
+fn default_groups() -> Vec<GroupConfig> {
+    vec![GroupConfig {
+        group: GroupKind::Secp256k1,
+        database: None,
+        signer_socket: None,
+    }]
+}
+
+/// The config for the olivia-signer daemon
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+#[serde(deny_unknown_fields)]
+pub struct SignerConfig {
+    /// The Unix socket to listen on
+    pub socket: PathBuf,
+    pub secret_seed: Option<Seed>,
+    pub seed: Option<SeedConfig>,
+    /// Where to record every outcome signed
+    pub signing_ledger: PathBuf,
+    #[serde(default)]
+    pub group: GroupKind,
+    #[serde(default)]
+    pub loggers: LoggersConfig,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case", tag = "type", deny_unknown_fields)]
+pub enum SeedConfig {
+    /// A BIP39 mnemonic. If `words` isn't set they are read from `OLIVIA_SEED_MNEMONIC` or
+    /// prompted for.
+    Mnemonic { words: Option<String> },
+    /// A passphrase encrypted seed file. The passphrase is read from the `passphrase-env`
+    /// environment variable or prompted for.
+    #[serde(rename_all = "kebab-case")]
+    File {
+        path: PathBuf,
+        #[serde(default = "default_passphrase_env")]
+        passphrase_env: String,
+    },
+}
+
+fn default_passphrase_env() -> String {
+    "OLIVIA_SEED_PASSPHRASE".into()
+}
+
+/// Reads a secret from the environment variable or prompts for it if it isn't set
+pub fn read_secret(env: &str, prompt: &str) -> anyhow::Result<String> {
+    match std::env::var(env) {
+        Ok(secret) => Ok(secret),
+        Err(_) => Ok(rpassword::prompt_password(prompt)?),
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq)]
@@ -59,6 +141,32 @@ impl Default for DbConfig {
     }
 }
 
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum GroupKind {
+    #[default]
+    #[serde(rename = "secp256k1")]
+    Secp256k1,
+    #[serde(rename = "ristretto255")]
+    Ristretto255,
+}
+
+/// Evaluates `$body` with `$C` as the [`olivia_core::Group`] that `$group` refers to
+#[macro_export]
+macro_rules! with_group {
+    ($group:expr, $C:ident => $body:expr) => {
+        match $group {
+            $crate::config::GroupKind::Secp256k1 => {
+                type $C = olivia_secp256k1::Secp256k1;
+                $body
+            }
+            $crate::config::GroupKind::Ristretto255 => {
+                type $C = olivia_ristretto::Ristretto;
+                $body
+            }
+        }
+    };
+}
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "kebab-case", tag = "type", deny_unknown_fields)]
 pub enum EventSource {
@@ -106,8 +214,42 @@ pub enum OutcomeSource {
         ends_with: Path,
         event_kind: Option<EventKind>,
     },
+    #[serde(rename_all = "kebab-case")]
+    /// Answer `false` to deadline events once their deadline has passed. It can't be mounted
+    /// over or under a `bitcoind` source which attests to its own deadline events.
+    Deadline {
+        #[serde(default)]
+        ends_with: Path,
+    },
     /// Get outcomes from redis
     Redis(RedisConfig),
+    /// Get outcomes about blocks from bitcoind
+    Bitcoind(BitcoindConfig),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+#[serde(deny_unknown_fields)]
+pub struct BitcoindConfig {
+    /// The JSON-RPC url e.g. http://localhost:8332
+    pub url: String,
+    pub user: Option<String>,
+    #[serde(skip_serializing)]
+    pub password: Option<String>,
+    /// How many blocks deep a block must be before events about it are attested to
+    #[serde(default = "default_confirmations")]
+    pub confirmations: u32,
+    /// How many seconds to wait between checking for new blocks
+    #[serde(default = "default_poll_interval")]
+    pub poll_interval: u64,
+}
+
+fn default_confirmations() -> u32 {
+    6
+}
+
+fn default_poll_interval() -> u64 {
+    30
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -124,6 +266,8 @@ pub struct PredicateConfig {
     #[serde(rename = "type")]
     kind: PredicateKind,
     filter: OutcomeFilter,
+    /// How far past the filtered value a range predicate ends
+    width: Option<u64>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -132,6 +276,14 @@ pub enum PredicateKind {
     Eq,
     #[serde(rename = "_")]
     Gt,
+    #[serde(rename = "_lt")]
+    Lt,
+    #[serde(rename = "_ge")]
+    Ge,
+    #[serde(rename = "_le")]
+    Le,
+    #[serde(rename = "..")]
+    Range,
 }
 
 #[derive(Deserialize, Debug, Clone)]
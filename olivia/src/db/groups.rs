@@ -0,0 +1,117 @@
+use std::{cmp::Reverse, collections::HashSet, sync::Arc};
+
+use crate::db::*;
+
+/// Reads the events of every group's database as one so the sources keep producing events and
+/// outcomes until every group has them.
+#[derive(Clone)]
+pub struct GroupsDb {
+    groups: Vec<Arc<dyn DbReadEvent>>,
+}
+
+impl GroupsDb {
+    pub fn new(groups: Vec<Arc<dyn DbReadEvent>>) -> Self {
+        Self { groups }
+    }
+}
+
+#[async_trait]
+impl DbReadEvent for GroupsDb {
+    async fn get_node(&self, path: PathRef<'_>) -> anyhow::Result<Option<GetPath>> {
+        // nodes are set in every group at the same time
+        match self.groups.first() {
+            Some(group) => group.get_node(path).await,
+            None => Ok(None),
+        }
+    }
+
+    /// The earliest event is the earliest one from any group. The latest event is the latest one
+    /// in the group that is furthest behind and there isn't one if any group doesn't have one.
+    async fn query_event(&self, query: EventQuery<'_, '_>) -> anyhow::Result<Option<Event>> {
+        let mut events = vec![];
+        for group in &self.groups {
+            match group.query_event(query.clone()).await? {
+                Some(event) => events.push(event),
+                None if matches!(query.order, Order::Latest) => return Ok(None),
+                None => {}
+            }
+        }
+        Ok(events
+            .into_iter()
+            .min_by_key(|event| event.expected_outcome_time))
+    }
+
+    async fn query_events(&self, query: EventQuery<'_, '_>) -> anyhow::Result<Vec<Event>> {
+        let mut seen = HashSet::new();
+        let mut events = vec![];
+        for group in &self.groups {
+            for event in group.query_events(query.clone()).await? {
+                if seen.insert(event.id.clone()) {
+                    events.push(event);
+                }
+            }
+        }
+        match query.order {
+            Order::Earliest => events.sort_by_key(|event| event.expected_outcome_time),
+            Order::Latest => events.sort_by_key(|event| Reverse(event.expected_outcome_time)),
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::in_memory::InMemory;
+    use olivia_core::{chrono::NaiveDateTime, AnnouncedEvent, Group};
+    use olivia_ristretto::Ristretto;
+    use olivia_secp256k1::Secp256k1;
+
+    fn event(minute: u32) -> Event {
+        Event::occur_event_from_dt(NaiveDateTime::from_timestamp(minute as i64 * 60, 0))
+    }
+
+    async fn insert<C: Group>(db: &InMemory<C>, event: Event, attested: bool) {
+        let announced = match attested {
+            true => AnnouncedEvent::test_attested_instance(event),
+            false => AnnouncedEvent::test_unattested_instance(event),
+        };
+        db.insert_event(announced).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reads_what_any_group_is_missing() {
+        let secp256k1 = InMemory::<Secp256k1>::default();
+        let ristretto = InMemory::<Ristretto>::default();
+        let db = GroupsDb::new(vec![
+            Arc::new(secp256k1.clone()),
+            Arc::new(ristretto.clone()),
+        ]);
+        let latest = EventQuery {
+            order: Order::Latest,
+            ..Default::default()
+        };
+        let unattested = EventQuery {
+            attested: Some(false),
+            ..Default::default()
+        };
+
+        insert(&secp256k1, event(1), true).await;
+        insert(&secp256k1, event(2), false).await;
+        assert_eq!(db.query_event(latest.clone()).await.unwrap(), None);
+
+        insert(&ristretto, event(1), false).await;
+        assert_eq!(
+            db.query_event(latest.clone()).await.unwrap(),
+            Some(event(1))
+        );
+        assert_eq!(
+            db.query_event(unattested.clone()).await.unwrap(),
+            Some(event(1))
+        );
+        assert_eq!(
+            db.query_events(unattested).await.unwrap(),
+            vec![event(1), event(2)]
+        );
+    }
+}
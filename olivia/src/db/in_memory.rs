@@ -3,7 +3,7 @@ use anyhow::anyhow;
 use async_trait::async_trait;
 use olivia_core::{
     chrono::NaiveDate, AnnouncedEvent, Attestation, Child, ChildDesc, Event, EventId, Group,
-    OracleKeys, Path, PrefixPath,
+    KeyEpoch, OracleKeys, Path, PrefixPath,
 };
 use std::{
     cmp::Reverse,
@@ -14,17 +14,20 @@ use std::{
 
 #[derive(Clone)]
 pub struct InMemory<C: Group> {
-    public_keys: Arc<RwLock<Option<OracleKeys<C>>>>,
+    key_epochs: Arc<RwLock<Vec<KeyEpoch<C>>>>,
     inner: Arc<RwLock<HashMap<EventId, AnnouncedEvent<C>>>>,
     node_kinds: Arc<RwLock<HashMap<Path, NodeKind>>>,
+    /// The compound events each operand is part of
+    compounds: Arc<RwLock<HashMap<EventId, Vec<EventId>>>>,
 }
 
 impl<C: Group> Default for InMemory<C> {
     fn default() -> Self {
         Self {
-            public_keys: Arc::new(RwLock::new(None)),
+            key_epochs: Arc::new(RwLock::new(vec![])),
             inner: Arc::new(RwLock::new(HashMap::default())),
             node_kinds: Arc::new(RwLock::new(HashMap::default())),
+            compounds: Arc::new(RwLock::new(HashMap::default())),
         }
     }
 }
@@ -40,7 +43,30 @@ impl<C: Group> DbReadOracle<C> for InMemory<C> {
     }
 
     async fn get_public_keys(&self) -> Result<Option<OracleKeys<C>>, Error> {
-        Ok(self.public_keys.read().unwrap().as_ref().map(Clone::clone))
+        Ok(self
+            .key_epochs
+            .read()
+            .unwrap()
+            .last()
+            .map(|key_epoch| key_epoch.keys.clone()))
+    }
+
+    async fn get_key_epochs(&self) -> Result<Vec<KeyEpoch<C>>, Error> {
+        Ok(self.key_epochs.read().unwrap().clone())
+    }
+
+    async fn get_unattested_compounds(&self, operand: &EventId) -> Result<Vec<EventId>, Error> {
+        let db = &*self.inner.read().unwrap();
+        Ok(self
+            .compounds
+            .read()
+            .unwrap()
+            .get(operand)
+            .into_iter()
+            .flatten()
+            .filter(|compound| db[*compound].attestation.is_none())
+            .cloned()
+            .collect())
     }
 }
 
@@ -219,6 +245,12 @@ impl<C: Group> DbWrite<C> for InMemory<C> {
                 return Err(anyhow!("{} already exists", observed_event.event.id))
             }
             Entry::Vacant(v) => {
+                if let EventKind::Compound { operands, .. } = v.key().event_kind() {
+                    let compounds = &mut *self.compounds.write().unwrap();
+                    for operand in operands {
+                        compounds.entry(operand).or_default().push(v.key().clone());
+                    }
+                }
                 v.insert(observed_event);
             }
         }
@@ -242,8 +274,18 @@ impl<C: Group> DbWrite<C> for InMemory<C> {
         }
     }
 
-    async fn set_public_keys(&self, public_keys: OracleKeys<C>) -> Result<(), Error> {
-        *self.public_keys.write().unwrap() = Some(public_keys);
+    async fn add_key_epoch(&self, key_epoch: KeyEpoch<C>) -> Result<(), Error> {
+        let key_epochs = &mut *self.key_epochs.write().unwrap();
+        if key_epochs
+            .iter()
+            .any(|existing| existing.epoch == key_epoch.epoch)
+        {
+            return Err(anyhow!("key epoch {} already exists", key_epoch.epoch));
+        }
+        if let Some(current) = key_epochs.last_mut() {
+            current.valid_until = Some(key_epoch.valid_from);
+        }
+        key_epochs.push(key_epoch);
         Ok(())
     }
 
@@ -315,5 +357,18 @@ mod test {
         let db = Arc::<InMemory<olivia_secp256k1::Secp256k1>>::default();
         crate::oracle::test::test_oracle_event_lifecycle(db.clone()).await;
         crate::oracle::test::test_price_oracle_event_lifecycle(db.clone()).await;
+        crate::oracle::test::test_compound_oracle_event_lifecycle(db.clone()).await;
+        crate::oracle::test::test_overflow_policies(db.clone()).await;
+        crate::oracle::test::test_key_rotation(db.clone()).await;
+        crate::oracle::test::test_key_namespaces(db.clone()).await;
+    }
+
+    #[tokio::test]
+    async fn test_against_ristretto_oracle() {
+        let db = Arc::<InMemory<olivia_ristretto::Ristretto>>::default();
+        crate::oracle::test::test_oracle_event_lifecycle(db.clone()).await;
+        crate::oracle::test::test_price_oracle_event_lifecycle(db.clone()).await;
+        crate::oracle::test::test_compound_oracle_event_lifecycle(db.clone()).await;
+        crate::oracle::test::test_key_rotation(db.clone()).await;
     }
 }
@@ -1,11 +1,13 @@
 use olivia_core::{
-    AnnouncedEvent, Attestation, Event, EventId, EventKind, GetPath, Group, Node, NodeKind,
-    OracleKeys, PathRef,
+    AnnouncedEvent, Attestation, Event, EventId, EventKind, GetPath, Group, KeyEpoch, Node,
+    NodeKind, OracleKeys, PathRef,
 };
+mod groups;
 pub mod in_memory;
 pub mod postgres;
 mod prefixed;
 use async_trait::async_trait;
+pub use groups::*;
 pub use prefixed::*;
 
 #[cfg(test)]
@@ -16,7 +18,12 @@ pub type Error = anyhow::Error;
 #[async_trait]
 pub trait DbReadOracle<C: Group>: Send + Sync + DbReadEvent {
     async fn get_announced_event(&self, id: &EventId) -> anyhow::Result<Option<AnnouncedEvent<C>>>;
+    /// The keys of the current key epoch
     async fn get_public_keys(&self) -> Result<Option<OracleKeys<C>>, Error>;
+    /// All key epochs ordered from first to current
+    async fn get_key_epochs(&self) -> Result<Vec<KeyEpoch<C>>, Error>;
+    /// The unattested compound events that have `operand` as one of their operands
+    async fn get_unattested_compounds(&self, operand: &EventId) -> Result<Vec<EventId>, Error>;
 }
 
 #[async_trait]
@@ -36,7 +43,8 @@ pub trait DbWrite<C: Group>: Send + Sync {
         outcome: Attestation<C>,
     ) -> Result<(), Error>;
 
-    async fn set_public_keys(&self, public_key: OracleKeys<C>) -> Result<(), Error>;
+    /// Makes `key_epoch` the current key epoch, ending the previous one at its `valid_from`.
+    async fn add_key_epoch(&self, key_epoch: KeyEpoch<C>) -> Result<(), Error>;
 }
 
 pub trait Db<C: Group>:
@@ -1,10 +1,11 @@
 use super::NodeKind;
 use crate::db::*;
+use anyhow::anyhow;
 use async_trait::async_trait;
 use olivia_core::Path;
 use olivia_core::{
     attest, chrono::NaiveDate, AnnouncedEvent, Attestation, AttestationSchemes, Child, ChildDesc,
-    Event, EventId, Group, OracleKeys, PathRef, PrefixPath, RawAnnouncement, RawOracleEvent,
+    Event, EventId, Group, KeyEpoch, PathRef, PrefixPath, RawAnnouncement, RawOracleEvent,
 };
 use std::{
     collections::{BTreeMap, HashSet},
@@ -103,6 +104,17 @@ pub struct Version {
     version: u32,
 }
 
+/// The schema changes made since `init.sql`. Migration `i` takes the database from version `i` to
+/// version `i + 1`. `init.sql` is the version 0 schema that deployed databases were created with so
+/// it must never be edited. Schema changes go here instead.
+const MIGRATIONS: &[&str] = &[
+    include_str!("postgres/migrations/1_tlv_announcement.sql"),
+    include_str!("postgres/migrations/2_dlc_v0_attestation.sql"),
+    include_str!("postgres/migrations/3_compound_operand.sql"),
+    include_str!("postgres/migrations/4_attestation_overflow.sql"),
+    include_str!("postgres/migrations/5_key_epoch.sql"),
+];
+
 impl PgBackendWrite {
     pub async fn connect(database_url: &str) -> anyhow::Result<Self> {
         let (client, connection) = tokio_postgres::connect(database_url, NoTls).await?;
@@ -135,7 +147,46 @@ impl PgBackendWrite {
 
     pub async fn setup(&self) -> anyhow::Result<()> {
         let sql = include_str!("postgres/init.sql");
-        Ok(self.client.read().await.batch_execute(sql).await?)
+        self.client.read().await.batch_execute(sql).await?;
+        self.migrate().await?;
+        Ok(())
+    }
+
+    /// Brings the schema up to date returning the version it was at before
+    pub async fn migrate(&self) -> anyhow::Result<u32> {
+        let mut client = self.client.write().await;
+        let tx = client.transaction().await?;
+        // locking the row stops two processes from migrating at the same time
+        let row = tx
+            .query_one(
+                r#"SELECT value FROM meta WHERE key = 'version' FOR UPDATE"#,
+                &[],
+            )
+            .await?;
+        let Version { version } = serde_json::from_value(row.get::<_, serde_json::Value>("value"))?;
+
+        if version as usize > MIGRATIONS.len() {
+            return Err(anyhow!(
+                "database schema version {} is newer than this olivia ({})",
+                version,
+                MIGRATIONS.len()
+            ));
+        }
+
+        for migration in &MIGRATIONS[version as usize..] {
+            tx.batch_execute(migration).await?;
+        }
+
+        let latest = serde_json::to_value(Version {
+            version: MIGRATIONS.len() as u32,
+        })?;
+        tx.execute(
+            "UPDATE meta SET value = $1 WHERE key = 'version'",
+            &[&latest],
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(version)
     }
 }
 
@@ -148,10 +199,15 @@ impl<C: Group> crate::db::DbReadOracle<C> for tokio_postgres::Client {
                       expected_outcome_time,
                       (ann).oracle_event,
                       (ann).signature,
+                      (tlv_ann).oracle_event AS tlv_oracle_event,
+                      (tlv_ann).signature AS tlv_signature,
                       (att).outcome,
                       (att).olivia_v1_scalars,
                       (att).ecdsa_v1_signature,
-                      (att).time
+                      (att).dlc_v0_signatures,
+                      (att).time,
+                      (att).overflow,
+                      key_epoch
                FROM event
                  WHERE event.id = $1
             "#,
@@ -162,6 +218,10 @@ impl<C: Group> crate::db::DbReadOracle<C> for tokio_postgres::Client {
         match row {
             None => return Ok(None),
             Some(row) => {
+                let overflow = row
+                    .get::<_, Option<_>>("overflow")
+                    .map(serde_json::from_value)
+                    .transpose()?;
                 let attestation = row.try_get("outcome").ok().map(|outcome| Attestation {
                     outcome,
                     schemes: AttestationSchemes {
@@ -171,8 +231,12 @@ impl<C: Group> crate::db::DbReadOracle<C> for tokio_postgres::Client {
                         ecdsa_v1: row
                             .get::<_, Option<_>>("ecdsa_v1_signature")
                             .map(|signature| attest::EcdsaV1 { signature }),
+                        dlc_v0: row
+                            .get::<_, Option<_>>("dlc_v0_signatures")
+                            .map(|signatures| attest::DlcV0 { signatures }),
                     },
                     time: row.get("time"),
+                    overflow,
                 });
                 Ok(Some(AnnouncedEvent {
                     event: Event {
@@ -183,7 +247,14 @@ impl<C: Group> crate::db::DbReadOracle<C> for tokio_postgres::Client {
                         oracle_event: RawOracleEvent::from_json_bytes(row.get("oracle_event")),
                         signature: row.get("signature"),
                     },
+                    tlv_announcement: row.get::<_, Option<_>>("tlv_oracle_event").map(
+                        |oracle_event| RawAnnouncement {
+                            oracle_event: RawOracleEvent::from_tlv_bytes(oracle_event),
+                            signature: row.get("tlv_signature"),
+                        },
+                    ),
                     attestation,
+                    key_epoch: row.get::<_, i32>("key_epoch") as u32,
                 }))
             }
         }
@@ -191,13 +262,49 @@ impl<C: Group> crate::db::DbReadOracle<C> for tokio_postgres::Client {
 
     async fn get_public_keys(&self) -> Result<Option<olivia_core::OracleKeys<C>>, Error> {
         let row = self
-            .query_opt(r#"SELECT value FROM meta WHERE key = 'public_keys'"#, &[])
+            .query_opt(
+                r#"SELECT keys FROM key_epoch ORDER BY epoch DESC LIMIT 1"#,
+                &[],
+            )
             .await?;
 
         Ok(row
-            .map(|row| serde_json::from_value(row.get("value")))
+            .map(|row| serde_json::from_value(row.get("keys")))
             .transpose()?)
     }
+
+    async fn get_key_epochs(&self) -> Result<Vec<KeyEpoch<C>>, Error> {
+        let rows = self
+            .query(
+                r#"SELECT epoch, keys, valid_from, valid_until FROM key_epoch ORDER BY epoch ASC"#,
+                &[],
+            )
+            .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(KeyEpoch {
+                    epoch: row.get::<_, i32>("epoch") as u32,
+                    valid_from: row.get("valid_from"),
+                    valid_until: row.get("valid_until"),
+                    keys: serde_json::from_value(row.get("keys"))?,
+                })
+            })
+            .collect()
+    }
+
+    async fn get_unattested_compounds(&self, operand: &EventId) -> Result<Vec<EventId>, Error> {
+        let rows = self
+            .query(
+                r#"SELECT compound FROM compound_operand
+                   JOIN event ON event.id = compound
+                   WHERE operand = $1 AND (att).outcome IS NULL"#,
+                &[&operand.as_str()],
+            )
+            .await?;
+
+        Ok(rows.into_iter().map(|row| row.get("compound")).collect())
+    }
 }
 
 #[async_trait]
@@ -456,6 +563,14 @@ impl<C: Group> crate::db::DbReadOracle<C> for PgBackendWrite {
     async fn get_public_keys(&self) -> Result<Option<olivia_core::OracleKeys<C>>, Error> {
         self.client.read().await.get_public_keys().await
     }
+
+    async fn get_key_epochs(&self) -> Result<Vec<KeyEpoch<C>>, Error> {
+        self.client.read().await.get_key_epochs().await
+    }
+
+    async fn get_unattested_compounds(&self, operand: &EventId) -> Result<Vec<EventId>, Error> {
+        DbReadOracle::<C>::get_unattested_compounds(&*self.client.read().await, operand).await
+    }
 }
 
 #[async_trait]
@@ -525,17 +640,30 @@ impl<C: Group> crate::db::DbWrite<C> for PgBackendWrite {
         self.set_node_parents(&tx, node).await?;
 
         tx.execute(
-            "INSERT INTO event (id, expected_outcome_time, ann, path) VALUES ($1,$2,ROW($3,$4), $5)",
+            "INSERT INTO event (id, expected_outcome_time, ann, tlv_ann, path, key_epoch) VALUES ($1,$2,ROW($3,$4),ROW($5,$6),$7,$8)",
             &[
                 &event.event.id.as_str(),
                 &event.event.expected_outcome_time,
                 &event.announcement.oracle_event.as_bytes(),
                 &event.announcement.signature,
-                &Ltree::from(event.event.id.path())
+                &event.tlv_announcement.as_ref().map(|ann| ann.oracle_event.as_bytes()),
+                &event.tlv_announcement.as_ref().map(|ann| &ann.signature),
+                &Ltree::from(event.event.id.path()),
+                &(event.key_epoch as i32)
             ],
         )
         .await?;
 
+        if let EventKind::Compound { operands, .. } = event.event.id.event_kind() {
+            for operand in operands {
+                tx.execute(
+                    "INSERT INTO compound_operand (operand, compound) VALUES ($1, $2)",
+                    &[&operand.as_str(), &event.event.id.as_str()],
+                )
+                .await?;
+            }
+        }
+
         if let Some(attestation) = event.attestation {
             _complete_event(&event.event.id, attestation, &mut tx).await?;
         }
@@ -552,17 +680,26 @@ impl<C: Group> crate::db::DbWrite<C> for PgBackendWrite {
         Ok(())
     }
 
-    async fn set_public_keys(&self, public_keys: OracleKeys<C>) -> Result<(), Error> {
-        let value = serde_json::to_value(public_keys).unwrap();
-        let key = "public_keys";
-        self.client
-            .read()
-            .await
-            .execute(
-                "INSERT INTO meta (key,value) VALUES ($1, $2)",
-                &[&key, &value],
-            )
-            .await?;
+    async fn add_key_epoch(&self, key_epoch: KeyEpoch<C>) -> Result<(), Error> {
+        let keys = serde_json::to_value(&key_epoch.keys).unwrap();
+        let mut client = self.client.write().await;
+        let tx = client.transaction().await?;
+        tx.execute(
+            "UPDATE key_epoch SET valid_until = $1 WHERE valid_until IS NULL",
+            &[&key_epoch.valid_from],
+        )
+        .await?;
+        tx.execute(
+            "INSERT INTO key_epoch (epoch, keys, valid_from, valid_until) VALUES ($1, $2, $3, $4)",
+            &[
+                &(key_epoch.epoch as i32),
+                &keys,
+                &key_epoch.valid_from,
+                &key_epoch.valid_until,
+            ],
+        )
+        .await?;
+        tx.commit().await?;
         Ok(())
     }
 
@@ -589,15 +726,18 @@ async fn _complete_event<Client: tokio_postgres::GenericClient, C: Group>(
 ) -> Result<(), tokio_postgres::Error> {
     let Attestation {
         outcome,
-        schemes: AttestationSchemes {
-            olivia_v1,
-            ecdsa_v1,
-        },
+        schemes:
+            AttestationSchemes {
+                olivia_v1,
+                ecdsa_v1,
+                dlc_v0,
+            },
         time,
+        overflow,
     } = attestation;
     client.execute(
-        "UPDATE event SET att.outcome = $2, att.time = $3, att.olivia_v1_scalars= $4, att.ecdsa_v1_signature = $5 WHERE id = $1",
-        &[&event_id.as_str(), &outcome, &time, &olivia_v1.map(|x| x.scalars), &ecdsa_v1.map(|x| x.signature)],
+        "UPDATE event SET att.outcome = $2, att.time = $3, att.olivia_v1_scalars= $4, att.ecdsa_v1_signature = $5, att.overflow = $6, att.dlc_v0_signatures = $7 WHERE id = $1",
+        &[&event_id.as_str(), &outcome, &time, &olivia_v1.map(|x| x.scalars), &ecdsa_v1.map(|x| x.signature), &overflow.map(|x| serde_json::to_value(x).unwrap()), &dlc_v0.map(|x| x.signatures)],
     )
           .await?;
     Ok(())
@@ -735,6 +875,14 @@ mod test {
             db.clone(),
         )
         .await;
+        crate::oracle::test::test_compound_oracle_event_lifecycle::<olivia_secp256k1::Secp256k1>(
+            db.clone(),
+        )
+        .await;
+        crate::oracle::test::test_overflow_policies::<olivia_secp256k1::Secp256k1>(db.clone())
+            .await;
+        crate::oracle::test::test_key_rotation::<olivia_secp256k1::Secp256k1>(db.clone()).await;
+        crate::oracle::test::test_key_namespaces::<olivia_secp256k1::Secp256k1>(db.clone()).await;
     }
 
     #[tokio::test]
@@ -744,6 +892,110 @@ mod test {
         let db = PgBackendWrite::connect(&url).await.unwrap();
         db.setup().await.unwrap();
         let version = db.version().await.unwrap();
-        assert_eq!(version.version, 0);
+        assert_eq!(version.version as usize, MIGRATIONS.len());
+    }
+
+    #[tokio::test]
+    async fn migrate_from_first_version() {
+        use std::str::FromStr;
+        let docker = clients::Cli::default();
+        let (url, _container) = new_backend!(docker);
+        let db = PgBackendWrite::connect(&url).await.unwrap();
+        // keys written before there were key epochs
+        let old_keys = olivia_secp256k1::Secp256k1::test_oracle_keys();
+        // events written before any migrations existed
+        let old_events = [
+            "/test/postgres/old.occur",
+            "/test/postgres/old-both.and(~test~postgres~old.occur;~test~postgres~other.occur)",
+        ]
+        .iter()
+        .map(
+            |id| olivia_core::AnnouncedEvent::<olivia_secp256k1::Secp256k1> {
+                tlv_announcement: None,
+                ..olivia_core::AnnouncedEvent::test_unattested_instance(
+                    EventId::from_str(id).unwrap().into(),
+                )
+            },
+        )
+        .collect::<Vec<_>>();
+        {
+            let client = db.client.read().await;
+            client
+                .batch_execute(include_str!("postgres/init.sql"))
+                .await
+                .unwrap();
+            client
+                .execute(
+                    "INSERT INTO meta (key,value) VALUES ('public_keys', $1)",
+                    &[&serde_json::to_value(&old_keys).unwrap()],
+                )
+                .await
+                .unwrap();
+            for old_event in &old_events {
+                client
+                    .execute(
+                        "INSERT INTO event (id, ann, path) VALUES ($1, ROW($2,$3), $4)",
+                        &[
+                            &old_event.event.id.as_str(),
+                            &old_event.announcement.oracle_event.as_bytes(),
+                            &old_event.announcement.signature,
+                            &Ltree::from(old_event.event.id.path()),
+                        ],
+                    )
+                    .await
+                    .unwrap();
+            }
+        }
+        assert_eq!(db.migrate().await.unwrap(), 0);
+        assert_eq!(db.migrate().await.unwrap() as usize, MIGRATIONS.len());
+
+        let db: Arc<dyn crate::db::Db<olivia_secp256k1::Secp256k1>> = Arc::new(db);
+        for old_event in &old_events {
+            assert_eq!(
+                db.get_announced_event(&old_event.event.id).await.unwrap(),
+                Some(old_event.clone())
+            );
+        }
+        // the keys from before there were epochs are epoch 0 and the old events belong to it
+        let key_epochs = db.get_key_epochs().await.unwrap();
+        assert_eq!(key_epochs.len(), 1);
+        assert_eq!(key_epochs[0].epoch, 0);
+        assert_eq!(key_epochs[0].valid_until, None);
+        assert_eq!(key_epochs[0].keys, old_keys);
+        assert_eq!(db.get_public_keys().await.unwrap(), Some(old_keys));
+        // the operands of compound events that were already there are indexed
+        assert_eq!(
+            db.get_unattested_compounds(&old_events[0].event.id)
+                .await
+                .unwrap(),
+            vec![old_events[1].event.id.clone()]
+        );
+        // old events can be attested to with the new attestation attributes
+        let attestation = Attestation {
+            overflow: Some(olivia_core::OverflowPolicy::Clamp),
+            ..Attestation::test_instance(&old_events[0].event.id)
+        };
+        db.complete_event(&old_events[0].event.id, attestation.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_announced_event(&old_events[0].event.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .attestation,
+            Some(attestation)
+        );
+        let event = olivia_core::AnnouncedEvent::test_unattested_instance(
+            EventId::from_str("/test/postgres/migrated.occur")
+                .unwrap()
+                .into(),
+        );
+        assert!(event.tlv_announcement.is_some());
+        db.insert_event(event.clone()).await.unwrap();
+        assert_eq!(
+            db.get_announced_event(&event.event.id).await.unwrap(),
+            Some(event)
+        );
     }
 }
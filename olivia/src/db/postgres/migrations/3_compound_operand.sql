@@ -0,0 +1,11 @@
+-- So we can find the compound events waiting on an operand without looking at every event
+CREATE TABLE compound_operand (
+       operand text NOT NULL,
+       compound text NOT NULL REFERENCES event (id),
+       PRIMARY KEY (operand, compound)
+);
+
+-- Operands are in the compound event's id with '~' instead of '/' e.g. and(~foo.occur;~bar.occur)
+INSERT INTO compound_operand (operand, compound)
+SELECT replace(operand, '~', '/'), id
+FROM event, unnest(string_to_array(substring(id FROM '\.(?:and|or)\((.*)\)$'), ';')) AS operand;
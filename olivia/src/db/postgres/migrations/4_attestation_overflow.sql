@@ -0,0 +1 @@
+ALTER TYPE attestation ADD ATTRIBUTE overflow jsonb;
@@ -0,0 +1,15 @@
+CREATE TABLE key_epoch (
+       epoch integer NOT NULL PRIMARY KEY,
+       keys jsonb NOT NULL,
+       valid_from timestamp NOT NULL,
+       valid_until timestamp
+);
+
+ALTER TABLE event ADD COLUMN key_epoch integer NOT NULL DEFAULT 0;
+
+-- The keys from before there were epochs become epoch 0. When they were first used isn't recorded
+-- so they're valid from when the database was migrated.
+INSERT INTO key_epoch (epoch, keys, valid_from)
+SELECT 0, value, now() AT TIME ZONE 'utc' FROM meta WHERE key = 'public_keys';
+
+DELETE FROM meta WHERE key = 'public_keys';
@@ -24,7 +24,7 @@ macro_rules! run_node_db_tests {
         #[allow(redundant_semicolons, unused_imports, unused_variables)]
         mod node_db_test {
             use super::*;
-            use olivia_core::{path, Child, ChildDesc, EventKind, Group, Path, PathRef, RangeKind};
+            use olivia_core::{path, Child, ChildDesc, EventKind, Group, KeyEpoch, Path, PathRef, RangeKind, chrono::NaiveDateTime};
             use std::str::FromStr;
             use $crate::assert_children_eq;
 
@@ -166,15 +166,61 @@ macro_rules! run_node_db_tests {
                 assert_children_eq!($db, path!("/test/db/RED_BLUE"), children => [], events =>[first.event_kind(), second.event_kind()]);
             }
 
+            #[tokio::test]
+            async fn test_unattested_compounds() {
+                $($init)*;
+                let operand = EventId::from_str("/test/db/foo.occur").unwrap();
+                let compound = EventId::from_str("/test/db/foo-and-bar.and(~test~db~foo.occur;~test~db~bar.occur)").unwrap();
+                let attested = EventId::from_str("/test/db/foo-or-bar.or(~test~db~foo.occur;~test~db~bar.occur)").unwrap();
+                let other = EventId::from_str("/test/db/bar-and-baz.and(~test~db~bar.occur;~test~db~baz.occur)").unwrap();
+
+                $db.insert_event(AnnouncedEvent::test_unattested_instance(compound.clone().into())).await.unwrap();
+                $db.insert_event(AnnouncedEvent::test_attested_instance(attested.clone().into())).await.unwrap();
+                $db.insert_event(AnnouncedEvent::test_unattested_instance(other.clone().into())).await.unwrap();
+
+                assert_eq!($db.get_unattested_compounds(&operand).await.unwrap(), vec![compound.clone()]);
+                assert!($db.get_unattested_compounds(&compound).await.unwrap().is_empty());
+            }
+
             #[tokio::test]
             async fn test_insert_and_get_public_keys() {
                 $($init)*;
                 let oracle_keys = <$curve>::test_oracle_keys();
-                $db.set_public_keys(oracle_keys.clone()).await.unwrap();
+                let valid_from = NaiveDateTime::from_str("2021-03-01T00:00:00").unwrap();
+                $db.add_key_epoch(KeyEpoch { epoch: 0, valid_from, valid_until: None, keys: oracle_keys.clone() }).await.unwrap();
                 let retrieved_keys = $db.get_public_keys().await.unwrap().unwrap();
                 assert_eq!(oracle_keys, retrieved_keys);
             }
 
+            #[tokio::test]
+            async fn test_key_epochs() {
+                $($init)*;
+                let first = KeyEpoch {
+                    epoch: 0,
+                    valid_from: NaiveDateTime::from_str("2021-03-01T00:00:00").unwrap(),
+                    valid_until: None,
+                    keys: <$curve>::test_oracle_keys(),
+                };
+                let second = KeyEpoch {
+                    epoch: 1,
+                    valid_from: NaiveDateTime::from_str("2021-04-01T00:00:00").unwrap(),
+                    valid_until: None,
+                    keys: $crate::keychain::KeyChain::<$curve>::for_epoch($crate::seed::Seed::new([42u8; 64]), 1, &[]).oracle_public_keys(),
+                };
+                $db.add_key_epoch(first.clone()).await.unwrap();
+                $db.add_key_epoch(second.clone()).await.unwrap();
+                assert!($db.add_key_epoch(second.clone()).await.is_err(), "epochs can't be added twice");
+
+                assert_eq!($db.get_public_keys().await.unwrap().unwrap(), second.keys);
+                assert_eq!(
+                    $db.get_key_epochs().await.unwrap(),
+                    vec![
+                        KeyEpoch { valid_until: Some(second.valid_from), ..first },
+                        second
+                    ]
+                );
+            }
+
             #[tokio::test]
             async fn test_set_node() {
                 $($init)*;
@@ -1,20 +1,31 @@
-use crate::seed::Seed;
+use crate::{
+    seed::Seed,
+    signing_ledger::{LedgerError, SigningLedger},
+};
 use blake2::digest::{Update, VariableOutput};
 use olivia_core::{
-    announce, AnnouncementSchemes, Event, EventId, Group, OracleKeys, Outcome, RawAnnouncement,
-    StampedOutcome,
+    announce, AnnouncementSchemes, Event, EventId, Group, OracleKeys, Outcome, Path, PathRef,
+    RawAnnouncement, StampedOutcome,
 };
-use std::borrow::Borrow;
+use std::{borrow::Borrow, collections::BTreeMap};
 
 pub struct KeyChain<C: Group> {
     announcement_keypair: C::KeyPair,
     olivia_v1_keypair: C::KeyPair,
     ecdsa_v1_keypair: C::KeyPair,
+    olivia_v1_namespaces: BTreeMap<Path, C::KeyPair>,
     event_seed: Seed,
+    dlc_v0_event_seed: Seed,
 }
 
 impl<C: Group> KeyChain<C> {
     pub fn new(seed: Seed) -> Self {
+        Self::with_namespaces(seed, &[])
+    }
+
+    /// Events under each of the `olivia_v1_namespaces` are attested to with their own olivia-v1
+    /// key.
+    pub fn with_namespaces(seed: Seed, olivia_v1_namespaces: &[Path]) -> Self {
         let seed = seed.child(C::name().as_bytes());
         let announcement_keypair = {
             let seed = seed.child(b"announcement-key");
@@ -32,25 +43,81 @@ impl<C: Group> KeyChain<C> {
             C::keypair_from_secret_bytes(hash.finalize_boxed().borrow())
         };
 
+        let olivia_v1_namespaces = olivia_v1_namespaces
+            .iter()
+            .map(|namespace| {
+                let seed = seed
+                    .child(b"olivia-v1-key")
+                    .child(namespace.as_str().as_bytes());
+                let hash = seed.to_blake2b_var(C::KEY_MATERIAL_LEN);
+                (
+                    namespace.clone(),
+                    C::keypair_from_secret_bytes(hash.finalize_boxed().borrow()),
+                )
+            })
+            .collect();
+
         Self {
             event_seed: seed.child(b"oracle-events"),
+            dlc_v0_event_seed: seed.child(b"dlc-v0-oracle-events"),
             announcement_keypair,
             olivia_v1_keypair,
             ecdsa_v1_keypair,
+            olivia_v1_namespaces,
         }
     }
 
+    /// The keychain for a key epoch. Epoch 0 uses the keys the oracle had before keys could be
+    /// rotated.
+    ///
+    /// Every epoch is derived from the same seed so rotating keys only protects against one
+    /// epoch's secret keys leaking. If the seed itself is compromised so are all past and future
+    /// epochs and the oracle has to start again with a new seed.
+    pub fn for_epoch(seed: Seed, epoch: u32, olivia_v1_namespaces: &[Path]) -> Self {
+        match epoch {
+            0 => Self::with_namespaces(seed, olivia_v1_namespaces),
+            epoch => Self::with_namespaces(
+                seed.child(format!("key-epoch-{}", epoch).as_bytes()),
+                olivia_v1_namespaces,
+            ),
+        }
+    }
+
+    fn olivia_v1_keypair_for(&self, path: PathRef<'_>) -> &C::KeyPair {
+        self.olivia_v1_namespaces
+            .iter()
+            .filter(|(namespace, _)| namespace.as_path_ref().is_parent_of(path))
+            .max_by_key(|(namespace, _)| namespace.as_str().len())
+            .map(|(_, keypair)| keypair)
+            .unwrap_or(&self.olivia_v1_keypair)
+    }
+
     pub fn oracle_public_keys(&self) -> OracleKeys<C> {
         OracleKeys {
             olivia_v1: Some(self.olivia_v1_keypair.clone().into()),
-            ecdsa_v1: Some(self.ecdsa_v1_keypair.clone().into()),
+            ecdsa_v1: C::ECDSA_V1.then(|| self.ecdsa_v1_keypair.clone().into()),
             announcement: self.announcement_keypair.clone().into(),
             group: C::default(),
+            olivia_v1_namespaces: self
+                .olivia_v1_namespaces
+                .iter()
+                .map(|(namespace, keypair)| (namespace.clone(), keypair.clone().into()))
+                .collect(),
         }
     }
 
     pub fn nonces_for_event(&self, event_id: &EventId) -> Vec<C::NonceKeyPair> {
-        let event_seed = self.event_seed.child(event_id.as_bytes());
+        Self::nonces_from_seed(&self.event_seed, event_id)
+    }
+
+    /// The nonces for DLC spec attestations. They come from a different seed than the olivia-v1
+    /// nonces since signing with a nonce under both schemes would reveal the key.
+    pub fn dlc_v0_nonces_for_event(&self, event_id: &EventId) -> Vec<C::NonceKeyPair> {
+        Self::nonces_from_seed(&self.dlc_v0_event_seed, event_id)
+    }
+
+    fn nonces_from_seed(seed: &Seed, event_id: &EventId) -> Vec<C::NonceKeyPair> {
+        let event_seed = seed.child(event_id.as_bytes());
         let n = event_id.event_kind().n_nonces();
         let hash = event_seed.to_blake2b_var(C::KEY_MATERIAL_LEN);
         (0..n)
@@ -62,16 +129,41 @@ impl<C: Group> KeyChain<C> {
             .collect()
     }
 
+    /// The nonce the ledger records outcomes under. All of an event's nonces come from the same
+    /// seed so the first one identifies them.
+    fn ledger_nonce(&self, event_id: &EventId) -> String {
+        self.nonces_for_event(event_id)
+            .into_iter()
+            .next()
+            .map(|nonce| Into::<C::PublicNonce>::into(nonce).to_string())
+            .unwrap_or_default()
+    }
+
+    fn record_in_ledger(
+        &self,
+        outcome: &Outcome,
+        ledger: &mut SigningLedger,
+    ) -> Result<(), LedgerError> {
+        ledger.record(
+            &outcome.id,
+            &self.ledger_nonce(&outcome.id),
+            &outcome.outcome_string(),
+        )
+    }
+
+    /// Reveals the attestation scalars for the outcome once it's recorded in the ledger.
     pub fn olivia_v1_scalars_for_event_outcome(
         &self,
         stamped: &StampedOutcome,
-    ) -> Vec<C::AttestScalar> {
+        ledger: &mut SigningLedger,
+    ) -> Result<Vec<C::AttestScalar>, LedgerError> {
+        self.record_in_ledger(&stamped.outcome, ledger)?;
         let event_id = &stamped.outcome.id;
         let event_seed = self.event_seed.child(event_id.as_bytes());
         let hash = event_seed.to_blake2b_var(C::KEY_MATERIAL_LEN);
-        let attest_keypair = &self.olivia_v1_keypair;
+        let attest_keypair = self.olivia_v1_keypair_for(event_id.path());
         hash.clone().finalize_boxed();
-        stamped
+        Ok(stamped
             .outcome
             .attestation_indexes()
             .iter()
@@ -92,27 +184,89 @@ impl<C: Group> KeyChain<C> {
                 ));
                 scalar
             })
-            .collect()
+            .collect())
     }
 
-    pub fn create_announcement(&self, event: Event) -> RawAnnouncement<C> {
+    /// Signs each of the outcome's attestation strings with its dlc-v0 nonce once the outcome is
+    /// recorded in the ledger.
+    pub fn dlc_v0_sign_event_outcome(
+        &self,
+        outcome: &Outcome,
+        ledger: &mut SigningLedger,
+    ) -> Result<Vec<C::Signature>, LedgerError> {
+        self.record_in_ledger(outcome, ledger)?;
+        let attest_keypair = self.olivia_v1_keypair_for(outcome.id.path());
+        Ok(self
+            .dlc_v0_nonces_for_event(&outcome.id)
+            .into_iter()
+            .zip(outcome.attestation_outcome_strings())
+            .map(|(nonce_keypair, outcome)| {
+                let signature =
+                    C::dlc_v0_sign(attest_keypair, nonce_keypair.clone(), outcome.as_bytes());
+                // Always verify the attestation before publishing it
+                assert!(C::dlc_v0_verify(
+                    &attest_keypair.clone().into(),
+                    &nonce_keypair.into(),
+                    outcome.as_bytes(),
+                    &signature
+                ));
+                signature
+            })
+            .collect())
+    }
+
+    fn olivia_v1_nonces(&self, event_id: &EventId) -> Option<announce::OliviaV1<C>> {
         let nonces = self
-            .nonces_for_event(&event.id)
+            .nonces_for_event(event_id)
             .into_iter()
             .map(|nonce_kp| nonce_kp.into())
             .collect::<Vec<_>>();
 
+        match nonces.is_empty() {
+            true => None,
+            false => Some(announce::OliviaV1 { nonces }),
+        }
+    }
+
+    pub fn create_announcement(&self, event: Event) -> RawAnnouncement<C> {
+        let schemes = AnnouncementSchemes {
+            olivia_v1: self.olivia_v1_nonces(&event.id),
+            ecdsa_v1: C::ECDSA_V1.then_some(announce::EcdsaV1 {}),
+            dlc_v0: None,
+        };
+        RawAnnouncement::create(event, &self.announcement_keypair, schemes)
+    }
+
+    /// Creates a DLC spec TLV announcement for the event if it can be represented as one.
+    ///
+    /// The DLC spec only has a single oracle key so this is signed with the event's olivia-v1 key
+    /// rather than the announcement key.
+    pub fn create_tlv_announcement(&self, event: Event) -> Option<RawAnnouncement<C>> {
+        let nonces = self
+            .dlc_v0_nonces_for_event(&event.id)
+            .into_iter()
+            .map(|nonce_kp| nonce_kp.into())
+            .collect::<Vec<_>>();
         let schemes = AnnouncementSchemes {
-            olivia_v1: match nonces.is_empty() {
+            dlc_v0: match nonces.is_empty() {
                 true => None,
-                false => Some(announce::OliviaV1 { nonces }),
+                false => Some(announce::DlcV0 { nonces }),
             },
-            ecdsa_v1: Some(announce::EcdsaV1 {}),
+            ..Default::default()
         };
-        RawAnnouncement::create(event, &self.announcement_keypair, schemes)
+        let olivia_v1_keypair = self.olivia_v1_keypair_for(event.id.path());
+        RawAnnouncement::create_tlv(event, olivia_v1_keypair, schemes)
     }
 
-    pub fn ecdsa_sign_outcome(&self, outcome: &Outcome) -> C::EcdsaSignature {
-        C::ecdsa_sign(&self.announcement_keypair, &outcome.attestation_string())
+    pub fn ecdsa_sign_outcome(
+        &self,
+        outcome: &Outcome,
+        ledger: &mut SigningLedger,
+    ) -> Result<C::EcdsaSignature, LedgerError> {
+        self.record_in_ledger(outcome, ledger)?;
+        Ok(C::ecdsa_sign(
+            &self.announcement_keypair,
+            &outcome.attestation_string(),
+        ))
     }
 }
@@ -4,6 +4,9 @@ pub mod db;
 pub mod oracle;
 mod oracle_loop;
 pub mod seed;
+pub mod seed_file;
+pub mod signer;
+pub mod signing_ledger;
 pub use crate::oracle::Oracle;
 
 pub mod cli;
@@ -14,8 +14,10 @@ impl OracleLog for slog::Logger {
                 Changed => info!(self, "changed"),
                 AlreadyExists => debug!(self, "ignored - already exists"),
                 AlreadyCompleted => debug!(self, "ignored - already completed"),
+                MissingDeadline => error!(self, "deadline event has no expected outcome time"),
                 DbReadErr(e) => crit!(self,"database read";"error" => format!("{}",e)),
                 DbWriteErr(e) => crit!(self,"database write"; "error" => format!("{}", e)),
+                SignerErr(e) => crit!(self, "signer"; "error" => format!("{}", e)),
             },
         }
     }
@@ -33,6 +35,13 @@ impl OracleLog for slog::Logger {
                 DbReadErr(e) => crit!(self, "database read"; "error" => format!("{}", e)),
                 DbWriteErr(e) => crit!(self, "database write"; "error" => format!("{}", e)),
                 AnnouncementWasBogus => crit!(self, "bogus announcement"),
+                OutOfRange { outcome } => {
+                    error!(self, "outcome out of range"; "outcome" => outcome)
+                }
+                SignerErr(e) => crit!(self, "signer"; "error" => format!("{}", e)),
+                UnknownKeyEpoch { key_epoch } => {
+                    crit!(self, "no keys for key epoch (restart the oracle after rotating keys)"; "key_epoch" => key_epoch)
+                }
             },
         }
     }
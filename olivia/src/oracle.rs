@@ -1,10 +1,13 @@
-use crate::{keychain::KeyChain, seed::Seed};
+use crate::{
+    seed::Seed,
+    signer::{KeyChainSigner, KeyId, Signer, SignerError},
+};
 use anyhow::anyhow;
 use olivia_core::{
-    attest, AnnouncedEvent, Attestation, AttestationSchemes, Event, Group, OracleKeys,
-    StampedOutcome,
+    attest, chrono, AnnouncedEvent, Attestation, AttestationSchemes, Event, EventKind, Group,
+    KeyEpoch, OracleKeys, Outcome, OverflowPolicy, Path, StampedOutcome,
 };
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 #[derive(thiserror::Error, Debug)]
 pub enum EventResult {
@@ -14,10 +17,14 @@ pub enum EventResult {
     AlreadyCompleted,
     #[error("event already exists but was updated")]
     Changed,
+    #[error("deadline events must have an expected outcome time")]
+    MissingDeadline,
     #[error("unable to read from database: {0}")]
     DbReadErr(crate::db::Error),
     #[error("unable to write to database: {0}")]
     DbWriteErr(crate::db::Error),
+    #[error("the signer refused to create the announcement: {0}")]
+    SignerErr(SignerError),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -34,35 +41,150 @@ pub enum OutcomeResult {
     DbWriteErr(crate::db::Error),
     #[error("the announcement for this event was no longer valid when read from database")]
     AnnouncementWasBogus,
+    #[error("the outcome '{outcome}' is out of the range the event can attest to")]
+    OutOfRange { outcome: String },
+    #[error("the event was announced in key epoch {key_epoch} which this oracle has no keys for")]
+    UnknownKeyEpoch { key_epoch: u32 },
+    #[error("the signer refused to sign the outcome: {0}")]
+    SignerErr(SignerError),
 }
 
 pub struct Oracle<C: Group> {
     db: Arc<dyn crate::db::Db<C>>,
-    keychain: KeyChain<C>,
+    signer: Arc<dyn Signer<C>>,
+    key_epochs: BTreeMap<u32, OracleKeys<C>>,
+    key_namespaces: Vec<Path>,
+    overflow_policy: OverflowPolicy,
 }
 
 impl<C: Group> Oracle<C> {
     pub async fn new(seed: Seed, db: Arc<dyn crate::db::Db<C>>) -> anyhow::Result<Self> {
-        let keychain = KeyChain::new(seed);
-        let public_keys = keychain.oracle_public_keys();
-        if let Some(db_pubkeys) = db.get_public_keys().await? {
-            if public_keys != db_pubkeys {
+        Self::new_with_key_namespaces(seed, db, vec![]).await
+    }
+
+    pub async fn new_with_key_namespaces(
+        seed: Seed,
+        db: Arc<dyn crate::db::Db<C>>,
+        key_namespaces: Vec<Path>,
+    ) -> anyhow::Result<Self> {
+        Self::new_with_signer(Arc::new(KeyChainSigner::new(seed)), db, key_namespaces).await
+    }
+
+    /// `key_namespaces` are the paths that get their own olivia-v1 key. They only apply to new
+    /// key epochs so existing epochs keep the namespaces they were created with.
+    pub async fn new_with_signer(
+        signer: Arc<dyn Signer<C>>,
+        db: Arc<dyn crate::db::Db<C>>,
+        mut key_namespaces: Vec<Path>,
+    ) -> anyhow::Result<Self> {
+        key_namespaces.sort();
+        key_namespaces.dedup();
+        let mut key_epochs = BTreeMap::new();
+        let db_key_epochs = db.get_key_epochs().await?;
+        if db_key_epochs.is_empty() {
+            let keys = signer
+                .public_keys(&KeyId {
+                    epoch: 0,
+                    olivia_v1_namespaces: key_namespaces.clone(),
+                })
+                .await?;
+            db.add_key_epoch(KeyEpoch {
+                epoch: 0,
+                valid_from: chrono::Utc::now().naive_utc(),
+                valid_until: None,
+                keys: keys.clone(),
+            })
+            .await?;
+            key_epochs.insert(0, keys);
+        }
+        for key_epoch in db_key_epochs {
+            let keys = signer
+                .public_keys(&KeyId::new(key_epoch.epoch, &key_epoch.keys))
+                .await?;
+            if keys != key_epoch.keys {
                 return Err(anyhow!(
-                    "public key derived from seed does not match database"
+                    "public key derived from seed does not match database for key epoch {}",
+                    key_epoch.epoch
                 ));
             }
-        } else {
-            db.set_public_keys(public_keys).await?
+            key_epochs.insert(key_epoch.epoch, keys);
         }
 
-        Ok(Self { db, keychain })
+        Ok(Self {
+            db,
+            signer,
+            key_epochs,
+            key_namespaces,
+            overflow_policy: OverflowPolicy::default(),
+        })
+    }
+
+    pub fn with_overflow_policy(self, overflow_policy: OverflowPolicy) -> Self {
+        Self {
+            overflow_policy,
+            ..self
+        }
+    }
+
+    /// The current key epoch and its keys
+    fn current_keys(&self) -> (u32, &OracleKeys<C>) {
+        let (epoch, keys) = self
+            .key_epochs
+            .iter()
+            .next_back()
+            .expect("oracle always has a key epoch");
+        (*epoch, keys)
+    }
+
+    pub fn key_epoch(&self) -> u32 {
+        self.current_keys().0
     }
 
     pub fn public_keys(&self) -> OracleKeys<C> {
-        self.keychain.oracle_public_keys()
+        self.current_keys().1.clone()
+    }
+
+    /// Whether the current key epoch has different namespaces to the ones the oracle was created
+    /// with (they take effect after the next key rotation).
+    pub fn key_namespaces_changed(&self) -> bool {
+        !self
+            .current_keys()
+            .1
+            .olivia_v1_namespaces
+            .keys()
+            .eq(self.key_namespaces.iter())
+    }
+
+    /// Starts a new key epoch with keys derived from the seed and the oracle's key namespaces.
+    /// Events announced before the rotation are still attested to with the keys of the epoch they
+    /// were announced in.
+    pub async fn rotate_keys(&mut self) -> anyhow::Result<KeyEpoch<C>> {
+        let epoch = self.key_epoch() + 1;
+        let keys = self
+            .signer
+            .public_keys(&KeyId {
+                epoch,
+                olivia_v1_namespaces: self.key_namespaces.clone(),
+            })
+            .await?;
+        let key_epoch = KeyEpoch {
+            epoch,
+            valid_from: chrono::Utc::now().naive_utc(),
+            valid_until: None,
+            keys: keys.clone(),
+        };
+        self.db.add_key_epoch(key_epoch.clone()).await?;
+        self.key_epochs.insert(epoch, keys);
+        Ok(key_epoch)
     }
 
     pub async fn add_event(&self, new_event: Event) -> Result<(), EventResult> {
+        // the deadline is when the event gets attested false if nothing else has attested it
+        if new_event.id.event_kind() == EventKind::Deadline
+            && new_event.expected_outcome_time.is_none()
+        {
+            return Err(EventResult::MissingDeadline);
+        }
         match self.db.get_announced_event(&new_event.id).await {
             Ok(Some(AnnouncedEvent {
                 attestation: Some(_),
@@ -73,12 +195,25 @@ impl<C: Group> Oracle<C> {
                 Err(EventResult::AlreadyExists)
             }
             Ok(None) => {
-                let announcement = self.keychain.create_announcement(new_event.clone());
+                let (key_epoch, keys) = self.current_keys();
+                let key_id = KeyId::new(key_epoch, keys);
+                let announcement = self
+                    .signer
+                    .create_announcement(&key_id, new_event.clone())
+                    .await
+                    .map_err(EventResult::SignerErr)?;
+                let tlv_announcement = self
+                    .signer
+                    .create_tlv_announcement(&key_id, new_event.clone())
+                    .await
+                    .map_err(EventResult::SignerErr)?;
                 self.db
                     .insert_event(AnnouncedEvent {
                         event: new_event,
                         announcement,
+                        tlv_announcement,
                         attestation: None,
+                        key_epoch,
                     })
                     .await
                     .map_err(EventResult::DbWriteErr)
@@ -88,6 +223,24 @@ impl<C: Group> Oracle<C> {
     }
 
     pub async fn complete_event(&self, stamped: StampedOutcome) -> Result<(), OutcomeResult> {
+        let (stamped, overflow) = match stamped.outcome.clamped() {
+            None => (stamped, None),
+            Some(clamped) => {
+                let outcome = match self.overflow_policy {
+                    OverflowPolicy::Reject => {
+                        return Err(OutcomeResult::OutOfRange {
+                            outcome: stamped.outcome.outcome_string(),
+                        })
+                    }
+                    OverflowPolicy::Clamp => clamped,
+                    OverflowPolicy::Void => Outcome::void(stamped.outcome.id),
+                };
+                (
+                    StampedOutcome { outcome, ..stamped },
+                    Some(self.overflow_policy),
+                )
+            }
+        };
         let existing = self.db.get_announced_event(&stamped.outcome.id).await;
         let outcome_val_str = stamped.outcome.outcome_string();
         match existing {
@@ -108,29 +261,64 @@ impl<C: Group> Oracle<C> {
             Ok(Some(AnnouncedEvent {
                 event,
                 announcement,
+                tlv_announcement,
+                key_epoch,
                 ..
             })) => {
-                if let Some(oracle_event) = announcement.verify_against_id(
-                    &stamped.outcome.id,
-                    &self.keychain.oracle_public_keys().announcement,
-                ) {
-                    let att_schemes =
-                        AttestationSchemes {
-                            olivia_v1: oracle_event.schemes.olivia_v1.as_ref().map(|_| {
-                                attest::OliviaV1 {
-                                    scalars: self
-                                        .keychain
-                                        .olivia_v1_scalars_for_event_outcome(&stamped),
-                                }
-                            }),
-                            ecdsa_v1: oracle_event.schemes.ecdsa_v1.as_ref().map(|_| {
-                                attest::EcdsaV1 {
-                                    signature: self.keychain.ecdsa_sign_outcome(&stamped.outcome),
-                                }
-                            }),
-                        };
-
-                    let attestation = Attestation::new(outcome_val_str, stamped.time, att_schemes);
+                let keys = self
+                    .key_epochs
+                    .get(&key_epoch)
+                    .ok_or(OutcomeResult::UnknownKeyEpoch { key_epoch })?;
+                let key_id = KeyId::new(key_epoch, keys);
+                if let Some(oracle_event) =
+                    announcement.verify_against_id(&stamped.outcome.id, &keys.announcement)
+                {
+                    let olivia_v1 = match oracle_event.schemes.olivia_v1 {
+                        Some(_) => Some(attest::OliviaV1 {
+                            scalars: self
+                                .signer
+                                .olivia_v1_scalars_for_event_outcome(&key_id, &stamped)
+                                .await
+                                .map_err(OutcomeResult::SignerErr)?,
+                        }),
+                        None => None,
+                    };
+                    let ecdsa_v1 = match oracle_event.schemes.ecdsa_v1 {
+                        Some(_) => Some(attest::EcdsaV1 {
+                            signature: self
+                                .signer
+                                .ecdsa_sign_outcome(&key_id, &stamped.outcome)
+                                .await
+                                .map_err(OutcomeResult::SignerErr)?,
+                        }),
+                        None => None,
+                    };
+                    let tlv_oracle_event = tlv_announcement.and_then(|tlv_announcement| {
+                        tlv_announcement.verify_against_id(
+                            &stamped.outcome.id,
+                            keys.olivia_v1_key_for(stamped.outcome.id.path())?,
+                        )
+                    });
+                    let dlc_v0 = match tlv_oracle_event.and_then(|event| event.schemes.dlc_v0) {
+                        Some(_) => Some(attest::DlcV0 {
+                            signatures: self
+                                .signer
+                                .dlc_v0_sign_outcome(&key_id, &stamped.outcome)
+                                .await
+                                .map_err(OutcomeResult::SignerErr)?,
+                        }),
+                        None => None,
+                    };
+                    let att_schemes = AttestationSchemes {
+                        olivia_v1,
+                        ecdsa_v1,
+                        dlc_v0,
+                    };
+
+                    let attestation = Attestation {
+                        overflow,
+                        ..Attestation::new(outcome_val_str, stamped.time, att_schemes)
+                    };
 
                     self.db
                         .complete_event(&event.id, attestation)
@@ -149,8 +337,11 @@ impl<C: Group> Oracle<C> {
 pub mod test {
     use super::*;
     use crate::db::Db;
-    use core::{convert::TryInto, str::FromStr};
-    use olivia_core::{EventId, WireEventOutcome};
+    use core::{
+        convert::{TryFrom, TryInto},
+        str::FromStr,
+    };
+    use olivia_core::{chrono, EventId, OverflowPolicy, Path, WireEventOutcome};
 
     pub async fn test_oracle_event_lifecycle<C: Group>(db: Arc<dyn Db<C>>) {
         let oracle = Oracle::new(crate::seed::Seed::new([42u8; 64]), db.clone())
@@ -175,6 +366,19 @@ pub mod test {
             .verify_against_id(&event_id, &public_keys.announcement)
             .expect("announcement signature should be valid");
 
+        let tlv_oracle_event = event
+            .tlv_announcement
+            .expect("event should have a TLV announcement")
+            .verify_against_id(&event_id, public_keys.olivia_v1.as_ref().unwrap())
+            .expect("TLV announcement signature should be valid");
+        assert_eq!(tlv_oracle_event.event, oracle_event.event);
+        let dlc_v0_nonces = &tlv_oracle_event.schemes.dlc_v0.as_ref().unwrap().nonces;
+        let olivia_v1_nonces = &oracle_event.schemes.olivia_v1.as_ref().unwrap().nonces;
+        assert_eq!(dlc_v0_nonces.len(), olivia_v1_nonces.len());
+        assert!(dlc_v0_nonces
+            .iter()
+            .all(|nonce| !olivia_v1_nonces.contains(nonce)));
+
         let outcome: StampedOutcome = WireEventOutcome {
             event_id: event_id.clone(),
             outcome: "true".into(),
@@ -196,6 +400,10 @@ pub mod test {
             attestation.verify_olivia_v1_attestation(&oracle_event, &public_keys),
             Ok(())
         );
+        assert_eq!(
+            attestation.verify_dlc_v0_attestation(&tlv_oracle_event, &public_keys),
+            Ok(())
+        );
     }
 
     pub async fn test_price_oracle_event_lifecycle<C: Group>(db: Arc<dyn Db<C>>) {
@@ -207,40 +415,328 @@ pub mod test {
             .await
             .unwrap()
             .expect("creating oracle should have set public keys");
-        let event_id = EventId::from_str("/foo/bar/baz.price?n=6").unwrap();
-        assert!(oracle.add_event(event_id.clone().into()).await.is_ok());
+        for (event_id, outcome) in [
+            ("/foo/bar/baz.price?n=6", "23"),
+            ("/foo/bar/baz.price?n=6&signed", "-23"),
+            ("/foo/bar/baz.price?n=6&b=10", "4213"),
+            ("/foo/bar/qux.price?n=6&b=10&signed", "void"),
+        ] {
+            let event_id = EventId::from_str(event_id).unwrap();
+            assert!(oracle.add_event(event_id.clone().into()).await.is_ok());
 
-        let event = db
-            .get_announced_event(&event_id)
+            let event = db
+                .get_announced_event(&event_id)
+                .await
+                .unwrap()
+                .expect("event should be there");
+
+            let oracle_event = event
+                .announcement
+                .verify_against_id(&event_id, &public_keys.announcement)
+                .expect("announcement signature should be valid");
+
+            let tlv_oracle_event = event
+                .tlv_announcement
+                .expect("event should have a TLV announcement")
+                .verify_against_id(&event_id, public_keys.olivia_v1.as_ref().unwrap())
+                .expect("TLV announcement signature should be valid");
+            assert_eq!(tlv_oracle_event.event, oracle_event.event);
+            let dlc_v0_nonces = &tlv_oracle_event.schemes.dlc_v0.as_ref().unwrap().nonces;
+            let olivia_v1_nonces = &oracle_event.schemes.olivia_v1.as_ref().unwrap().nonces;
+            assert_eq!(dlc_v0_nonces.len(), olivia_v1_nonces.len());
+            assert!(dlc_v0_nonces
+                .iter()
+                .all(|nonce| !olivia_v1_nonces.contains(nonce)));
+
+            let outcome: StampedOutcome = WireEventOutcome {
+                event_id: event_id.clone(),
+                outcome: outcome.into(),
+                time: None,
+            }
+            .try_into()
+            .unwrap();
+
+            assert!(oracle.complete_event(outcome.clone()).await.is_ok());
+
+            let attested_event = db
+                .get_announced_event(&event_id)
+                .await
+                .unwrap()
+                .expect("event should still be there");
+
+            let attestation = attested_event.attestation.expect("should be attested to");
+            assert_eq!(
+                attestation.verify_olivia_v1_attestation(&oracle_event, &public_keys),
+                Ok(())
+            );
+            assert_eq!(
+                attestation.verify_dlc_v0_attestation(&tlv_oracle_event, &public_keys),
+                Ok(())
+            );
+        }
+    }
+
+    pub async fn test_compound_oracle_event_lifecycle<C: Group>(db: Arc<dyn Db<C>>) {
+        use crate::sources::complete_compound::CompleteCompound;
+        let oracle = Oracle::new(crate::seed::Seed::new([42u8; 64]), db.clone())
             .await
+            .expect("should be able to create oracle");
+        let complete_compound = CompleteCompound { db: db.clone() };
+        let btc = EventId::from_str("/x/BitMEX/BXBT/2021-10-05T12:00:00.price_50000").unwrap();
+        let eth = EventId::from_str("/x/BitMEX/ETHUSD/2021-10-05T12:00:00.price_3000").unwrap();
+        let and = EventId::from_str("/combo/2021-10-05T12:00:00.and(~x~BitMEX~BXBT~2021-10-05T12:00:00.price_50000;~x~BitMEX~ETHUSD~2021-10-05T12:00:00.price_3000)").unwrap();
+        let or = EventId::from_str("/combo/2021-10-05T12:00:00.or(~x~BitMEX~BXBT~2021-10-05T12:00:00.price_50000;~x~BitMEX~ETHUSD~2021-10-05T12:00:00.price_3000)").unwrap();
+
+        for event_id in [&btc, &eth, &and, &or] {
+            assert!(oracle.add_event(event_id.clone().into()).await.is_ok());
+        }
+
+        let complete = |event_id: &EventId, outcome: &str| {
+            StampedOutcome::try_from(WireEventOutcome {
+                event_id: event_id.clone(),
+                outcome: outcome.into(),
+                time: None,
+            })
             .unwrap()
-            .expect("event should be there");
+        };
 
-        let oracle_event = event
-            .announcement
-            .verify_against_id(&event_id, &public_keys.announcement)
-            .expect("announcement signature should be valid");
+        assert!(oracle.complete_event(complete(&btc, "true")).await.is_ok());
+        assert_eq!(
+            complete_compound.complete_compound(&btc).await.unwrap(),
+            vec![],
+            "nothing should be completed until all operands are attested"
+        );
 
-        let outcome: StampedOutcome = WireEventOutcome {
-            event_id: event_id.clone(),
-            outcome: "23".into(),
-            time: None,
+        assert!(oracle.complete_event(complete(&eth, "false")).await.is_ok());
+        let mut outcomes = complete_compound.complete_compound(&eth).await.unwrap();
+        outcomes.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(
+            outcomes
+                .iter()
+                .map(|outcome| (outcome.id.clone(), outcome.outcome_string()))
+                .collect::<Vec<_>>(),
+            vec![
+                (and.clone(), "false".to_string()),
+                (or.clone(), "true".to_string())
+            ]
+        );
+
+        let public_keys = oracle.public_keys();
+        for outcome in outcomes {
+            let id = outcome.id.clone();
+            assert!(oracle
+                .complete_event(StampedOutcome {
+                    outcome,
+                    time: chrono::Utc::now().naive_utc()
+                })
+                .await
+                .is_ok());
+            let event = db.get_announced_event(&id).await.unwrap().unwrap();
+            let oracle_event = event
+                .announcement
+                .verify_against_id(&id, &public_keys.announcement)
+                .unwrap();
+            assert_eq!(
+                event
+                    .attestation
+                    .expect("compound event should be attested")
+                    .verify_olivia_v1_attestation(&oracle_event, &public_keys),
+                Ok(())
+            );
         }
-        .try_into()
-        .unwrap();
 
-        assert!(oracle.complete_event(outcome.clone()).await.is_ok());
+        assert_eq!(
+            complete_compound.complete_compound(&eth).await.unwrap(),
+            vec![]
+        );
+    }
 
-        let attested_event = db
-            .get_announced_event(&event_id)
+    pub async fn test_overflow_policies<C: Group>(db: Arc<dyn Db<C>>) {
+        let public_keys = db.get_public_keys().await.unwrap();
+        for (policy, event_id, expected) in [
+            (OverflowPolicy::Reject, "/overflow/reject.price?n=4", None),
+            (
+                OverflowPolicy::Clamp,
+                "/overflow/clamp.price?n=4",
+                Some("15"),
+            ),
+            (
+                OverflowPolicy::Void,
+                "/overflow/void.price?n=4",
+                Some("void"),
+            ),
+        ] {
+            let oracle = Oracle::new(crate::seed::Seed::new([42u8; 64]), db.clone())
+                .await
+                .expect("should be able to create oracle")
+                .with_overflow_policy(policy);
+            let event_id = EventId::from_str(event_id).unwrap();
+            assert!(oracle.add_event(event_id.clone().into()).await.is_ok());
+            let stamped = |outcome: &str| {
+                StampedOutcome::try_from(WireEventOutcome {
+                    event_id: event_id.clone(),
+                    outcome: outcome.into(),
+                    time: None,
+                })
+                .unwrap()
+            };
+
+            let res = oracle.complete_event(stamped("16")).await;
+            let event = db.get_announced_event(&event_id).await.unwrap().unwrap();
+            match expected {
+                None => {
+                    assert!(matches!(res, Err(OutcomeResult::OutOfRange { .. })));
+                    assert!(event.attestation.is_none());
+                }
+                Some(expected) => {
+                    assert!(res.is_ok());
+                    let attestation = event.attestation.expect("should be attested to");
+                    assert_eq!(attestation.outcome, expected);
+                    assert_eq!(attestation.overflow, Some(policy));
+                    let oracle_event = event
+                        .announcement
+                        .verify_against_id(&event_id, &public_keys.as_ref().unwrap().announcement)
+                        .unwrap();
+                    assert_eq!(
+                        attestation.verify_olivia_v1_attestation(
+                            &oracle_event,
+                            public_keys.as_ref().unwrap()
+                        ),
+                        Ok(())
+                    );
+                    // sending the same out of range outcome again shouldn't look like a change
+                    assert!(matches!(
+                        oracle.complete_event(stamped("16")).await,
+                        Err(OutcomeResult::AlreadyCompleted)
+                    ));
+                }
+            }
+        }
+    }
+
+    pub async fn test_key_rotation<C: Group>(db: Arc<dyn Db<C>>) {
+        let seed = crate::seed::Seed::new([42u8; 64]);
+        let mut oracle = Oracle::new(seed.clone(), db.clone())
             .await
-            .unwrap()
-            .expect("event should still be there");
+            .expect("should be able to create oracle");
+        let old_epoch = oracle.key_epoch();
+        let old_keys = oracle.public_keys();
+        let before = EventId::from_str("/rotation/before.occur").unwrap();
+        assert!(oracle.add_event(before.clone().into()).await.is_ok());
 
-        let attestation = attested_event.attestation.expect("should be attested to");
+        let key_epoch = oracle.rotate_keys().await.unwrap();
+        assert_eq!(key_epoch.epoch, old_epoch + 1);
+        assert_eq!(oracle.key_epoch(), key_epoch.epoch);
+        assert_ne!(key_epoch.keys, old_keys);
         assert_eq!(
-            attestation.verify_olivia_v1_attestation(&oracle_event, &public_keys),
-            Ok(())
+            db.get_public_keys().await.unwrap(),
+            Some(key_epoch.keys.clone())
         );
+
+        let key_epochs = db.get_key_epochs().await.unwrap();
+        let previous = &key_epochs[key_epochs.len() - 2];
+        assert_eq!(previous.epoch, old_epoch);
+        assert_eq!(previous.keys, old_keys);
+        assert_eq!(previous.valid_until, Some(key_epoch.valid_from));
+        assert_eq!(key_epochs[key_epochs.len() - 1], key_epoch);
+
+        let after = EventId::from_str("/rotation/after.occur").unwrap();
+        assert!(oracle.add_event(after.clone().into()).await.is_ok());
+
+        for (event_id, key_epoch, keys) in [
+            (before, old_epoch, &old_keys),
+            (after, key_epoch.epoch, &key_epoch.keys),
+        ] {
+            let outcome: StampedOutcome = WireEventOutcome {
+                event_id: event_id.clone(),
+                outcome: "true".into(),
+                time: None,
+            }
+            .try_into()
+            .unwrap();
+            assert!(oracle.complete_event(outcome).await.is_ok());
+
+            let event = db.get_announced_event(&event_id).await.unwrap().unwrap();
+            assert_eq!(event.key_epoch, key_epoch);
+            let oracle_event = event
+                .announcement
+                .verify_against_id(&event_id, &keys.announcement)
+                .expect("announcement should be signed by the key epoch it records");
+            assert_eq!(
+                event
+                    .attestation
+                    .expect("should be attested to")
+                    .verify_olivia_v1_attestation(&oracle_event, keys),
+                Ok(())
+            );
+        }
+
+        assert!(Oracle::new(seed, db.clone()).await.is_ok());
+        assert!(
+            Oracle::new(crate::seed::Seed::new([43u8; 64]), db.clone())
+                .await
+                .is_err(),
+            "a different seed should not match the key history"
+        );
+    }
+
+    pub async fn test_key_namespaces<C: Group>(db: Arc<dyn Db<C>>) {
+        let seed = crate::seed::Seed::new([42u8; 64]);
+        let bitmex = Path::from_str("/x/BitMEX").unwrap();
+        let mut oracle =
+            Oracle::new_with_key_namespaces(seed.clone(), db.clone(), vec![bitmex.clone()])
+                .await
+                .expect("should be able to create oracle");
+        if oracle.key_namespaces_changed() {
+            oracle.rotate_keys().await.unwrap();
+        }
+        assert!(!oracle.key_namespaces_changed());
+
+        let public_keys = oracle.public_keys();
+        let namespace_key = public_keys.olivia_v1_namespaces.get(&bitmex).unwrap();
+        assert_ne!(Some(namespace_key), public_keys.olivia_v1.as_ref());
+
+        let in_namespace =
+            EventId::from_str("/x/BitMEX/BXBT/2021-10-05T12:00:00.price?n=20").unwrap();
+        let outside = EventId::from_str("/x/BitMEXX/BXBT/2021-10-05T12:00:00.price?n=20").unwrap();
+        for (event_id, key) in [
+            (&in_namespace, namespace_key),
+            (&outside, public_keys.olivia_v1.as_ref().unwrap()),
+        ] {
+            assert_eq!(public_keys.olivia_v1_key_for(event_id.path()), Some(key));
+            assert!(oracle.add_event(event_id.clone().into()).await.is_ok());
+            let outcome: StampedOutcome = WireEventOutcome {
+                event_id: event_id.clone(),
+                outcome: "42000".into(),
+                time: None,
+            }
+            .try_into()
+            .unwrap();
+            assert!(oracle.complete_event(outcome).await.is_ok());
+
+            let event = db.get_announced_event(event_id).await.unwrap().unwrap();
+            let tlv_oracle_event = event
+                .tlv_announcement
+                .as_ref()
+                .unwrap()
+                .verify_against_id(event_id, key)
+                .expect("TLV announcement should be signed by the event's olivia-v1 key");
+            let oracle_event = event
+                .announcement
+                .verify_against_id(event_id, &public_keys.announcement)
+                .unwrap();
+            let attestation = event.attestation.expect("should be attested to");
+            assert_eq!(
+                attestation.verify_olivia_v1_attestation(&oracle_event, &public_keys),
+                Ok(())
+            );
+            assert_eq!(
+                attestation.verify_dlc_v0_attestation(&tlv_oracle_event, &public_keys),
+                Ok(())
+            );
+        }
+
+        // the namespaces are remembered in the key history so they don't need to be passed in
+        assert!(Oracle::new(seed, db.clone()).await.is_ok());
     }
 }
@@ -1,67 +1,182 @@
 use crate::{
-    db::Db,
+    db::{self, Db},
     log::OracleLog,
-    sources::{self, Update},
+    oracle::{EventResult, OutcomeResult},
+    sources::{self, complete_compound::CompleteCompound, Update},
     Oracle,
 };
-use olivia_core::{Event, Group, Node, Path, PrefixPath, StampedOutcome};
+use async_trait::async_trait;
+use olivia_core::{Event, EventId, Group, Node, Outcome, Path, PrefixPath, StampedOutcome};
 use std::sync::Arc;
 use tokio_stream::{StreamExt, StreamMap};
 
-pub struct OracleLoop<G: Group> {
+/// The oracle of one group and its database
+pub struct GroupOracle<C: Group> {
+    pub oracle: Oracle<C>,
+    pub db: Arc<dyn Db<C>>,
+}
+
+/// A [`GroupOracle`] of any group so one loop can drive several of them
+#[async_trait]
+pub trait AnyGroupOracle: Send + Sync {
+    fn group_name(&self) -> &'static str;
+    async fn add_event(&self, event: Event) -> Result<(), EventResult>;
+    async fn complete_event(&self, stamped: StampedOutcome) -> Result<(), OutcomeResult>;
+    async fn complete_compound(&self, completed: &EventId) -> anyhow::Result<Vec<Outcome>>;
+    async fn set_node(&self, node: Node) -> Result<(), db::Error>;
+}
+
+#[async_trait]
+impl<C: Group> AnyGroupOracle for GroupOracle<C> {
+    fn group_name(&self) -> &'static str {
+        C::name()
+    }
+
+    async fn add_event(&self, event: Event) -> Result<(), EventResult> {
+        self.oracle.add_event(event).await
+    }
+
+    async fn complete_event(&self, stamped: StampedOutcome) -> Result<(), OutcomeResult> {
+        self.oracle.complete_event(stamped).await
+    }
+
+    async fn complete_compound(&self, completed: &EventId) -> anyhow::Result<Vec<Outcome>> {
+        CompleteCompound {
+            db: self.db.clone(),
+        }
+        .complete_compound(completed)
+        .await
+    }
+
+    async fn set_node(&self, node: Node) -> Result<(), db::Error> {
+        self.db.set_node(node).await
+    }
+}
+
+/// Whether a group failed to add the event. A group that already has it hasn't failed since the
+/// sources send events again until every group has them.
+fn event_failed(res: &Result<(), EventResult>) -> bool {
+    !matches!(
+        res,
+        Ok(()) | Err(EventResult::AlreadyExists | EventResult::AlreadyCompleted)
+    )
+}
+
+/// Whether a group failed to attest to the outcome. Like [`event_failed`] a group that already
+/// attested to it hasn't failed.
+fn outcome_failed(res: &Result<(), OutcomeResult>) -> bool {
+    !matches!(res, Ok(()) | Err(OutcomeResult::AlreadyCompleted))
+}
+
+/// Announces and attests to the events from the streams in every group's oracle
+pub struct OracleLoop {
     pub events: StreamMap<(Path, usize), sources::Stream<Event>>,
     pub outcomes: StreamMap<(Path, usize), sources::Stream<StampedOutcome>>,
     pub nodes: StreamMap<(Path, usize), sources::Stream<Node>>,
-    pub oracle: Oracle<G>,
-    pub db: Arc<dyn Db<G>>,
+    pub oracles: Vec<Box<dyn AnyGroupOracle>>,
     pub logger: slog::Logger,
 }
 
-impl<G: Group> OracleLoop<G> {
+impl OracleLoop {
     pub async fn start(self) {
         let OracleLoop {
             mut events,
             mut outcomes,
             mut nodes,
-            oracle,
-            db,
+            oracles,
             logger,
         } = self;
         loop {
             tokio::select! {
                 Some((parent, Update { update: event, processed_notifier })) = events.next() => {
                     let event = event.prefix_path(parent.0.as_path_ref());
-                    let logger = logger
-                        .new(o!("type" => "new_event", "event_id" => event.id.to_string()));
-                    let res = oracle.add_event(event).await;
+                    let mut failed = false;
+                    for oracle in &oracles {
+                        let logger = logger.new(o!(
+                            "type" => "new_event",
+                            "event_id" => event.id.to_string(),
+                            "group" => oracle.group_name()
+                        ));
+                        let res = oracle.add_event(event.clone()).await;
+                        failed |= event_failed(&res);
+                        logger.log_event_result(res)
+                    }
                     if let Some(processed_notifier) = processed_notifier {
-                        let _ = processed_notifier.send(res.is_err());
+                        let _ = processed_notifier.send(failed);
                     }
-                    logger.log_event_result(res)
                 },
                 Some((parent, Update { update: stamped, processed_notifier })) = outcomes.next() => {
                     let stamped = stamped.prefix_path(parent.0.as_path_ref());
-                    let logger = logger.new(
-                            o!("type" => "new_outcome", "event_id" => stamped.outcome.id.to_string(), "value" => stamped.outcome.outcome_string()),
-                        );
-                    let res = oracle.complete_event(stamped.clone()).await;
+                    let mut failed = false;
+                    let mut compounds = Vec::<Outcome>::new();
+                    for oracle in &oracles {
+                        let logger = logger.new(o!(
+                            "type" => "new_outcome",
+                            "event_id" => stamped.outcome.id.to_string(),
+                            "value" => stamped.outcome.outcome_string(),
+                            "group" => oracle.group_name()
+                        ));
+                        let res = oracle.complete_event(stamped.clone()).await;
+                        failed |= outcome_failed(&res);
+                        let attested = matches!(res, Ok(()) | Err(OutcomeResult::AlreadyCompleted));
+                        logger.log_outcome_result(res);
+
+                        if attested {
+                            // this may have been the last operand a compound event was waiting on
+                            match oracle.complete_compound(&stamped.outcome.id).await {
+                                Ok(outcomes) => for outcome in outcomes {
+                                    if !compounds.iter().any(|compound| compound.id == outcome.id) {
+                                        compounds.push(outcome);
+                                    }
+                                },
+                                Err(e) => {
+                                    failed = true;
+                                    error!(logger, "completing compound events"; "error" => e.to_string())
+                                }
+                            }
+                        }
+                    }
+                    // every group attests to the same compound events even if some of them
+                    // failed to attest to the operand
+                    for outcome in compounds {
+                        for oracle in &oracles {
+                            let logger = logger.new(o!(
+                                "type" => "new_outcome",
+                                "event_id" => stamped.outcome.id.to_string(),
+                                "compound_event_id" => outcome.id.to_string(),
+                                "value" => outcome.outcome_string(),
+                                "group" => oracle.group_name()
+                            ));
+                            let res = oracle
+                                .complete_event(StampedOutcome { outcome: outcome.clone(), time: stamped.time })
+                                .await;
+                            failed |= outcome_failed(&res);
+                            logger.log_outcome_result(res)
+                        }
+                    }
                     if let Some(processed_notifier) = processed_notifier {
-                        let _ = processed_notifier.send(res.is_err());
+                        let _ = processed_notifier.send(failed);
                     }
-                    logger.log_outcome_result(res)
                 },
                 Some((parent, Update { update: node, processed_notifier })) = nodes.next() => {
                     let node = node.prefix_path(parent.0.as_path_ref());
-                    let logger =
-                        logger.new(o!("type" => "new_node", "path" => node.path.to_string()));
-                    let res = db.set_node(node.clone()).await;
-                    if let Some(processed_notifier) = processed_notifier {
-                        let _ = processed_notifier.send(res.is_err());
+                    let mut failed = false;
+                    for oracle in &oracles {
+                        let logger = logger.new(o!(
+                            "type" => "new_node",
+                            "path" => node.path.to_string(),
+                            "group" => oracle.group_name()
+                        ));
+                        match oracle.set_node(node.clone()).await {
+                            Ok(()) => info!(logger, "added"),
+                            Err(e) => {
+                                failed = true;
+                                error!(logger, "failed to add"; "error" => e.to_string())
+                            }
+                        }
                     }
-
-                    match res {
-                        Ok(()) => info!(logger, "added"),
-                        Err(e) => error!(logger, "failed to add"; "error" => e.to_string()),
+                    if let Some(processed_notifier) = processed_notifier {
+                        let _ = processed_notifier.send(failed);
                     }
                 },
                 else =>  {
@@ -72,3 +187,198 @@ impl<G: Group> OracleLoop<G> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        db::{in_memory::InMemory, DbReadOracle},
+        rest_api,
+        seed::Seed,
+    };
+    use core::str::FromStr;
+    use olivia_core::http::{EventResponse, RootResponse};
+    use olivia_ristretto::Ristretto;
+    use olivia_secp256k1::Secp256k1;
+
+    async fn group_oracle<C: Group>(db: &InMemory<C>) -> Box<dyn AnyGroupOracle> {
+        let db: Arc<dyn Db<C>> = Arc::new(db.clone());
+        Box::new(GroupOracle {
+            oracle: Oracle::new(Seed::new([42u8; 64]), db.clone())
+                .await
+                .unwrap(),
+            db,
+        })
+    }
+
+    fn stream_of<T: Send + 'static>(items: Vec<T>) -> StreamMap<(Path, usize), sources::Stream<T>> {
+        let mut streams = StreamMap::new();
+        let stream: sources::Stream<T> =
+            Box::pin(tokio_stream::iter(items.into_iter().map(Update::from)));
+        streams.insert((Path::root(), 0), stream);
+        streams
+    }
+
+    #[tokio::test]
+    async fn serves_every_group() {
+        let secp256k1 = InMemory::<Secp256k1>::default();
+        let ristretto = InMemory::<Ristretto>::default();
+        let logger = slog::Logger::root(slog::Discard, o!());
+        let event_id = EventId::from_str("/foo/red_blue.winner").unwrap();
+
+        OracleLoop {
+            events: stream_of(vec![Event::from(event_id.clone())]),
+            outcomes: StreamMap::new(),
+            nodes: StreamMap::new(),
+            oracles: vec![
+                group_oracle(&secp256k1).await,
+                group_oracle(&ristretto).await,
+            ],
+            logger: logger.clone(),
+        }
+        .start()
+        .await;
+        OracleLoop {
+            events: StreamMap::new(),
+            outcomes: stream_of(vec![StampedOutcome {
+                outcome: Outcome::try_from_id_and_outcome(event_id.clone(), "red").unwrap(),
+                time: olivia_core::chrono::Utc::now().naive_utc(),
+            }]),
+            nodes: StreamMap::new(),
+            oracles: vec![
+                group_oracle(&secp256k1).await,
+                group_oracle(&ristretto).await,
+            ],
+            logger: logger.clone(),
+        }
+        .start()
+        .await;
+
+        let routes = rest_api::group_routes(vec![
+            (
+                Secp256k1::name(),
+                rest_api::boxed_routes::<Secp256k1>(Arc::new(secp256k1), logger.clone()),
+            ),
+            (
+                Ristretto::name(),
+                rest_api::boxed_routes::<Ristretto>(Arc::new(ristretto), logger.clone()),
+            ),
+        ]);
+
+        let root = warp::test::request().path("/").reply(&routes).await;
+        let root = serde_json::from_slice::<RootResponse<Secp256k1>>(root.body()).unwrap();
+        let secp256k1_event = warp::test::request()
+            .path(event_id.as_str())
+            .reply(&routes)
+            .await;
+        let secp256k1_event =
+            serde_json::from_slice::<EventResponse<Secp256k1>>(secp256k1_event.body()).unwrap();
+        assert_eq!(secp256k1_event.attestation.unwrap().outcome, "red");
+        assert_eq!(
+            warp::test::request()
+                .path("/.groups/secp256k1/")
+                .reply(&routes)
+                .await
+                .body(),
+            &serde_json::to_vec(&root).unwrap()
+        );
+
+        let ristretto_root = warp::test::request()
+            .path("/.groups/ristretto255/")
+            .reply(&routes)
+            .await;
+        let ristretto_root =
+            serde_json::from_slice::<RootResponse<Ristretto>>(ristretto_root.body()).unwrap();
+        let ristretto_event = warp::test::request()
+            .path(&format!("/.groups/ristretto255{}", event_id))
+            .reply(&routes)
+            .await;
+        let ristretto_event =
+            serde_json::from_slice::<EventResponse<Ristretto>>(ristretto_event.body()).unwrap();
+        let oracle_event = ristretto_event
+            .announcement
+            .verify_against_id(&event_id, &ristretto_root.public_keys.announcement)
+            .unwrap();
+        assert_eq!(
+            ristretto_event
+                .attestation
+                .unwrap()
+                .verify_olivia_v1_attestation(&oracle_event, &ristretto_root.public_keys),
+            Ok(())
+        );
+    }
+
+    #[tokio::test]
+    async fn groups_attest_to_the_same_compound_events() {
+        let secp256k1 = InMemory::<Secp256k1>::default();
+        let ristretto = InMemory::<Ristretto>::default();
+        let logger = slog::Logger::root(slog::Discard, o!());
+        let foo = EventId::from_str("/foo/a.occur").unwrap();
+        let bar = EventId::from_str("/foo/b.occur").unwrap();
+        let compound = EventId::from_str("/foo/a-and-b.and(~foo~a.occur;~foo~b.occur)").unwrap();
+
+        OracleLoop {
+            events: stream_of(vec![
+                Event::from(foo.clone()),
+                Event::from(compound.clone()),
+            ]),
+            outcomes: StreamMap::new(),
+            nodes: StreamMap::new(),
+            oracles: vec![
+                group_oracle(&secp256k1).await,
+                group_oracle(&ristretto).await,
+            ],
+            logger: logger.clone(),
+        }
+        .start()
+        .await;
+        // ristretto255 failed to add the last operand so it can't attest to it
+        group_oracle(&secp256k1)
+            .await
+            .add_event(Event::from(bar.clone()))
+            .await
+            .unwrap();
+
+        OracleLoop {
+            events: StreamMap::new(),
+            outcomes: stream_of(
+                [foo, bar]
+                    .into_iter()
+                    .map(|id| StampedOutcome {
+                        outcome: Outcome::try_from_id_and_outcome(id, "true").unwrap(),
+                        time: olivia_core::chrono::Utc::now().naive_utc(),
+                    })
+                    .collect(),
+            ),
+            nodes: StreamMap::new(),
+            oracles: vec![
+                group_oracle(&secp256k1).await,
+                group_oracle(&ristretto).await,
+            ],
+            logger,
+        }
+        .start()
+        .await;
+
+        assert_eq!(
+            secp256k1
+                .get_announced_event(&compound)
+                .await
+                .unwrap()
+                .unwrap()
+                .attestation
+                .map(|attestation| attestation.outcome),
+            Some("true".to_string())
+        );
+        assert_eq!(
+            ristretto
+                .get_announced_event(&compound)
+                .await
+                .unwrap()
+                .unwrap()
+                .attestation
+                .map(|attestation| attestation.outcome),
+            Some("true".to_string())
+        );
+    }
+}
@@ -3,7 +3,7 @@ use core::{convert::TryFrom, str::FromStr};
 use olivia_core::{http::*, EventId, GetPath, Group, Path, PathRef};
 use serde::Serialize;
 use std::{convert::Infallible, sync::Arc};
-use warp::{self, http, Filter};
+use warp::{self, filters::BoxedFilter, http, Filter};
 
 #[derive(Clone, Debug)]
 pub enum ApiReply<T> {
@@ -112,7 +112,16 @@ async fn get_event<C: Group>(
         Ok(event_id) => {
             let res = db.get_announced_event(&event_id).await;
             match res {
-                Ok(Some(event)) => ApiReply::Ok(event.into()),
+                Ok(Some(event)) => match db.get_key_epochs().await {
+                    Ok(key_epochs) => match key_epochs
+                        .into_iter()
+                        .find(|key_epoch| key_epoch.epoch == event.key_epoch)
+                    {
+                        Some(key_epoch) => ApiReply::Ok(EventResponse::new(event, &key_epoch.keys)),
+                        None => ApiReply::Err(ErrorMessage::internal_server_error()),
+                    },
+                    Err(_) => ApiReply::Err(ErrorMessage::internal_server_error()),
+                },
                 Ok(None) => ApiReply::Err(ErrorMessage::not_found()),
                 Err(_e) => ApiReply::Err(ErrorMessage::internal_server_error()),
             }
@@ -127,11 +136,12 @@ async fn get_event<C: Group>(
 }
 
 pub async fn get_root<C: Group>(db: Arc<dyn DbReadOracle<C>>) -> ApiReply<RootResponse<C>> {
-    let public_keys = db.get_public_keys().await;
-    match public_keys {
-        Ok(Some(public_keys)) => match db.get_node(PathRef::root()).await {
+    let key_epochs = db.get_key_epochs().await;
+    match key_epochs {
+        Ok(key_epochs) if !key_epochs.is_empty() => match db.get_node(PathRef::root()).await {
             Ok(Some(node)) => ApiReply::Ok(RootResponse {
-                public_keys,
+                public_keys: key_epochs[key_epochs.len() - 1].keys.clone(),
+                key_epochs,
                 node: GetPath {
                     events: node.events,
                     child_desc: node.child_desc,
@@ -142,7 +152,7 @@ pub async fn get_root<C: Group>(db: Arc<dyn DbReadOracle<C>>) -> ApiReply<RootRe
                     .with_message("root node could not be read from the database"),
             ),
         },
-        Err(_) | Ok(None) => ApiReply::Err(
+        Err(_) | Ok(_) => ApiReply::Err(
             ErrorMessage::internal_server_error()
                 .with_message("oracle public keys could not be retrieved from the database"),
         ),
@@ -152,7 +162,7 @@ pub async fn get_root<C: Group>(db: Arc<dyn DbReadOracle<C>>) -> ApiReply<RootRe
 async fn get_path<C: Group>(
     tail: ApiReply<String>,
     db: Arc<dyn DbReadOracle<C>>,
-) -> ApiReply<PathResponse> {
+) -> ApiReply<PathResponse<C>> {
     let tail = match tail {
         ApiReply::Ok(tail) => tail,
         ApiReply::Err(e) => return ApiReply::Err(e),
@@ -168,8 +178,14 @@ async fn get_path<C: Group>(
         }
     };
     let node = db.get_node(path.as_path_ref()).await;
+    let public_keys = match db.get_public_keys().await {
+        Ok(public_keys) => public_keys,
+        Err(_e) => return ApiReply::Err(ErrorMessage::internal_server_error()),
+    };
     match node {
         Ok(Some(node)) => ApiReply::Ok(PathResponse {
+            olivia_v1_key: public_keys
+                .and_then(|public_keys| public_keys.olivia_v1_key_for(path.as_path_ref()).cloned()),
             node: GetPath {
                 events: node.events,
                 child_desc: node.child_desc,
@@ -190,7 +206,7 @@ async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply, warp
 pub fn routes<C: Group>(
     db: Arc<dyn DbReadOracle<C>>,
     _logger: slog::Logger,
-) -> impl Filter<Extract = impl warp::Reply, Error = warp::reject::Rejection> + Clone {
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::reject::Rejection> + Clone {
     let event = warp::get()
         .and(percent_decoded_tail())
         .map(|tail| (tail, None))
@@ -224,3 +240,33 @@ pub fn routes<C: Group>(
         .with(cors)
         .recover(handle_rejection)
 }
+
+pub type BoxedRoutes = BoxedFilter<(Box<dyn warp::Reply>,)>;
+
+/// [`routes`] with the group erased so routes for several groups can be combined
+pub fn boxed_routes<C: Group>(db: Arc<dyn DbReadOracle<C>>, logger: slog::Logger) -> BoxedRoutes {
+    routes(db, logger)
+        .map(|reply| Box::new(reply) as Box<dyn warp::Reply>)
+        .boxed()
+}
+
+/// Serves each group's routes under `/.groups/<group name>` and the first group's routes at the
+/// root as well.
+pub fn group_routes(groups: Vec<(&'static str, BoxedRoutes)>) -> BoxedRoutes {
+    let root = groups
+        .first()
+        .expect("there is always at least one group")
+        .1
+        .clone();
+    groups
+        .into_iter()
+        .rev()
+        .fold(root, |routes, (name, group_routes)| {
+            warp::path(".groups")
+                .and(warp::path(name))
+                .and(group_routes)
+                .or(routes)
+                .unify()
+                .boxed()
+        })
+}
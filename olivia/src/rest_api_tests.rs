@@ -13,7 +13,7 @@ macro_rules! run_rest_api_tests {
             use crate::rest_api::ErrorMessage;
             use warp::http;
             use serde_json::from_slice as j;
-            use olivia_core::{ GetPath, http::*};
+            use olivia_core::{ GetPath, http::*, chrono, Outcome, StampedOutcome};
             use core::str::FromStr;
 
             #[tokio::test]
@@ -100,6 +100,9 @@ macro_rules! run_rest_api_tests {
                     }
                 );
                 assert_eq!(body.public_keys, $oracle.public_keys());
+                assert_eq!(body.key_epochs.len(), 1);
+                assert_eq!(body.key_epochs[0].keys, $oracle.public_keys());
+                assert_eq!(body.key_epochs[0].valid_until, None);
             }
 
             #[tokio::test]
@@ -136,6 +139,7 @@ macro_rules! run_rest_api_tests {
 
                 let body = j::<EventResponse<$curve>>(&res.body()).unwrap();
 
+                assert_eq!(body.key_epoch, $oracle.key_epoch());
                 assert!(body
                         .announcement
                         .verify_against_id(&event_id, &public_keys.announcement)
@@ -169,7 +173,39 @@ macro_rules! run_rest_api_tests {
                 assert!(body
                         .announcement
                         .verify_against_id(&event_id, &public_keys.announcement)
-                        .is_some())
+                        .is_some());
+
+                assert!(body
+                        .tlv_announcement
+                        .expect("price events can be TLV encoded")
+                        .verify_against_id(&event_id, public_keys.olivia_v1.as_ref().unwrap())
+                        .is_some());
+                assert_eq!(body.tlv_attestation, None);
+
+                $oracle
+                    .complete_event(StampedOutcome {
+                        outcome: Outcome { id: event_id.clone(), value: 42, void: false },
+                        time: chrono::Utc::now().naive_utc(),
+                    })
+                    .await
+                    .unwrap();
+
+                let res = warp::test::request()
+                    .path(event_id.as_str())
+                    .reply(&$routes)
+                    .await;
+
+                let body = j::<EventResponse<$curve>>(&res.body()).unwrap();
+                let tlv_attestation = olivia_core::tlv::OracleAttestationTlv::<$curve>::decode(
+                    &body.tlv_attestation.expect("attested event should have TLV attestation")
+                ).unwrap();
+                assert_eq!(tlv_attestation.event_id, event_id.as_str());
+                assert_eq!(&tlv_attestation.public_key, public_keys.olivia_v1.as_ref().unwrap());
+                assert_eq!(tlv_attestation.outcomes.len(), 20);
+                assert_eq!(
+                    tlv_attestation.outcomes,
+                    Outcome { id: event_id.clone(), value: 42, void: false }.attestation_outcome_strings()
+                );
             }
 
             #[tokio::test]
@@ -32,6 +32,11 @@ impl Seed {
     pub const fn new(bytes: [u8; 64]) -> Self {
         Self(bytes)
     }
+
+    /// The BIP39 seed of the mnemonic (with an empty BIP39 passphrase)
+    pub fn from_mnemonic(words: &str) -> Result<Self, bip39::Error> {
+        Ok(Seed(bip39::Mnemonic::parse(words)?.to_seed("")))
+    }
 }
 
 impl AsRef<[u8; 64]> for Seed {
@@ -46,3 +51,17 @@ impl Default for Seed {
         Seed::new(*b"xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx")
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::str::FromStr;
+
+    #[test]
+    fn seed_from_mnemonic() {
+        let seed = Seed::from_mnemonic("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about").unwrap();
+        let expected = Seed::from_str("5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4").unwrap();
+        assert_eq!(seed.as_ref(), expected.as_ref());
+        assert!(Seed::from_mnemonic("abandon abandon abandon").is_err());
+    }
+}
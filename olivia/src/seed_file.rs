@@ -0,0 +1,198 @@
+//! Seeds stored in a file encrypted under a passphrase.
+//!
+//! The file is a header followed by the encrypted seed:
+//!
+//! `"olivia-seed" | version | m_cost | t_cost | p_cost | salt | nonce | ciphertext`
+//!
+//! The key is derived from the passphrase with argon2id using the parameters and salt in the
+//! header and the seed is encrypted with XChaCha20Poly1305 with the header as associated data.
+use crate::seed::Seed;
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+use rand::RngCore;
+use std::{convert::TryInto, path::Path};
+
+const MAGIC: &[u8; 11] = b"olivia-seed";
+const VERSION: u8 = 0;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const HEADER_LEN: usize = MAGIC.len() + 1 + 3 * 4 + SALT_LEN + NONCE_LEN;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SeedFileError {
+    #[error("not an olivia seed file")]
+    BadFormat,
+    #[error("unsupported seed file version {0}")]
+    UnsupportedVersion(u8),
+    #[error("invalid key derivation parameters: {0}")]
+    Kdf(argon2::Error),
+    #[error("wrong passphrase or the seed file is corrupted")]
+    Decrypt,
+    #[error("unable to access seed file: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// argon2id parameters for deriving the encryption key from the passphrase
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KdfParams {
+    /// memory in KiB
+    pub m_cost: u32,
+    pub t_cost: u32,
+    pub p_cost: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            m_cost: 64 * 1024,
+            t_cost: 3,
+            p_cost: 1,
+        }
+    }
+}
+
+impl KdfParams {
+    fn derive_key(&self, passphrase: &str, salt: &[u8]) -> Result<[u8; 32], SeedFileError> {
+        let params = argon2::Params::new(self.m_cost, self.t_cost, self.p_cost, Some(32))
+            .map_err(SeedFileError::Kdf)?;
+        let argon2 =
+            argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
+        let mut key = [0u8; 32];
+        argon2
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(SeedFileError::Kdf)?;
+        Ok(key)
+    }
+}
+
+pub fn encrypt(
+    seed: &Seed,
+    passphrase: &str,
+    params: KdfParams,
+    rng: &mut impl RngCore,
+) -> Result<Vec<u8>, SeedFileError> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill_bytes(&mut salt);
+    rng.fill_bytes(&mut nonce);
+
+    let mut file = Vec::with_capacity(HEADER_LEN + 64 + 16);
+    file.extend_from_slice(MAGIC);
+    file.push(VERSION);
+    for param in [params.m_cost, params.t_cost, params.p_cost] {
+        file.extend_from_slice(&param.to_be_bytes());
+    }
+    file.extend_from_slice(&salt);
+    file.extend_from_slice(&nonce);
+
+    let key = params.derive_key(passphrase, &salt)?;
+    let ciphertext = XChaCha20Poly1305::new(&key.into())
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: seed.as_ref(),
+                aad: &file,
+            },
+        )
+        .expect("encrypting 64 bytes can't fail");
+    file.extend_from_slice(&ciphertext);
+    Ok(file)
+}
+
+pub fn decrypt(file: &[u8], passphrase: &str) -> Result<Seed, SeedFileError> {
+    if file.len() < HEADER_LEN || &file[..MAGIC.len()] != MAGIC {
+        return Err(SeedFileError::BadFormat);
+    }
+    let (header, ciphertext) = file.split_at(HEADER_LEN);
+    let version = header[MAGIC.len()];
+    if version != VERSION {
+        return Err(SeedFileError::UnsupportedVersion(version));
+    }
+    let mut params = header[MAGIC.len() + 1..]
+        .chunks(4)
+        .map(|param| u32::from_be_bytes(param.try_into().unwrap()));
+    let params = KdfParams {
+        m_cost: params.next().unwrap(),
+        t_cost: params.next().unwrap(),
+        p_cost: params.next().unwrap(),
+    };
+    let salt = &header[HEADER_LEN - NONCE_LEN - SALT_LEN..HEADER_LEN - NONCE_LEN];
+    let nonce = &header[HEADER_LEN - NONCE_LEN..];
+
+    let key = params.derive_key(passphrase, salt)?;
+    let plaintext = XChaCha20Poly1305::new(&key.into())
+        .decrypt(
+            XNonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| SeedFileError::Decrypt)?;
+
+    let bytes: [u8; 64] = plaintext.try_into().map_err(|_| SeedFileError::BadFormat)?;
+    Ok(Seed::new(bytes))
+}
+
+pub fn read(path: &Path, passphrase: &str) -> Result<Seed, SeedFileError> {
+    decrypt(&std::fs::read(path)?, passphrase)
+}
+
+/// Writes the encrypted seed so only the owner can read it. The file is written next to `path`
+/// first and then moved over it so an existing seed file is never left half written.
+pub fn write(path: &Path, file: &[u8]) -> Result<(), SeedFileError> {
+    use std::{fs::OpenOptions, io::Write};
+    let tmp_path = path.with_extension("tmp");
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut tmp = options.open(&tmp_path)?;
+    tmp.write_all(file)?;
+    tmp.sync_all()?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_PARAMS: KdfParams = KdfParams {
+        m_cost: 64,
+        t_cost: 1,
+        p_cost: 1,
+    };
+
+    #[test]
+    fn encrypt_decrypt_roundtrip() {
+        let seed = Seed::new([42u8; 64]);
+        let file = encrypt(&seed, "hunter2", TEST_PARAMS, &mut rand::thread_rng()).unwrap();
+        assert_eq!(decrypt(&file, "hunter2").unwrap().as_ref(), seed.as_ref());
+        assert!(matches!(
+            decrypt(&file, "hunter3"),
+            Err(SeedFileError::Decrypt)
+        ));
+
+        let mut tampered_params = file.clone();
+        tampered_params[MAGIC.len() + 4] ^= 1;
+        assert!(decrypt(&tampered_params, "hunter2").is_err());
+
+        let mut bad_version = file.clone();
+        bad_version[MAGIC.len()] = 1;
+        assert!(matches!(
+            decrypt(&bad_version, "hunter2"),
+            Err(SeedFileError::UnsupportedVersion(1))
+        ));
+
+        assert!(matches!(
+            decrypt(&file[..HEADER_LEN - 1], "hunter2"),
+            Err(SeedFileError::BadFormat)
+        ));
+    }
+}
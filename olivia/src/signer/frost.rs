@@ -0,0 +1,889 @@
+//! One logical oracle key shared between several nodes with FROST (see
+//! [`olivia_secp256k1::frost`]).
+//!
+//! Each [`FrostNode`] holds its own seed and signing ledger and answers requests over a channel.
+//! The [`FrostSigner`] coordinates them and is the [`Signer`] the oracle uses. Keys and the
+//! olivia-v1 nonces of each event come from a DKG between all the nodes. A node's contribution is
+//! derived from its seed so running a DKG again gives the same shares. The coordinator relays the
+//! secret shares encrypted to their recipient. Signing and attesting only need `threshold` nodes.
+//!
+//! Threshold ECDSA isn't supported so announcements made this way don't have the ecdsa-v1 scheme.
+//! Nor are there DLC spec TLV announcements since the nodes can't yet sign dlc-v0 attestations.
+use super::{KeyId, Signer, SignerError};
+use crate::{seed::Seed, signing_ledger::SigningLedger};
+use async_trait::async_trait;
+use blake2::digest::VariableOutput;
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    ChaCha20Poly1305, Nonce,
+};
+use olivia_core::{
+    announce, AnnouncementSchemes, Event, EventId, Group, OracleEvent, OracleKeys, Outcome, Path,
+    PathRef, RawAnnouncement, RawOracleEvent, StampedOutcome,
+};
+use olivia_secp256k1::{
+    frost::{
+        self, FrostError, KeygenContribution, SecretPolynomial, SharedKey, SigningCommitment,
+        SigningNonce, SigningSession,
+    },
+    fun::{g, marker::*, Point, Scalar, G},
+    AttestScalar, Secp256k1, Signature,
+};
+use std::{collections::HashMap, convert::TryInto, sync::Mutex};
+use tokio::sync::{mpsc, oneshot};
+
+type TransportKey = Point<Normal, Public, NonZero>;
+
+enum NodeRequest {
+    Contribute {
+        context: String,
+    },
+    Shares {
+        context: String,
+    },
+    Finish {
+        context: String,
+        contributions: Vec<KeygenContribution>,
+        shares: Vec<Vec<u8>>,
+    },
+    Commit {
+        session: u64,
+    },
+    Sign {
+        session: u64,
+        key_context: String,
+        commitments: Vec<(u32, SigningCommitment)>,
+        oracle_event: RawOracleEvent<Secp256k1>,
+    },
+    Attest {
+        key_context: String,
+        nonce_contexts: Vec<String>,
+        outcome: Outcome,
+    },
+}
+
+enum NodeResponse {
+    Contribution(KeygenContribution),
+    Shares(Vec<Vec<u8>>),
+    Finished,
+    Commitment(SigningCommitment),
+    SignatureShare(Scalar<Public, Zero>),
+    PartialAttestScalars(Vec<Scalar<Public, Zero>>),
+}
+
+type Envelope = (
+    NodeRequest,
+    oneshot::Sender<Result<NodeResponse, SignerError>>,
+);
+
+fn secret_bytes(seed: &Seed) -> [u8; 32] {
+    let hash = seed.to_blake2b_var(32);
+    hash.finalize_boxed()
+        .as_ref()
+        .try_into()
+        .expect("asked for 32 bytes")
+}
+
+fn missing_share(context: &str) -> SignerError {
+    SignerError::MissingShare(context.to_owned())
+}
+
+/// A node holding one share of the oracle's keys
+pub struct FrostNode {
+    index: u32,
+    threshold: usize,
+    seed: Seed,
+    transport_secret: Scalar,
+    transport_keys: Vec<TransportKey>,
+    ledger: SigningLedger,
+    shares: HashMap<String, (SharedKey, Scalar<Secret, Zero>)>,
+    signing_nonces: HashMap<u64, SigningNonce>,
+}
+
+impl FrostNode {
+    fn transport_secret(seed: &Seed) -> Scalar {
+        Scalar::from_bytes_mod_order(secret_bytes(&seed.child(b"frost-transport-key")))
+            .mark::<NonZero>()
+            .expect("hash output will not be zero")
+    }
+
+    /// The key other nodes encrypt secret shares to this node with
+    pub fn transport_key(seed: &Seed) -> TransportKey {
+        g!({ Self::transport_secret(seed) } * G).mark::<Normal>()
+    }
+
+    /// `index` is the node's position in `transport_keys` plus one. `threshold` must be a majority
+    /// of the nodes.
+    pub fn new(
+        index: u32,
+        threshold: usize,
+        seed: Seed,
+        transport_keys: Vec<TransportKey>,
+        ledger: SigningLedger,
+    ) -> Result<Self, FrostError> {
+        frost::check_threshold(threshold, transport_keys.len())?;
+        Ok(Self {
+            index,
+            threshold,
+            transport_secret: Self::transport_secret(&seed),
+            seed,
+            transport_keys,
+            ledger,
+            shares: HashMap::new(),
+            signing_nonces: HashMap::new(),
+        })
+    }
+
+    /// Runs the node until every handle to it is dropped
+    pub fn spawn(mut self) -> FrostNodeHandle {
+        let (sender, mut receiver) = mpsc::channel::<Envelope>(16);
+        let index = self.index;
+        tokio::spawn(async move {
+            while let Some((request, reply)) = receiver.recv().await {
+                let _ = reply.send(self.handle(request));
+            }
+        });
+        FrostNodeHandle { index, sender }
+    }
+
+    fn polynomial(&self, context: &str) -> Result<SecretPolynomial, SignerError> {
+        let seed = self.seed.child(b"frost").child(context.as_bytes());
+        SecretPolynomial::from_coefficient_bytes(
+            (0..self.threshold)
+                .map(|i| secret_bytes(&seed.child(&[i as u8])))
+                .collect(),
+        )
+        .map_err(|e| SignerError::BadRequest(e.to_string()))
+    }
+
+    /// Secret shares are encrypted under a key that's unique to the DKG and the pair of nodes. A
+    /// DKG that runs again encrypts the same share so the fixed nonce is never reused for a
+    /// different message.
+    fn share_cipher(&self, context: &str, from: u32, to: u32) -> ChaCha20Poly1305 {
+        let peer = &self.transport_keys[(if from == self.index { to } else { from }) as usize - 1];
+        let shared = g!({ &self.transport_secret } * peer).mark::<Normal>();
+        let mut hash = blake2::VarBlake2b::new(32).expect("32 is a valid length");
+        blake2::digest::Update::update(&mut hash, shared.to_bytes());
+        blake2::digest::Update::update(&mut hash, context.as_bytes());
+        blake2::digest::Update::update(&mut hash, from.to_be_bytes());
+        blake2::digest::Update::update(&mut hash, to.to_be_bytes());
+        let key: [u8; 32] = hash.finalize_boxed().as_ref().try_into().unwrap();
+        ChaCha20Poly1305::new(&key.into())
+    }
+
+    fn share(&self, context: &str) -> Result<&(SharedKey, Scalar<Secret, Zero>), SignerError> {
+        self.shares
+            .get(context)
+            .ok_or_else(|| missing_share(context))
+    }
+
+    fn handle(&mut self, request: NodeRequest) -> Result<NodeResponse, SignerError> {
+        let bad_request = |e: &dyn std::fmt::Display| SignerError::BadRequest(e.to_string());
+        match request {
+            NodeRequest::Contribute { context } => Ok(NodeResponse::Contribution(
+                self.polynomial(&context)?
+                    .contribution(context.as_bytes(), self.index),
+            )),
+            NodeRequest::Shares { context } => {
+                let polynomial = self.polynomial(&context)?;
+                let shares = (1..=self.transport_keys.len() as u32)
+                    .map(|to| {
+                        self.share_cipher(&context, self.index, to)
+                            .encrypt(
+                                Nonce::from_slice(&[0u8; 12]),
+                                &polynomial.share_for(to).to_bytes()[..],
+                            )
+                            .expect("encrypting 32 bytes can't fail")
+                    })
+                    .collect();
+                Ok(NodeResponse::Shares(shares))
+            }
+            NodeRequest::Finish {
+                context,
+                contributions,
+                shares,
+            } => {
+                let own = self
+                    .polynomial(&context)?
+                    .contribution(context.as_bytes(), self.index);
+                if contributions.get(self.index as usize - 1) != Some(&own) {
+                    return Err(SignerError::BadRequest(
+                        "our own contribution was changed".into(),
+                    ));
+                }
+                let key = SharedKey::new(context.as_bytes(), contributions)
+                    .map_err(|e| bad_request(&e))?;
+                let shares = shares
+                    .iter()
+                    .enumerate()
+                    .map(|(from, ciphertext)| {
+                        let plaintext = self
+                            .share_cipher(&context, from as u32 + 1, self.index)
+                            .decrypt(Nonce::from_slice(&[0u8; 12]), &ciphertext[..])
+                            .map_err(|_| {
+                                SignerError::BadRequest(format!(
+                                    "unable to decrypt share from node {}",
+                                    from + 1
+                                ))
+                            })?;
+                        plaintext
+                            .try_into()
+                            .ok()
+                            .and_then(Scalar::from_bytes)
+                            .ok_or_else(|| SignerError::BadRequest("invalid share".into()))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let secret_share = key
+                    .secret_share(self.index, &shares)
+                    .map_err(|e| bad_request(&e))?;
+                self.shares.insert(context, (key, secret_share));
+                Ok(NodeResponse::Finished)
+            }
+            NodeRequest::Commit { session } => {
+                let nonce = SigningNonce::new(rand::random(), rand::random());
+                let commitment = nonce.commitment();
+                self.signing_nonces.insert(session, nonce);
+                Ok(NodeResponse::Commitment(commitment))
+            }
+            NodeRequest::Sign {
+                session,
+                key_context,
+                commitments,
+                oracle_event,
+            } => {
+                // the nonce is removed whatever happens so it's never used twice
+                let nonce = self
+                    .signing_nonces
+                    .remove(&session)
+                    .ok_or_else(|| SignerError::BadRequest("unknown signing session".into()))?;
+                let (key, secret_share) = self.share(&key_context)?;
+                if !commitments.contains(&(self.index, nonce.commitment())) {
+                    return Err(SignerError::BadRequest(
+                        "our signing commitment is missing".into(),
+                    ));
+                }
+                let session = SigningSession::new(key, commitments, &oracle_event)
+                    .map_err(|e| bad_request(&e))?;
+                let signature_share = session
+                    .sign(self.index, secret_share, nonce)
+                    .expect("checked we are one of the signers");
+                Ok(NodeResponse::SignatureShare(signature_share))
+            }
+            NodeRequest::Attest {
+                key_context,
+                nonce_contexts,
+                outcome,
+            } => {
+                let indexes = outcome.attestation_indexes();
+                if indexes.len() != nonce_contexts.len() {
+                    return Err(SignerError::BadRequest(
+                        "wrong number of nonces for the outcome".into(),
+                    ));
+                }
+                let key_share = self.share(&key_context)?.1.clone();
+                let nonces = nonce_contexts
+                    .iter()
+                    .map(|context| self.share(context).cloned())
+                    .collect::<Result<Vec<_>, _>>()?;
+                let ledger_nonce = nonces
+                    .first()
+                    .map(|(nonce, _)| nonce.public_nonce().to_string())
+                    .unwrap_or_default();
+                self.ledger
+                    .record(&outcome.id, &ledger_nonce, &outcome.outcome_string())?;
+                Ok(NodeResponse::PartialAttestScalars(
+                    indexes
+                        .iter()
+                        .zip(&nonces)
+                        .map(|(index, (_, nonce_share))| {
+                            frost::partial_attest_scalar(&key_share, nonce_share, *index)
+                        })
+                        .collect(),
+                ))
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct FrostNodeHandle {
+    index: u32,
+    sender: mpsc::Sender<Envelope>,
+}
+
+impl FrostNodeHandle {
+    async fn request(&self, request: NodeRequest) -> Result<NodeResponse, SignerError> {
+        let unreachable = || SignerError::Unreachable(format!("frost node {}", self.index));
+        let (reply, response) = oneshot::channel();
+        self.sender
+            .send((request, reply))
+            .await
+            .map_err(|_| unreachable())?;
+        response.await.map_err(|_| unreachable())?
+    }
+}
+
+fn unexpected(node: u32) -> SignerError {
+    SignerError::BadRequest(format!("unexpected response from frost node {}", node))
+}
+
+/// Signs for the oracle by coordinating [`FrostNode`]s
+pub struct FrostSigner {
+    nodes: Vec<FrostNodeHandle>,
+    threshold: usize,
+    keys: Mutex<HashMap<String, SharedKey>>,
+}
+
+impl FrostSigner {
+    /// `nodes` must be in order of their index and `threshold` must be a majority of them
+    pub fn new(nodes: Vec<FrostNodeHandle>, threshold: usize) -> Result<Self, FrostError> {
+        frost::check_threshold(threshold, nodes.len())?;
+        Ok(Self {
+            nodes,
+            threshold,
+            keys: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Runs a DKG between all of the nodes
+    async fn run_dkg(&self, context: &str) -> Result<SharedKey, SignerError> {
+        let mut contributions = vec![];
+        let mut shares = vec![];
+        for node in &self.nodes {
+            match node
+                .request(NodeRequest::Contribute {
+                    context: context.into(),
+                })
+                .await?
+            {
+                NodeResponse::Contribution(contribution) => contributions.push(contribution),
+                _ => return Err(unexpected(node.index)),
+            }
+            match node
+                .request(NodeRequest::Shares {
+                    context: context.into(),
+                })
+                .await?
+            {
+                NodeResponse::Shares(node_shares) if node_shares.len() == self.nodes.len() => {
+                    shares.push(node_shares)
+                }
+                _ => return Err(unexpected(node.index)),
+            }
+        }
+        let key = SharedKey::new(context.as_bytes(), contributions.clone())
+            .map_err(|e| SignerError::BadRequest(e.to_string()))?;
+        if key.threshold() != self.threshold {
+            return Err(SignerError::BadRequest(format!(
+                "nodes are using threshold {} instead of {}",
+                key.threshold(),
+                self.threshold
+            )));
+        }
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            let shares = shares
+                .iter()
+                .map(|from| from[i].clone())
+                .collect::<Vec<_>>();
+            match node
+                .request(NodeRequest::Finish {
+                    context: context.into(),
+                    contributions: contributions.clone(),
+                    shares,
+                })
+                .await?
+            {
+                NodeResponse::Finished => {}
+                _ => return Err(unexpected(node.index)),
+            }
+        }
+        self.keys
+            .lock()
+            .unwrap()
+            .insert(context.to_owned(), key.clone());
+        Ok(key)
+    }
+
+    async fn shared_key(&self, context: &str) -> Result<SharedKey, SignerError> {
+        let existing = self.keys.lock().unwrap().get(context).cloned();
+        match existing {
+            Some(key) => Ok(key),
+            None => self.run_dkg(context).await,
+        }
+    }
+
+    async fn sign(
+        &self,
+        key_context: &str,
+        oracle_event: RawOracleEvent<Secp256k1>,
+    ) -> Result<Signature, SignerError> {
+        let key = self.shared_key(key_context).await?;
+        match self.try_sign(&key, key_context, &oracle_event).await {
+            // nodes forget their shares when they restart
+            Err(SignerError::MissingShare(_)) => {
+                let key = self.run_dkg(key_context).await?;
+                self.try_sign(&key, key_context, &oracle_event).await
+            }
+            result => result,
+        }
+    }
+
+    async fn try_sign(
+        &self,
+        key: &SharedKey,
+        key_context: &str,
+        oracle_event: &RawOracleEvent<Secp256k1>,
+    ) -> Result<Signature, SignerError> {
+        let session_id = rand::random::<u64>();
+        let mut signers = vec![];
+        let mut commitments = vec![];
+        for node in &self.nodes {
+            if signers.len() == self.threshold {
+                break;
+            }
+            if let Ok(NodeResponse::Commitment(commitment)) = node
+                .request(NodeRequest::Commit {
+                    session: session_id,
+                })
+                .await
+            {
+                signers.push(node);
+                commitments.push((node.index, commitment));
+            }
+        }
+        if signers.len() < self.threshold {
+            return Err(SignerError::Unreachable(format!(
+                "only {} frost nodes are available but {} are needed",
+                signers.len(),
+                self.threshold
+            )));
+        }
+
+        let session = SigningSession::new(key, commitments.clone(), oracle_event)
+            .map_err(|e| SignerError::BadRequest(e.to_string()))?;
+        let mut signature_shares = vec![];
+        for node in signers {
+            match node
+                .request(NodeRequest::Sign {
+                    session: session_id,
+                    key_context: key_context.into(),
+                    commitments: commitments.clone(),
+                    oracle_event: oracle_event.clone(),
+                })
+                .await?
+            {
+                NodeResponse::SignatureShare(share)
+                    if session.verify_signature_share(key, node.index, &share) =>
+                {
+                    signature_shares.push(share)
+                }
+                _ => return Err(unexpected(node.index)),
+            }
+        }
+        Ok(session.combine(&signature_shares))
+    }
+
+    fn announcement_context(epoch: u32) -> String {
+        format!("epoch-{}/announcement-key", epoch)
+    }
+
+    fn olivia_v1_context(epoch: u32, namespace: Option<&Path>) -> String {
+        match namespace {
+            Some(namespace) => format!("epoch-{}/olivia-v1-key{}", epoch, namespace),
+            None => format!("epoch-{}/olivia-v1-key", epoch),
+        }
+    }
+
+    fn olivia_v1_context_for(key_id: &KeyId, path: PathRef<'_>) -> String {
+        let namespace = key_id
+            .olivia_v1_namespaces
+            .iter()
+            .filter(|namespace| namespace.as_path_ref().is_parent_of(path))
+            .max_by_key(|namespace| namespace.as_str().len());
+        Self::olivia_v1_context(key_id.epoch, namespace)
+    }
+
+    fn nonce_contexts(key_id: &KeyId, event_id: &EventId) -> Vec<String> {
+        (0..event_id.n_nonces())
+            .map(|i| format!("epoch-{}/nonce/{}/{}", key_id.epoch, event_id, i))
+            .collect()
+    }
+
+    async fn oracle_event(
+        &self,
+        key_id: &KeyId,
+        event: Event,
+    ) -> Result<OracleEvent<Secp256k1>, SignerError> {
+        let mut nonces = vec![];
+        for context in Self::nonce_contexts(key_id, &event.id) {
+            nonces.push(self.shared_key(&context).await?.public_nonce());
+        }
+        Ok(OracleEvent {
+            event,
+            schemes: AnnouncementSchemes {
+                olivia_v1: match nonces.is_empty() {
+                    true => None,
+                    false => Some(announce::OliviaV1 { nonces }),
+                },
+                ecdsa_v1: None,
+                dlc_v0: None,
+            },
+        })
+    }
+
+    /// Asks the nodes for their partial attest scalars until `threshold` of them are valid.
+    async fn partial_attest_scalars(
+        &self,
+        key_context: &str,
+        nonce_contexts: &[String],
+        outcome: &Outcome,
+    ) -> Result<Vec<(u32, Vec<Scalar<Public, Zero>>)>, SignerError> {
+        let key = self.shared_key(key_context).await?;
+        let mut nonces = vec![];
+        for context in nonce_contexts {
+            nonces.push(self.shared_key(context).await?);
+        }
+        let indexes = outcome.attestation_indexes();
+
+        let mut partials = vec![];
+        for node in &self.nodes {
+            if partials.len() == self.threshold {
+                break;
+            }
+            let request = || NodeRequest::Attest {
+                key_context: key_context.into(),
+                nonce_contexts: nonce_contexts.to_vec(),
+                outcome: outcome.clone(),
+            };
+            let response = match node.request(request()).await {
+                Err(SignerError::MissingShare(_)) => {
+                    self.run_dkg(key_context).await?;
+                    for context in nonce_contexts {
+                        self.run_dkg(context).await?;
+                    }
+                    node.request(request()).await
+                }
+                response => response,
+            };
+            match response {
+                Ok(NodeResponse::PartialAttestScalars(scalars))
+                    if scalars.len() == indexes.len()
+                        && scalars.iter().zip(&indexes).zip(&nonces).all(
+                            |((partial, index), nonce)| {
+                                frost::verify_partial_attest_scalar(
+                                    &key, nonce, node.index, *index, partial,
+                                )
+                            },
+                        ) =>
+                {
+                    partials.push((node.index, scalars))
+                }
+                // a node refusing to equivocate means something is badly wrong
+                Err(e @ SignerError::Equivocation { .. }) => return Err(e),
+                _ => continue,
+            }
+        }
+        if partials.len() < self.threshold {
+            return Err(SignerError::Unreachable(format!(
+                "only {} frost nodes attested but {} are needed",
+                partials.len(),
+                self.threshold
+            )));
+        }
+        Ok(partials)
+    }
+}
+
+#[async_trait]
+impl Signer<Secp256k1> for FrostSigner {
+    async fn public_keys(&self, key_id: &KeyId) -> Result<OracleKeys<Secp256k1>, SignerError> {
+        let announcement = self
+            .shared_key(&Self::announcement_context(key_id.epoch))
+            .await?
+            .public_key();
+        let olivia_v1 = self
+            .shared_key(&Self::olivia_v1_context(key_id.epoch, None))
+            .await?
+            .public_key();
+        let mut olivia_v1_namespaces = std::collections::BTreeMap::new();
+        for namespace in &key_id.olivia_v1_namespaces {
+            let key = self
+                .shared_key(&Self::olivia_v1_context(key_id.epoch, Some(namespace)))
+                .await?;
+            olivia_v1_namespaces.insert(namespace.clone(), key.public_key());
+        }
+        Ok(OracleKeys {
+            announcement,
+            olivia_v1: Some(olivia_v1),
+            ecdsa_v1: None,
+            group: Secp256k1,
+            olivia_v1_namespaces,
+        })
+    }
+
+    async fn create_announcement(
+        &self,
+        key_id: &KeyId,
+        event: Event,
+    ) -> Result<RawAnnouncement<Secp256k1>, SignerError> {
+        let oracle_event = self.oracle_event(key_id, event).await?.encode_json();
+        let signature = self
+            .sign(
+                &Self::announcement_context(key_id.epoch),
+                oracle_event.clone(),
+            )
+            .await?;
+        Ok(RawAnnouncement {
+            oracle_event,
+            signature,
+        })
+    }
+
+    async fn create_tlv_announcement(
+        &self,
+        _key_id: &KeyId,
+        _event: Event,
+    ) -> Result<Option<RawAnnouncement<Secp256k1>>, SignerError> {
+        Ok(None)
+    }
+
+    async fn olivia_v1_scalars_for_event_outcome(
+        &self,
+        key_id: &KeyId,
+        stamped: &StampedOutcome,
+    ) -> Result<Vec<AttestScalar>, SignerError> {
+        let outcome = &stamped.outcome;
+        let key_context = Self::olivia_v1_context_for(key_id, outcome.id.path());
+        let nonce_contexts = Self::nonce_contexts(key_id, &outcome.id);
+        let partials = self
+            .partial_attest_scalars(&key_context, &nonce_contexts, outcome)
+            .await?;
+
+        let key = self.shared_key(&key_context).await?.public_key();
+        let mut scalars = vec![];
+        for (i, (index, context)) in outcome
+            .attestation_indexes()
+            .into_iter()
+            .zip(&nonce_contexts)
+            .enumerate()
+        {
+            let scalar = frost::combine_partial_attest_scalars(
+                &partials
+                    .iter()
+                    .map(|(node, scalars)| (*node, scalars[i]))
+                    .collect::<Vec<_>>(),
+            );
+            let nonce = self.shared_key(context).await?.public_nonce();
+            // Always verify the attestation before publishing it
+            if !Secp256k1::verify_attest_scalar(&key, &nonce, index, &scalar) {
+                return Err(SignerError::BadRequest(
+                    "combined attest scalar is invalid".into(),
+                ));
+            }
+            scalars.push(scalar);
+        }
+        Ok(scalars)
+    }
+
+    async fn ecdsa_sign_outcome(
+        &self,
+        _key_id: &KeyId,
+        _outcome: &Outcome,
+    ) -> Result<<Secp256k1 as Group>::EcdsaSignature, SignerError> {
+        Err(SignerError::BadRequest(
+            "ecdsa-v1 isn't supported by frost signers".into(),
+        ))
+    }
+
+    async fn dlc_v0_sign_outcome(
+        &self,
+        _key_id: &KeyId,
+        _outcome: &Outcome,
+    ) -> Result<Vec<Signature>, SignerError> {
+        Err(SignerError::BadRequest(
+            "dlc-v0 isn't supported by frost signers".into(),
+        ))
+    }
+}
+
+/// Starts `n` in-process nodes from `seeds` with in-memory ledgers and a signer coordinating
+/// them.
+pub fn spawn_local(
+    seeds: Vec<Seed>,
+    threshold: usize,
+) -> Result<(FrostSigner, Vec<FrostNodeHandle>), FrostError> {
+    frost::check_threshold(threshold, seeds.len())?;
+    let transport_keys = seeds
+        .iter()
+        .map(FrostNode::transport_key)
+        .collect::<Vec<_>>();
+    let nodes = seeds
+        .into_iter()
+        .enumerate()
+        .map(|(i, seed)| {
+            FrostNode::new(
+                i as u32 + 1,
+                threshold,
+                seed,
+                transport_keys.clone(),
+                SigningLedger::in_memory(),
+            )
+            .map(FrostNode::spawn)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((FrostSigner::new(nodes.clone(), threshold)?, nodes))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{db::in_memory::InMemory, Oracle};
+    use core::str::FromStr;
+    use std::sync::Arc;
+
+    fn seeds(n: u8) -> Vec<Seed> {
+        (1..=n).map(|i| Seed::new([i; 64])).collect()
+    }
+
+    #[test]
+    fn refuses_minority_thresholds() {
+        for (threshold, n) in [(1, 3), (2, 4)] {
+            assert!(matches!(
+                spawn_local(seeds(n), threshold),
+                Err(FrostError::InvalidThreshold { .. })
+            ));
+            let seeds = seeds(n);
+            let transport_keys = seeds.iter().map(FrostNode::transport_key).collect();
+            assert!(matches!(
+                FrostNode::new(
+                    1,
+                    threshold,
+                    seeds[0].clone(),
+                    transport_keys,
+                    SigningLedger::in_memory()
+                ),
+                Err(FrostError::InvalidThreshold { .. })
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn frost_oracle_event_lifecycle() {
+        let (signer, _) = spawn_local(seeds(3), 2).unwrap();
+        let db: Arc<dyn crate::db::Db<Secp256k1>> = Arc::new(InMemory::default());
+        let oracle = Oracle::new_with_signer(Arc::new(signer), db.clone(), vec![])
+            .await
+            .unwrap();
+        let public_keys = db.get_public_keys().await.unwrap().unwrap();
+        assert_eq!(public_keys.ecdsa_v1, None);
+
+        for (event_id, outcome) in [
+            ("/foo/red_blue.winner", "blue"),
+            ("/foo/bar.price?n=6&b=10", "4213"),
+        ] {
+            let event_id = EventId::from_str(event_id).unwrap();
+            oracle.add_event(event_id.clone().into()).await.unwrap();
+            let event = db.get_announced_event(&event_id).await.unwrap().unwrap();
+            let oracle_event = event
+                .announcement
+                .verify_against_id(&event_id, &public_keys.announcement)
+                .expect("threshold announcement signature is valid");
+            assert_eq!(event.tlv_announcement, None);
+
+            oracle
+                .complete_event(StampedOutcome {
+                    outcome: Outcome::try_from_id_and_outcome(event_id.clone(), outcome).unwrap(),
+                    time: olivia_core::chrono::Utc::now().naive_utc(),
+                })
+                .await
+                .unwrap();
+            let attestation = db
+                .get_announced_event(&event_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .attestation
+                .unwrap();
+            assert_eq!(attestation.schemes.ecdsa_v1, None);
+            assert_eq!(
+                attestation.verify_olivia_v1_attestation(&oracle_event, &public_keys),
+                Ok(())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn attests_without_a_node_and_nodes_refuse_to_equivocate() {
+        let seeds = seeds(3);
+        let transport_keys = seeds
+            .iter()
+            .map(FrostNode::transport_key)
+            .collect::<Vec<_>>();
+        let mut nodes = seeds
+            .iter()
+            .enumerate()
+            .map(|(i, seed)| {
+                FrostNode::new(
+                    i as u32 + 1,
+                    2,
+                    seed.clone(),
+                    transport_keys.clone(),
+                    SigningLedger::in_memory(),
+                )
+                .unwrap()
+                .spawn()
+            })
+            .collect::<Vec<_>>();
+        let key_id = KeyId {
+            epoch: 0,
+            olivia_v1_namespaces: vec![],
+        };
+        let event_id = EventId::from_str("/foo/red_blue.winner").unwrap();
+        let signer = FrostSigner::new(nodes.clone(), 2).unwrap();
+        let keys = signer.public_keys(&key_id).await.unwrap();
+        let announcement = signer
+            .create_announcement(&key_id, event_id.clone().into())
+            .await
+            .unwrap();
+        let oracle_event = announcement
+            .verify_against_id(&event_id, &keys.announcement)
+            .unwrap();
+
+        // the first node goes offline
+        let (offline, _) = mpsc::channel(1);
+        nodes[0].sender = offline;
+        let signer = FrostSigner {
+            keys: Mutex::new(signer.keys.lock().unwrap().clone()),
+            ..FrostSigner::new(nodes.clone(), 2).unwrap()
+        };
+        let stamped = |outcome: &str| StampedOutcome {
+            outcome: Outcome::try_from_id_and_outcome(event_id.clone(), outcome).unwrap(),
+            time: olivia_core::chrono::Utc::now().naive_utc(),
+        };
+        let scalars = signer
+            .olivia_v1_scalars_for_event_outcome(&key_id, &stamped("red"))
+            .await
+            .unwrap();
+        let attestation = olivia_core::Attestation::new(
+            "red".into(),
+            stamped("red").time,
+            olivia_core::AttestationSchemes {
+                olivia_v1: Some(olivia_core::attest::OliviaV1 { scalars }),
+                ecdsa_v1: None,
+                dlc_v0: None,
+            },
+        );
+        assert_eq!(
+            attestation.verify_olivia_v1_attestation(&oracle_event, &keys),
+            Ok(())
+        );
+
+        assert!(matches!(
+            signer
+                .olivia_v1_scalars_for_event_outcome(&key_id, &stamped("blue"))
+                .await,
+            Err(SignerError::Equivocation { .. })
+        ));
+    }
+}
@@ -0,0 +1,267 @@
+use crate::{
+    keychain::KeyChain,
+    seed::Seed,
+    signing_ledger::{LedgerError, SigningLedger},
+};
+use async_trait::async_trait;
+use olivia_core::{
+    Event, EventId, Group, OracleKeys, Outcome, Path, RawAnnouncement, StampedOutcome,
+};
+use std::sync::Mutex;
+pub mod frost;
+pub mod unix;
+
+/// Identifies which keys to sign with: the key epoch and the olivia-v1 namespaces it was created
+/// with.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct KeyId {
+    pub epoch: u32,
+    #[serde(default)]
+    pub olivia_v1_namespaces: Vec<Path>,
+}
+
+impl KeyId {
+    pub fn new<C: Group>(epoch: u32, keys: &OracleKeys<C>) -> Self {
+        Self {
+            epoch,
+            olivia_v1_namespaces: keys.olivia_v1_namespaces.keys().cloned().collect(),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SignerError {
+    #[error(
+        "refusing to attest to '{new}' for {event_id} since '{existing}' was already attested to"
+    )]
+    Equivocation {
+        event_id: EventId,
+        existing: String,
+        new: String,
+    },
+    #[error("signing ledger error: {0}")]
+    Ledger(String),
+    #[error("signer rejected the request: {0}")]
+    BadRequest(String),
+    #[error("unable to reach the signer: {0}")]
+    Unreachable(String),
+    #[error("signer has no share of the key for {0}")]
+    MissingShare(String),
+}
+
+/// Holds the oracle's secret keys and signs on its behalf
+#[async_trait]
+pub trait Signer<C: Group>: Send + Sync + 'static {
+    async fn public_keys(&self, key_id: &KeyId) -> Result<OracleKeys<C>, SignerError>;
+    async fn create_announcement(
+        &self,
+        key_id: &KeyId,
+        event: Event,
+    ) -> Result<RawAnnouncement<C>, SignerError>;
+    async fn create_tlv_announcement(
+        &self,
+        key_id: &KeyId,
+        event: Event,
+    ) -> Result<Option<RawAnnouncement<C>>, SignerError>;
+    async fn olivia_v1_scalars_for_event_outcome(
+        &self,
+        key_id: &KeyId,
+        stamped: &StampedOutcome,
+    ) -> Result<Vec<C::AttestScalar>, SignerError>;
+    async fn ecdsa_sign_outcome(
+        &self,
+        key_id: &KeyId,
+        outcome: &Outcome,
+    ) -> Result<C::EcdsaSignature, SignerError>;
+    async fn dlc_v0_sign_outcome(
+        &self,
+        key_id: &KeyId,
+        outcome: &Outcome,
+    ) -> Result<Vec<C::Signature>, SignerError>;
+}
+
+impl From<LedgerError> for SignerError {
+    fn from(e: LedgerError) -> Self {
+        match e {
+            LedgerError::Equivocation {
+                event_id,
+                existing,
+                new,
+                ..
+            } => SignerError::Equivocation {
+                event_id,
+                existing,
+                new,
+            },
+            e => SignerError::Ledger(e.to_string()),
+        }
+    }
+}
+
+/// Signs with keychains derived from the seed. Every outcome is recorded in the signing ledger
+/// first and it refuses to sign a different outcome for an event.
+pub struct KeyChainSigner {
+    seed: Seed,
+    ledger: Mutex<SigningLedger>,
+}
+
+impl KeyChainSigner {
+    /// A signer with an in-memory ledger
+    pub fn new(seed: Seed) -> Self {
+        Self::with_ledger(seed, SigningLedger::in_memory())
+    }
+
+    pub fn with_ledger(seed: Seed, ledger: SigningLedger) -> Self {
+        Self {
+            seed,
+            ledger: Mutex::new(ledger),
+        }
+    }
+
+    fn keychain<C: Group>(&self, key_id: &KeyId) -> KeyChain<C> {
+        KeyChain::for_epoch(
+            self.seed.clone(),
+            key_id.epoch,
+            &key_id.olivia_v1_namespaces,
+        )
+    }
+}
+
+#[async_trait]
+impl<C: Group> Signer<C> for KeyChainSigner {
+    async fn public_keys(&self, key_id: &KeyId) -> Result<OracleKeys<C>, SignerError> {
+        Ok(self.keychain::<C>(key_id).oracle_public_keys())
+    }
+
+    async fn create_announcement(
+        &self,
+        key_id: &KeyId,
+        event: Event,
+    ) -> Result<RawAnnouncement<C>, SignerError> {
+        Ok(self.keychain::<C>(key_id).create_announcement(event))
+    }
+
+    async fn create_tlv_announcement(
+        &self,
+        key_id: &KeyId,
+        event: Event,
+    ) -> Result<Option<RawAnnouncement<C>>, SignerError> {
+        Ok(self.keychain::<C>(key_id).create_tlv_announcement(event))
+    }
+
+    async fn olivia_v1_scalars_for_event_outcome(
+        &self,
+        key_id: &KeyId,
+        stamped: &StampedOutcome,
+    ) -> Result<Vec<C::AttestScalar>, SignerError> {
+        let mut ledger = self.ledger.lock().unwrap();
+        Ok(self
+            .keychain::<C>(key_id)
+            .olivia_v1_scalars_for_event_outcome(stamped, &mut ledger)?)
+    }
+
+    async fn ecdsa_sign_outcome(
+        &self,
+        key_id: &KeyId,
+        outcome: &Outcome,
+    ) -> Result<C::EcdsaSignature, SignerError> {
+        if !C::ECDSA_V1 {
+            return Err(SignerError::BadRequest(format!(
+                "ecdsa-v1 isn't supported on {}",
+                C::name()
+            )));
+        }
+        let mut ledger = self.ledger.lock().unwrap();
+        Ok(self
+            .keychain::<C>(key_id)
+            .ecdsa_sign_outcome(outcome, &mut ledger)?)
+    }
+
+    async fn dlc_v0_sign_outcome(
+        &self,
+        key_id: &KeyId,
+        outcome: &Outcome,
+    ) -> Result<Vec<C::Signature>, SignerError> {
+        let mut ledger = self.ledger.lock().unwrap();
+        Ok(self
+            .keychain::<C>(key_id)
+            .dlc_v0_sign_event_outcome(outcome, &mut ledger)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::str::FromStr;
+    use olivia_ristretto::Ristretto;
+    use olivia_secp256k1::Secp256k1;
+
+    #[tokio::test]
+    async fn refuses_to_equivocate() {
+        let signer = KeyChainSigner::new(Seed::new([42u8; 64]));
+        let key_id = KeyId {
+            epoch: 0,
+            olivia_v1_namespaces: vec![],
+        };
+        let id = EventId::from_str("/foo/red_blue.winner").unwrap();
+        let outcome =
+            |outcome: &str| Outcome::try_from_id_and_outcome(id.clone(), outcome).unwrap();
+
+        assert!(
+            Signer::<Secp256k1>::ecdsa_sign_outcome(&signer, &key_id, &outcome("red"))
+                .await
+                .is_ok()
+        );
+        // signing the same outcome again is fine
+        assert!(Signer::<Secp256k1>::olivia_v1_scalars_for_event_outcome(
+            &signer,
+            &key_id,
+            &StampedOutcome {
+                outcome: outcome("red"),
+                time: olivia_core::chrono::Utc::now().naive_utc(),
+            }
+        )
+        .await
+        .is_ok());
+        assert_eq!(
+            Signer::<Secp256k1>::ecdsa_sign_outcome(&signer, &key_id, &outcome("blue")).await,
+            Err(SignerError::Equivocation {
+                event_id: id.clone(),
+                existing: "red".into(),
+                new: "blue".into(),
+            })
+        );
+    }
+    #[tokio::test]
+    async fn ristretto_has_no_ecdsa_v1() {
+        let signer = KeyChainSigner::new(Seed::new([42u8; 64]));
+        let key_id = KeyId {
+            epoch: 0,
+            olivia_v1_namespaces: vec![],
+        };
+        let id = EventId::from_str("/foo/red_blue.winner").unwrap();
+        let outcome = Outcome::try_from_id_and_outcome(id.clone(), "red").unwrap();
+        let public_keys = Signer::<Ristretto>::public_keys(&signer, &key_id)
+            .await
+            .unwrap();
+        assert_eq!(public_keys.ecdsa_v1, None);
+        let announcement =
+            Signer::<Ristretto>::create_announcement(&signer, &key_id, id.clone().into())
+                .await
+                .unwrap();
+        assert_eq!(
+            announcement
+                .verify_against_id(&id, &public_keys.announcement)
+                .unwrap()
+                .schemes
+                .ecdsa_v1,
+            None
+        );
+        assert!(matches!(
+            Signer::<Ristretto>::ecdsa_sign_outcome(&signer, &key_id, &outcome).await,
+            Err(SignerError::BadRequest(_))
+        ));
+    }
+}
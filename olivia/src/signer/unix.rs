@@ -0,0 +1,310 @@
+//! A signer in another process reached over a Unix socket.
+//!
+//! Each request and response is a line of JSON. The response is the JSON of
+//! `Result<serde_json::Value, SignerError>`.
+use super::{KeyId, Signer, SignerError};
+use async_trait::async_trait;
+use core::convert::TryFrom;
+use olivia_core::{
+    Event, Group, OracleKeys, Outcome, RawAnnouncement, StampedOutcome, WireEventOutcome,
+};
+use serde::de::DeserializeOwned;
+use std::{path::PathBuf, sync::Arc};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+};
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case", tag = "method")]
+enum Request {
+    PublicKeys {
+        key_id: KeyId,
+    },
+    CreateAnnouncement {
+        key_id: KeyId,
+        event: Event,
+    },
+    CreateTlvAnnouncement {
+        key_id: KeyId,
+        event: Event,
+    },
+    OliviaV1Scalars {
+        key_id: KeyId,
+        outcome: WireEventOutcome,
+    },
+    EcdsaSignOutcome {
+        key_id: KeyId,
+        outcome: WireEventOutcome,
+    },
+    DlcV0SignOutcome {
+        key_id: KeyId,
+        outcome: WireEventOutcome,
+    },
+}
+
+fn wire_outcome(
+    outcome: &Outcome,
+    time: Option<olivia_core::chrono::NaiveDateTime>,
+) -> WireEventOutcome {
+    WireEventOutcome {
+        event_id: outcome.id.clone(),
+        outcome: outcome.outcome_string(),
+        time,
+    }
+}
+
+/// Talks to a signer served with [`serve`]
+pub struct UnixSigner {
+    path: PathBuf,
+}
+
+impl UnixSigner {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    async fn request<T: DeserializeOwned>(&self, request: Request) -> Result<T, SignerError> {
+        let unreachable = |e: std::io::Error| SignerError::Unreachable(e.to_string());
+        let stream = UnixStream::connect(&self.path).await.map_err(unreachable)?;
+        let (read, mut write) = stream.into_split();
+        let mut line = serde_json::to_vec(&request).unwrap();
+        line.push(b'\n');
+        write.write_all(&line).await.map_err(unreachable)?;
+
+        let mut response = String::new();
+        BufReader::new(read)
+            .read_line(&mut response)
+            .await
+            .map_err(unreachable)?;
+        let response: Result<serde_json::Value, SignerError> = serde_json::from_str(&response)
+            .map_err(|e| SignerError::Unreachable(format!("invalid response: {}", e)))?;
+        serde_json::from_value(response?)
+            .map_err(|e| SignerError::Unreachable(format!("invalid response: {}", e)))
+    }
+}
+
+#[async_trait]
+impl<C: Group> Signer<C> for UnixSigner {
+    async fn public_keys(&self, key_id: &KeyId) -> Result<OracleKeys<C>, SignerError> {
+        self.request(Request::PublicKeys {
+            key_id: key_id.clone(),
+        })
+        .await
+    }
+
+    async fn create_announcement(
+        &self,
+        key_id: &KeyId,
+        event: Event,
+    ) -> Result<RawAnnouncement<C>, SignerError> {
+        self.request(Request::CreateAnnouncement {
+            key_id: key_id.clone(),
+            event,
+        })
+        .await
+    }
+
+    async fn create_tlv_announcement(
+        &self,
+        key_id: &KeyId,
+        event: Event,
+    ) -> Result<Option<RawAnnouncement<C>>, SignerError> {
+        self.request(Request::CreateTlvAnnouncement {
+            key_id: key_id.clone(),
+            event,
+        })
+        .await
+    }
+
+    async fn olivia_v1_scalars_for_event_outcome(
+        &self,
+        key_id: &KeyId,
+        stamped: &StampedOutcome,
+    ) -> Result<Vec<C::AttestScalar>, SignerError> {
+        self.request(Request::OliviaV1Scalars {
+            key_id: key_id.clone(),
+            outcome: wire_outcome(&stamped.outcome, Some(stamped.time)),
+        })
+        .await
+    }
+
+    async fn ecdsa_sign_outcome(
+        &self,
+        key_id: &KeyId,
+        outcome: &Outcome,
+    ) -> Result<C::EcdsaSignature, SignerError> {
+        self.request(Request::EcdsaSignOutcome {
+            key_id: key_id.clone(),
+            outcome: wire_outcome(outcome, None),
+        })
+        .await
+    }
+
+    async fn dlc_v0_sign_outcome(
+        &self,
+        key_id: &KeyId,
+        outcome: &Outcome,
+    ) -> Result<Vec<C::Signature>, SignerError> {
+        self.request(Request::DlcV0SignOutcome {
+            key_id: key_id.clone(),
+            outcome: wire_outcome(outcome, None),
+        })
+        .await
+    }
+}
+
+/// Answers requests from [`UnixSigner`]s with `signer`
+pub async fn serve<C: Group>(
+    listener: UnixListener,
+    signer: Arc<dyn Signer<C>>,
+    logger: slog::Logger,
+) -> anyhow::Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let signer = signer.clone();
+        let logger = logger.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, signer, &logger).await {
+                debug!(logger, "connection closed"; "error" => e.to_string());
+            }
+        });
+    }
+}
+
+async fn handle_connection<C: Group>(
+    stream: UnixStream,
+    signer: Arc<dyn Signer<C>>,
+    logger: &slog::Logger,
+) -> std::io::Result<()> {
+    let (read, mut write) = stream.into_split();
+    let mut lines = BufReader::new(read).lines();
+    while let Some(line) = lines.next_line().await? {
+        let response = handle_request(signer.as_ref(), &line).await;
+        if let Err(e) = &response {
+            warn!(logger, "refused request"; "error" => e.to_string());
+        }
+        let mut response = serde_json::to_vec(&response).unwrap();
+        response.push(b'\n');
+        write.write_all(&response).await?;
+    }
+    Ok(())
+}
+
+async fn handle_request<C: Group>(
+    signer: &dyn Signer<C>,
+    line: &str,
+) -> Result<serde_json::Value, SignerError> {
+    let bad_request = |e: &dyn std::fmt::Display| SignerError::BadRequest(e.to_string());
+    let request: Request = serde_json::from_str(line).map_err(|e| bad_request(&e))?;
+    let value = match request {
+        Request::PublicKeys { key_id } => serde_json::to_value(signer.public_keys(&key_id).await?),
+        Request::CreateAnnouncement { key_id, event } => {
+            serde_json::to_value(signer.create_announcement(&key_id, event).await?)
+        }
+        Request::CreateTlvAnnouncement { key_id, event } => {
+            serde_json::to_value(signer.create_tlv_announcement(&key_id, event).await?)
+        }
+        Request::OliviaV1Scalars { key_id, outcome } => {
+            let stamped = StampedOutcome::try_from(outcome).map_err(|e| bad_request(&e))?;
+            serde_json::to_value(
+                signer
+                    .olivia_v1_scalars_for_event_outcome(&key_id, &stamped)
+                    .await?,
+            )
+        }
+        Request::EcdsaSignOutcome { key_id, outcome } => {
+            let outcome = Outcome::try_from_id_and_outcome(outcome.event_id, &outcome.outcome)
+                .map_err(|e| bad_request(&e))?;
+            serde_json::to_value(signer.ecdsa_sign_outcome(&key_id, &outcome).await?)
+        }
+        Request::DlcV0SignOutcome { key_id, outcome } => {
+            let outcome = Outcome::try_from_id_and_outcome(outcome.event_id, &outcome.outcome)
+                .map_err(|e| bad_request(&e))?;
+            serde_json::to_value(signer.dlc_v0_sign_outcome(&key_id, &outcome).await?)
+        }
+    };
+    Ok(value.expect("signer responses can always be serialized"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{seed::Seed, signer::KeyChainSigner};
+    use core::str::FromStr;
+    use olivia_core::EventId;
+    use olivia_secp256k1::Secp256k1;
+
+    #[tokio::test]
+    async fn unix_signer_matches_local_signer() {
+        let path =
+            std::env::temp_dir().join(format!("olivia-signer-{}.sock", rand::random::<u64>()));
+        let local = Arc::new(KeyChainSigner::new(Seed::new([42u8; 64])));
+        let listener = UnixListener::bind(&path).unwrap();
+        tokio::spawn(serve::<Secp256k1>(
+            listener,
+            local.clone(),
+            slog::Logger::root(slog::Discard, o!()),
+        ));
+        let remote = UnixSigner::new(path.clone());
+        let key_id = KeyId {
+            epoch: 1,
+            olivia_v1_namespaces: vec![olivia_core::Path::from_str("/foo").unwrap()],
+        };
+
+        let public_keys = Signer::<Secp256k1>::public_keys(&remote, &key_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            public_keys,
+            Signer::<Secp256k1>::public_keys(local.as_ref(), &key_id)
+                .await
+                .unwrap()
+        );
+
+        let id = EventId::from_str("/foo/red_blue.winner").unwrap();
+        let announcement =
+            Signer::<Secp256k1>::create_announcement(&remote, &key_id, id.clone().into())
+                .await
+                .unwrap();
+        let oracle_event = announcement
+            .verify_against_id(&id, &public_keys.announcement)
+            .unwrap();
+
+        let stamped = |outcome: &str| StampedOutcome {
+            outcome: Outcome::try_from_id_and_outcome(id.clone(), outcome).unwrap(),
+            time: olivia_core::chrono::Utc::now().naive_utc(),
+        };
+        let scalars = Signer::<Secp256k1>::olivia_v1_scalars_for_event_outcome(
+            &remote,
+            &key_id,
+            &stamped("red"),
+        )
+        .await
+        .unwrap();
+        let attestation = olivia_core::Attestation::new(
+            "red".into(),
+            stamped("red").time,
+            olivia_core::AttestationSchemes {
+                olivia_v1: Some(olivia_core::attest::OliviaV1 { scalars }),
+                ecdsa_v1: None,
+                dlc_v0: None,
+            },
+        );
+        assert_eq!(
+            attestation.verify_olivia_v1_attestation(&oracle_event, &public_keys),
+            Ok(())
+        );
+
+        assert!(matches!(
+            Signer::<Secp256k1>::olivia_v1_scalars_for_event_outcome(
+                &remote,
+                &key_id,
+                &stamped("blue")
+            )
+            .await,
+            Err(SignerError::Equivocation { .. })
+        ));
+        let _ = std::fs::remove_file(path);
+    }
+}
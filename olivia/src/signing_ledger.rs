@@ -0,0 +1,229 @@
+//! An append-only record of every outcome the oracle has signed.
+//!
+//! Revealing attestation scalars for two different outcomes under the same nonce leaks the
+//! olivia-v1 secret key so the keychain records the outcome here before revealing anything and
+//! refuses to sign for a different one afterwards. Unlike the database this can't be rolled back
+//! by restoring a backup and it's shared by every process that opens the same file.
+//!
+//! Each line of the file is the JSON of an [`Entry`].
+use fs2::FileExt;
+use olivia_core::EventId;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum LedgerError {
+    #[error("'{existing}' was already signed for {event_id} with nonce {nonce}")]
+    Equivocation {
+        event_id: EventId,
+        nonce: String,
+        existing: String,
+        new: String,
+    },
+    #[error("signing ledger is corrupted at line {line}: {error}")]
+    Corrupt { line: usize, error: String },
+    #[error("unable to access signing ledger: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Entry {
+    pub event_id: EventId,
+    pub nonce: String,
+    pub outcome: String,
+}
+
+pub struct SigningLedger {
+    file: Option<File>,
+    /// How much of the file has been read into `entries`
+    read_to: u64,
+    lines_read: usize,
+    entries: HashMap<(EventId, String), String>,
+}
+
+impl SigningLedger {
+    /// A ledger that forgets everything when the process exits
+    pub fn in_memory() -> Self {
+        Self {
+            file: None,
+            read_to: 0,
+            lines_read: 0,
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn open(path: &Path) -> Result<Self, LedgerError> {
+        let mut options = std::fs::OpenOptions::new();
+        options.read(true).append(true).create(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let file = options.open(path)?;
+        let mut ledger = Self {
+            file: Some(file),
+            ..Self::in_memory()
+        };
+        let file = ledger.file.as_ref().unwrap();
+        file.lock_exclusive()?;
+        let result = ledger.catch_up();
+        ledger.file.as_ref().unwrap().unlock()?;
+        result?;
+        Ok(ledger)
+    }
+
+    /// Reads entries other processes appended since we last looked. A line that was cut short by a
+    /// crash is removed since its outcome was never signed.
+    fn catch_up(&mut self) -> Result<(), LedgerError> {
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => return Ok(()),
+        };
+        file.seek(SeekFrom::Start(self.read_to))?;
+        let mut new = Vec::new();
+        file.read_to_end(&mut new)?;
+
+        let complete = new
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        if complete < new.len() {
+            file.set_len(self.read_to + complete as u64)?;
+        }
+
+        for line in new[..complete].split(|byte| *byte == b'\n') {
+            if line.is_empty() {
+                continue;
+            }
+            self.lines_read += 1;
+            let entry: Entry = serde_json::from_slice(line).map_err(|e| LedgerError::Corrupt {
+                line: self.lines_read,
+                error: e.to_string(),
+            })?;
+            self.entries
+                .entry((entry.event_id, entry.nonce))
+                .or_insert(entry.outcome);
+        }
+        self.read_to += complete as u64;
+        Ok(())
+    }
+
+    /// Records that `outcome` is about to be signed for the event with `nonce`. Only returns once
+    /// the entry is on disk. Recording the same outcome again is fine.
+    pub fn record(
+        &mut self,
+        event_id: &EventId,
+        nonce: &str,
+        outcome: &str,
+    ) -> Result<(), LedgerError> {
+        if let Some(file) = &self.file {
+            file.lock_exclusive()?;
+        }
+        let result = self.check_and_append(event_id, nonce, outcome);
+        if let Some(file) = &self.file {
+            file.unlock()?;
+        }
+        result
+    }
+
+    fn check_and_append(
+        &mut self,
+        event_id: &EventId,
+        nonce: &str,
+        outcome: &str,
+    ) -> Result<(), LedgerError> {
+        self.catch_up()?;
+        let key = (event_id.clone(), nonce.to_owned());
+        match self.entries.get(&key) {
+            Some(existing) if existing == outcome => return Ok(()),
+            Some(existing) => {
+                return Err(LedgerError::Equivocation {
+                    event_id: event_id.clone(),
+                    nonce: nonce.to_owned(),
+                    existing: existing.clone(),
+                    new: outcome.to_owned(),
+                })
+            }
+            None => {}
+        }
+
+        if let Some(file) = &mut self.file {
+            let mut line = serde_json::to_vec(&Entry {
+                event_id: event_id.clone(),
+                nonce: nonce.to_owned(),
+                outcome: outcome.to_owned(),
+            })
+            .unwrap();
+            line.push(b'\n');
+            file.write_all(&line)?;
+            file.sync_data()?;
+            self.read_to += line.len() as u64;
+            self.lines_read += 1;
+        }
+        self.entries.insert(key, outcome.to_owned());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::str::FromStr;
+
+    #[test]
+    fn persists_across_opens() {
+        let path =
+            std::env::temp_dir().join(format!("olivia-ledger-{}.jsonl", rand::random::<u64>()));
+        let id = EventId::from_str("/foo/red_blue.winner").unwrap();
+        let mut ledger = SigningLedger::open(&path).unwrap();
+        ledger.record(&id, "nonce-1", "red").unwrap();
+        ledger.record(&id, "nonce-1", "red").unwrap();
+        // a different nonce can't leak the key
+        ledger.record(&id, "nonce-2", "blue").unwrap();
+
+        // another process with the same ledger
+        let mut other = SigningLedger::open(&path).unwrap();
+        assert!(matches!(
+            other.record(&id, "nonce-1", "blue"),
+            Err(LedgerError::Equivocation { .. })
+        ));
+        other
+            .record(
+                &EventId::from_str("/foo/green_blue.winner").unwrap(),
+                "nonce-3",
+                "green",
+            )
+            .unwrap();
+        // entries appended by other processes are seen before recording
+        assert!(ledger
+            .record(
+                &EventId::from_str("/foo/green_blue.winner").unwrap(),
+                "nonce-3",
+                "blue"
+            )
+            .is_err());
+
+        // a line cut short by a crash is dropped
+        {
+            let mut file = std::fs::OpenOptions::new()
+                .append(true)
+                .open(&path)
+                .unwrap();
+            file.write_all(b"{\"event-id\":\"/foo/red_blue.win")
+                .unwrap();
+        }
+        let mut reopened = SigningLedger::open(&path).unwrap();
+        assert!(reopened.record(&id, "nonce-1", "blue").is_err());
+        reopened.record(&id, "nonce-4", "blue").unwrap();
+        assert!(SigningLedger::open(&path).is_ok());
+
+        let _ = std::fs::remove_file(path);
+    }
+}
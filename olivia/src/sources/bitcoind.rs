@@ -0,0 +1,473 @@
+//! Attests to events about bitcoin blocks using a bitcoind JSON-RPC.
+//!
+//! Events are relative to where the source is mounted (e.g. `/bitcoin/block`):
+//!
+//! - `/<height>.occur`: the block has been mined.
+//! - `/<height>.before`: the block was mined by the event's deadline according to the time in its
+//!   header. Don't attest to these with the `deadline` source as well since it would attest `false`
+//!   to blocks mined before the deadline that don't have enough confirmations yet.
+//! - `/<height>/<field>.price` (or a predicate on it): a number from the block where `<field>` is
+//!   one of `time`, `median_time`, `difficulty` or `median_fee`.
+use crate::{
+    db::{DbReadEvent, EventQuery, PrefixedDb},
+    sources::Update,
+};
+use anyhow::anyhow;
+use hyper::{client::HttpConnector, header, Body, Client, Method, Request, Uri};
+use olivia_core::{chrono, Event, EventId, EventKind, Outcome, StampedOutcome};
+use serde::de::DeserializeOwned;
+use std::{collections::HashMap, str::FromStr, time::Duration};
+use tokio::{sync::oneshot, time};
+use tokio_stream as stream;
+
+pub struct BitcoindRpc {
+    client: Client<HttpConnector>,
+    url: Uri,
+    auth: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct BlockHeader {
+    time: u64,
+    mediantime: u64,
+    difficulty: f64,
+}
+
+#[derive(Deserialize)]
+struct BlockStats {
+    medianfee: u64,
+}
+
+/// The data about a block that can be attested to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockData {
+    pub time: u64,
+    pub median_time: u64,
+    pub difficulty: f64,
+    /// Only fetched when it's needed since `getblockstats` doesn't work for blocks a pruned node
+    /// no longer has.
+    pub median_fee: Option<u64>,
+}
+
+impl BitcoindRpc {
+    pub fn new(url: &str, user: Option<&str>, password: Option<&str>) -> anyhow::Result<Self> {
+        Ok(Self {
+            client: Client::new(),
+            url: Uri::from_str(url)?,
+            auth: user.map(|user| {
+                format!(
+                    "Basic {}",
+                    base64::encode(format!("{}:{}", user, password.unwrap_or("")))
+                )
+            }),
+        })
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> anyhow::Result<T> {
+        let body = serde_json::json!({
+            "jsonrpc": "1.0",
+            "id": "olivia",
+            "method": method,
+            "params": params,
+        });
+        let mut request = Request::builder()
+            .method(Method::POST)
+            .uri(self.url.clone())
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(auth) = &self.auth {
+            request = request.header(header::AUTHORIZATION, auth);
+        }
+        let response = self
+            .client
+            .request(request.body(Body::from(body.to_string()))?)
+            .await?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        // bitcoind responds with an error status for RPC errors but still explains them in the body
+        let response = serde_json::from_slice::<RpcResponse<T>>(&body)
+            .map_err(|_| anyhow!("{} returned {} to {}", self.url, status, method))?;
+        match (response.result, response.error) {
+            (_, Some(RpcError { code, message })) => {
+                Err(anyhow!("{} failed with {}: {}", method, code, message))
+            }
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(anyhow!("{} returned no result", method)),
+        }
+    }
+
+    pub async fn block_count(&self) -> anyhow::Result<u64> {
+        self.call("getblockcount", serde_json::json!([])).await
+    }
+
+    pub async fn block_data(&self, height: u64, median_fee: bool) -> anyhow::Result<BlockData> {
+        let hash: String = self
+            .call("getblockhash", serde_json::json!([height]))
+            .await?;
+        let header: BlockHeader = self
+            .call("getblockheader", serde_json::json!([hash]))
+            .await?;
+        let median_fee = match median_fee {
+            true => Some(
+                self.call::<BlockStats>(
+                    "getblockstats",
+                    serde_json::json!([height, ["medianfee"]]),
+                )
+                .await?
+                .medianfee,
+            ),
+            false => None,
+        };
+        Ok(BlockData {
+            time: header.time,
+            median_time: header.mediantime,
+            difficulty: header.difficulty,
+            median_fee,
+        })
+    }
+}
+
+/// The height of the block the event is about.
+fn event_height(id: &EventId) -> Option<u64> {
+    let height = id.path().segments().next()?;
+    // don't allow things like "08" so each block has a single path
+    u64::from_str(height)
+        .ok()
+        .filter(|parsed| parsed.to_string() == height)
+}
+
+/// Whether the event needs the block's median fee.
+fn needs_median_fee(id: &EventId) -> bool {
+    id.path().segments().nth(1) == Some("median_fee")
+}
+
+/// The outcome of the event according to the block data or `None` if it's not an event about the
+/// block we know how to attest to.
+pub fn block_outcome(event: &Event, block: &BlockData) -> Option<Outcome> {
+    let id = &event.id;
+    let segments = id.path().segments().collect::<Vec<_>>();
+    let kind = id.event_kind();
+    let number = |field: &str| {
+        Some(match field {
+            "time" => block.time.to_string(),
+            "median_time" => block.median_time.to_string(),
+            "median_fee" => block.median_fee?.to_string(),
+            "difficulty" => format!("{:.*}", kind.precision() as usize, block.difficulty),
+            _ => return None,
+        })
+    };
+    match (&segments[..], kind.clone()) {
+        ([_], EventKind::SingleOccurrence) => {
+            Outcome::try_from_id_and_outcome(id.clone(), "true").ok()
+        }
+        ([_], EventKind::Deadline) => {
+            let deadline = event.expected_outcome_time?.timestamp();
+            let before = i64::try_from(block.time).ok()? <= deadline;
+            Outcome::try_from_id_and_outcome(id.clone(), &before.to_string()).ok()
+        }
+        ([_, field], EventKind::Price { .. }) => {
+            Outcome::try_from_id_and_outcome(id.clone(), &number(field)?).ok()
+        }
+        ([_, field], EventKind::Predicate { inner, predicate })
+            if matches!(*inner, EventKind::Price { .. }) =>
+        {
+            let inner =
+                Outcome::try_from_id_and_outcome(id.replace_kind(*inner), &number(field)?).ok()?;
+            Some(Outcome {
+                id: id.clone(),
+                value: predicate.predicate_outcome(&inner.outcome_string()),
+                void: false,
+            })
+        }
+        _ => None,
+    }
+}
+
+pub struct BitcoindOutcomeStream {
+    pub db: PrefixedDb,
+    pub rpc: BitcoindRpc,
+    pub logger: slog::Logger,
+    /// How many blocks deep a block must be before events about it are attested to.
+    pub confirmations: u32,
+    pub poll_interval: Duration,
+}
+
+impl BitcoindOutcomeStream {
+    pub fn start(self) -> impl stream::Stream<Item = Update<StampedOutcome>> {
+        let BitcoindOutcomeStream {
+            db,
+            rpc,
+            logger,
+            confirmations,
+            poll_interval,
+        } = self;
+        async_stream::stream! {
+            loop {
+                let tip = match rpc.block_count().await {
+                    Ok(tip) => tip,
+                    Err(e) => {
+                        error!(logger, "failed to get block count from bitcoind"; "error" => e.to_string());
+                        time::sleep(poll_interval).await;
+                        continue;
+                    }
+                };
+                let events = match db.query_events(EventQuery {
+                    attested: Some(false),
+                    ..Default::default()
+                }).await {
+                    Ok(events) => events,
+                    Err(e) => {
+                        crit!(logger, "DB error during bitcoind outcome stream"; "error" => e.to_string());
+                        time::sleep(poll_interval).await;
+                        continue;
+                    }
+                };
+
+                let mut blocks = HashMap::<u64, BlockData>::new();
+                for event in events {
+                    let height = match event_height(&event.id) {
+                        Some(height) if height + (confirmations.max(1) as u64) <= tip + 1 => height,
+                        _ => continue,
+                    };
+                    let median_fee = needs_median_fee(&event.id);
+                    if blocks.get(&height).map_or(true, |block| median_fee && block.median_fee.is_none()) {
+                        match rpc.block_data(height, median_fee).await {
+                            Ok(block) => { blocks.insert(height, block); },
+                            Err(e) => {
+                                error!(logger, "failed to get block from bitcoind"; "height" => height, "error" => e.to_string());
+                                continue;
+                            }
+                        }
+                    }
+                    let outcome = match block_outcome(&event, &blocks[&height]) {
+                        Some(outcome) => outcome,
+                        None => {
+                            debug!(logger, "unable to attest to event"; "id" => event.id.as_str());
+                            continue;
+                        }
+                    };
+
+                    let (sender, waiting) = oneshot::channel();
+                    yield Update {
+                        update: StampedOutcome {
+                            outcome,
+                            time: chrono::Utc::now().naive_utc(),
+                        },
+                        processed_notifier: Some(sender),
+                    };
+
+                    if let Err(_) | Ok(true) = waiting.await {
+                        error!(logger, "processing of bitcoind outcome failed (will try again)"; "id" => event.id.as_str());
+                    }
+                }
+
+                time::sleep(poll_interval).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::{in_memory::InMemory, DbWrite};
+    use olivia_core::{chrono::NaiveDateTime, AnnouncedEvent, Path};
+    use std::{net::SocketAddr, sync::Arc};
+    use tokio_stream::StreamExt;
+    use warp::Filter;
+
+    /// Serves a chain of 101 blocks the way bitcoind's RPC would. Blocks below 90 have been pruned.
+    fn mock_bitcoind() -> SocketAddr {
+        let rpc = warp::post()
+            .and(warp::header::exact("authorization", "Basic dXNlcjpwYXNz"))
+            .and(warp::body::json())
+            .map(|request: serde_json::Value| {
+                let params = &request["params"];
+                let result = match request["method"].as_str().unwrap() {
+                    "getblockcount" => serde_json::json!(100),
+                    "getblockhash" => match params[0].as_u64().unwrap() {
+                        height if height <= 100 => serde_json::json!(format!("{:064x}", height)),
+                        _ => {
+                            return warp::reply::json(&serde_json::json!({
+                                "result": null,
+                                "error": { "code": -8, "message": "Block height out of range" },
+                                "id": "olivia",
+                            }))
+                        }
+                    },
+                    "getblockheader" => {
+                        let height = u64::from_str_radix(params[0].as_str().unwrap(), 16).unwrap();
+                        serde_json::json!({
+                            "time": 1_600_000_000 + height * 600,
+                            "mediantime": 1_600_000_000 + height * 600 - 3000,
+                            "difficulty": 4.6565423739069247e-10,
+                        })
+                    }
+                    "getblockstats" => match params[0].as_u64().unwrap() {
+                        height if height >= 90 => serde_json::json!({ "medianfee": height * 10 }),
+                        _ => {
+                            return warp::reply::json(&serde_json::json!({
+                                "result": null,
+                                "error": { "code": -1, "message": "Block not available (pruned data)" },
+                                "id": "olivia",
+                            }))
+                        }
+                    },
+                    method => panic!("unexpected method {}", method),
+                };
+                warp::reply::json(
+                    &serde_json::json!({ "result": result, "error": null, "id": "olivia" }),
+                )
+            });
+        let (addr, server) = warp::serve(rpc).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        addr
+    }
+
+    fn rpc(addr: SocketAddr) -> BitcoindRpc {
+        BitcoindRpc::new(&format!("http://{}", addr), Some("user"), Some("pass")).unwrap()
+    }
+
+    #[tokio::test]
+    async fn rpc_block_data() {
+        let rpc = rpc(mock_bitcoind());
+        assert_eq!(rpc.block_count().await.unwrap(), 100);
+        assert_eq!(
+            rpc.block_data(92, true).await.unwrap(),
+            BlockData {
+                time: 1_600_055_200,
+                median_time: 1_600_052_200,
+                difficulty: 4.6565423739069247e-10,
+                median_fee: Some(920),
+            }
+        );
+        // the header is still there after the block is pruned
+        assert_eq!(rpc.block_data(42, false).await.unwrap().median_fee, None);
+        assert!(rpc.block_data(42, true).await.is_err());
+        assert!(rpc.block_data(101, false).await.is_err());
+    }
+
+    #[test]
+    fn block_outcomes() {
+        let block = BlockData {
+            time: 1_600_000_000,
+            median_time: 1_599_999_000,
+            difficulty: 22674148233453.1,
+            median_fee: Some(2000),
+        };
+        let outcome = |id: &str| {
+            block_outcome(&Event::from(EventId::from_str(id).unwrap()), &block)
+                .map(|outcome| outcome.outcome_string())
+        };
+        let before = |deadline: i64| {
+            block_outcome(
+                &Event {
+                    id: EventId::from_str("/700000.before").unwrap(),
+                    expected_outcome_time: Some(NaiveDateTime::from_timestamp(deadline, 0)),
+                },
+                &block,
+            )
+            .map(|outcome| outcome.outcome_string())
+        };
+        assert_eq!(outcome("/700000.occur").as_deref(), Some("true"));
+        // mined before the deadline
+        assert_eq!(before(1_600_000_060).as_deref(), Some("true"));
+        assert_eq!(before(1_600_000_000).as_deref(), Some("true"));
+        // mined after the deadline
+        assert_eq!(before(1_599_999_940).as_deref(), Some("false"));
+        assert_eq!(outcome("/700000.before"), None);
+        assert_eq!(
+            outcome("/700000/time.price?n=32").as_deref(),
+            Some("1600000000")
+        );
+        assert_eq!(
+            outcome("/700000/difficulty.price?n=64&precision=1").as_deref(),
+            Some("22674148233453.1")
+        );
+        assert_eq!(
+            outcome("/700000/median_fee.price_1000").as_deref(),
+            Some("true")
+        );
+        assert_eq!(outcome("/700000/hash.price"), None);
+        assert_eq!(outcome("/700000/time.occur"), None);
+        let pruned = BlockData {
+            median_fee: None,
+            ..block
+        };
+        assert_eq!(
+            block_outcome(
+                &Event::from(EventId::from_str("/700000/median_fee.price?n=32").unwrap()),
+                &pruned
+            ),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn attests_confirmed_blocks() {
+        let rpc = rpc(mock_bitcoind());
+        let db = InMemory::<olivia_secp256k1::Secp256k1>::default();
+        for id in [
+            "/bitcoin/block/10.occur",
+            "/bitcoin/block/95.occur",
+            "/bitcoin/block/95/median_fee.price?n=20",
+            "/bitcoin/block/96.occur",
+            "/bitcoin/block/200.occur",
+        ] {
+            db.insert_event(AnnouncedEvent::test_unattested_instance(Event::from(
+                EventId::from_str(id).unwrap(),
+            )))
+            .await
+            .unwrap();
+        }
+
+        let mut stream = Box::pin(
+            BitcoindOutcomeStream {
+                db: PrefixedDb::new(Arc::new(db), Path::from_str("/bitcoin/block").unwrap()),
+                rpc,
+                logger: slog::Logger::root(slog::Discard, o!()),
+                confirmations: 6,
+                poll_interval: Duration::from_secs(60),
+            }
+            .start(),
+        );
+
+        let mut outcomes = vec![];
+        for _ in 0..3 {
+            let update = stream.next().await.unwrap();
+            outcomes.push(update.update.outcome.to_string());
+            update.processed_notifier.unwrap().send(false).unwrap();
+        }
+        outcomes.sort();
+        // block 96 only has 5 confirmations and block 10's stats aren't needed
+        assert_eq!(
+            outcomes,
+            vec![
+                "/10.occur:true",
+                "/95.occur:true",
+                "/95/median_fee.price?n=20:950"
+            ]
+        );
+        assert!(
+            time::timeout(Duration::from_millis(100), stream.next())
+                .await
+                .is_err(),
+            "shouldn't attest to anything else until the next poll"
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use olivia_core::{AnnouncedEvent, EventId, EventKind, Group, Outcome, VOID_OUTCOME};
+use std::sync::Arc;
+
+use crate::db::Db;
+
+pub struct CompleteCompound<C> {
+    pub db: Arc<dyn Db<C>>,
+}
+
+impl<C: Group> CompleteCompound<C> {
+    /// Finds the unattested compound events that depend on the event that was just completed and
+    /// returns the outcomes of those that have all their operands attested.
+    pub async fn complete_compound(&self, completed: &EventId) -> anyhow::Result<Vec<Outcome>> {
+        if !completed.event_kind().is_boolean() {
+            return Ok(vec![]);
+        }
+
+        let compounds = self.db.get_unattested_compounds(completed).await?;
+
+        let mut outcomes = vec![];
+        for id in compounds {
+            let (op, operands) = match id.event_kind() {
+                EventKind::Compound { op, operands } => (op, operands),
+                _ => continue,
+            };
+
+            let mut operand_outcomes = Vec::with_capacity(operands.len());
+            for operand in &operands {
+                match self.db.get_announced_event(operand).await? {
+                    Some(AnnouncedEvent {
+                        attestation: Some(attestation),
+                        ..
+                    }) => operand_outcomes.push(attestation.outcome),
+                    _ => break,
+                }
+            }
+
+            if operand_outcomes.len() == operands.len() {
+                // if any of the operands were cancelled then so is the compound event
+                if operand_outcomes
+                    .iter()
+                    .any(|outcome| outcome == VOID_OUTCOME)
+                {
+                    outcomes.push(Outcome::void(id));
+                    continue;
+                }
+                outcomes.push(Outcome {
+                    id,
+                    value: op
+                        .compound_outcome(operand_outcomes.iter().map(|outcome| outcome == "true")),
+                    void: false,
+                });
+            }
+        }
+
+        Ok(outcomes)
+    }
+}
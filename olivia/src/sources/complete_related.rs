@@ -1,4 +1,5 @@
-use olivia_core::{EventKind, Outcome};
+use core::cmp::Ordering;
+use olivia_core::{EventId, EventKind, Outcome, VsMatchKind, Win, WinOrDraw};
 
 use crate::db::{DbReadEvent, EventQuery, PrefixedDb};
 
@@ -8,11 +9,13 @@ pub struct CompleteRelated {
 
 impl CompleteRelated {
     pub async fn complete_related(&self, outcome: &Outcome) -> anyhow::Result<Vec<Outcome>> {
-        if outcome.id.n_outcomes() < 3 {
-            // things with less than 3 outcomes won't be depended on by anything.
+        if matches!(
+            outcome.id.event_kind(),
+            EventKind::Predicate { .. } | EventKind::Compound { .. }
+        ) {
+            // predicates and compound events can't be predicated on so nothing depends on them
             return Ok(vec![]);
         }
-        let outcome_event_kind = outcome.id.event_kind();
 
         let related_events = self
             .db
@@ -25,28 +28,123 @@ impl CompleteRelated {
 
         Ok(related_events
             .into_iter()
-            .filter_map(|related| match related.id.event_kind() {
-                // If we have the outcome for the event we also have it for the predicated event.
-                EventKind::Predicate { inner, predicate }
-                    if inner.eq_fuzzy(&outcome_event_kind) =>
-                {
-                    let outcome_value = predicate.predicate_outcome(&outcome.outcome_string());
-                    Some(Outcome {
-                        id: related.id,
-                        value: outcome_value,
-                    })
-                }
-                // If we have a price outcome we don't care about nonces
-                EventKind::Price { n_digits: _ }
-                    if matches!(outcome_event_kind, EventKind::Price { n_digits: _ }) =>
-                {
-                    Some(Outcome {
-                        id: related.id,
-                        value: outcome.value,
-                    })
-                }
-                _ => None,
-            })
+            .filter_map(|related| related_outcome(outcome, related.id))
             .collect())
     }
 }
+
+fn related_outcome(outcome: &Outcome, related: EventId) -> Option<Outcome> {
+    let outcome_event_kind = outcome.id.event_kind();
+    match related.event_kind() {
+        // If we have the outcome for the event we also have it for the predicated event.
+        EventKind::Predicate { inner, predicate } if inner.eq_fuzzy(&outcome_event_kind) => {
+            // if the event was cancelled so was anything predicated on it
+            if outcome.void {
+                return Some(Outcome::void(related));
+            }
+            let outcome_value = predicate.predicate_outcome(&outcome.outcome_string());
+            Some(Outcome {
+                id: related,
+                value: outcome_value,
+                void: false,
+            })
+        }
+        // Predicates on the result of a match can be completed from its score
+        EventKind::Predicate { inner, .. }
+            if matches!(*inner, EventKind::VsMatch(_))
+                && matches!(outcome_event_kind, EventKind::Score { .. }) =>
+        {
+            let vs_outcome = related_outcome(outcome, related.replace_kind(*inner))?;
+            related_outcome(&vs_outcome, related)
+        }
+        // If we have a price or score outcome we don't care about nonces
+        kind @ (EventKind::Price { .. } | EventKind::Score { .. })
+            if kind.eq_fuzzy(&outcome_event_kind) =>
+        {
+            Some(Outcome {
+                id: related,
+                ..outcome.clone()
+            })
+        }
+        EventKind::VsMatch(vs_kind) if matches!(outcome_event_kind, EventKind::Score { .. }) => {
+            if outcome.void {
+                return Some(Outcome::void(related));
+            }
+            let (left, right) = outcome.score()?;
+            let value = match (vs_kind, left.cmp(&right)) {
+                (VsMatchKind::WinOrDraw, Ordering::Greater) => WinOrDraw::Left as u64,
+                (VsMatchKind::WinOrDraw, Ordering::Less) => WinOrDraw::Right as u64,
+                (VsMatchKind::WinOrDraw, Ordering::Equal) => WinOrDraw::Draw as u64,
+                (VsMatchKind::Win, Ordering::Greater) => Win::Left as u64,
+                (VsMatchKind::Win, Ordering::Less) => Win::Right as u64,
+                // a winner event can't be completed with a draw
+                (VsMatchKind::Win, Ordering::Equal) => return None,
+            };
+            Some(Outcome {
+                id: related,
+                value,
+                void: false,
+            })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::{in_memory::InMemory, DbWrite};
+    use core::str::FromStr;
+    use olivia_core::{AnnouncedEvent, Path};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn bool_predicate_completed_from_parent() {
+        let db = InMemory::<olivia_secp256k1::Secp256k1>::default();
+        let parent = EventId::from_str("/weather/London/2021-11-01/rain.bool").unwrap();
+        let predicate = EventId::from_str("/weather/London/2021-11-01/rain.bool=false").unwrap();
+        for id in [&parent, &predicate] {
+            db.insert_event(AnnouncedEvent::test_unattested_instance(id.clone().into()))
+                .await
+                .unwrap();
+        }
+        let complete_related = CompleteRelated {
+            db: PrefixedDb::new(Arc::new(db), Path::root()),
+        };
+
+        let outcome = Outcome::try_from_id_and_outcome(parent, "true").unwrap();
+        assert_eq!(
+            complete_related.complete_related(&outcome).await.unwrap(),
+            vec![Outcome::try_from_id_and_outcome(predicate, "false").unwrap()]
+        );
+    }
+
+    #[test]
+    fn related_outcomes_from_score() {
+        let score = Outcome::try_from_id_and_outcome(
+            EventId::from_str("/s/EPL/match/2021-08-13/BRE_ARS.score?n=4").unwrap(),
+            "1-3",
+        )
+        .unwrap();
+        let related = |outcome: &Outcome, kind: &str| {
+            related_outcome(
+                outcome,
+                EventId::from_str(&format!("/s/EPL/match/2021-08-13/BRE_ARS.{}", kind)).unwrap(),
+            )
+            .map(|outcome| outcome.outcome_string())
+        };
+        assert_eq!(related(&score, "vs").as_deref(), Some("ARS_win"));
+        assert_eq!(related(&score, "winner").as_deref(), Some("ARS"));
+        assert_eq!(related(&score, "vs=BRE_win").as_deref(), Some("false"));
+        assert_eq!(related(&score, "score").as_deref(), Some("1-3"));
+        assert_eq!(related(&score, "score=1-3").as_deref(), Some("true"));
+
+        let draw = Outcome::try_from_id_and_outcome(score.id.clone(), "2-2").unwrap();
+        assert_eq!(related(&draw, "vs").as_deref(), Some("draw"));
+        assert_eq!(related(&draw, "winner"), None);
+
+        let void = Outcome::void(score.id);
+        assert_eq!(related(&void, "winner").as_deref(), Some("void"));
+        assert_eq!(related(&void, "vs=draw").as_deref(), Some("void"));
+    }
+}
@@ -1,6 +1,8 @@
 use olivia_core::{Event, EventId, PathRef, PrefixPath};
 use tokio::sync::oneshot::Sender;
 use tokio_stream as stream;
+pub mod bitcoind;
+pub mod complete_compound;
 pub mod complete_related;
 pub mod predicate;
 pub mod redis;
@@ -8,6 +8,7 @@ use olivia_core::{
     chrono::{Duration, NaiveDateTime},
     Event, EventId, EventKind, Outcome, Path, PrefixPath, StampedOutcome,
 };
+use std::collections::{HashMap, HashSet};
 use tokio::{sync::oneshot, time};
 use tokio_stream as stream;
 
@@ -155,6 +156,7 @@ where
                         outcome: Outcome {
                             id: event.id.clone(),
                             value: outcome_creator.create_outcome(&event.id),
+                            void: false,
                         },
                         time: now(), // tell the actual truth about when we actually figured it was done
                     },
@@ -170,6 +172,117 @@ where
     }
 }
 
+/// Attests `false` to deadline events whose `expected_outcome_time` has passed without an outcome.
+pub struct DeadlineOutcomeStream {
+    pub db: PrefixedDb,
+    pub logger: slog::Logger,
+    pub ends_with: Path,
+}
+
+/// How long to wait before trying an event again after it has failed `failures` times in a row.
+fn deadline_retry_delay(failures: u32) -> Duration {
+    Duration::seconds(10 << (failures.clamp(1, 8) - 1))
+}
+
+impl DeadlineOutcomeStream {
+    pub fn start(self) -> impl stream::Stream<Item = Update<StampedOutcome>> {
+        let DeadlineOutcomeStream {
+            db,
+            logger,
+            ends_with,
+        } = self;
+        async_stream::stream! {
+            // events that failed to be processed and when they can be tried again so one bad
+            // event doesn't hold up the ones after it
+            let mut failed = HashMap::<EventId, (u32, NaiveDateTime)>::new();
+            // events without a deadline which we've already warned about
+            let mut no_deadline = HashSet::<EventId>::new();
+            loop {
+                let events = db.query_events(EventQuery {
+                    attested: Some(false),
+                    order: Order::Earliest,
+                    ends_with: ends_with.as_path_ref(),
+                    kind: Some(EventKind::Deadline),
+                    ..Default::default()
+                }).await;
+                let events = match events {
+                    Ok(events) => events,
+                    Err(e) => {
+                        crit!(
+                            logger,
+                            "DB error during deadline stream";
+                            "error" => format!("{}", e)
+                        );
+                        time::sleep(std::time::Duration::from_secs(60)).await;
+                        continue;
+                    }
+                };
+
+                let checked_at = now();
+                let next = events.into_iter().find_map(|event| {
+                    let deadline = match event.expected_outcome_time {
+                        Some(deadline) => deadline,
+                        None => {
+                            if no_deadline.insert(event.id.clone()) {
+                                error!(logger, "skipping deadline event without a deadline"; "id" => event.id.as_str());
+                            }
+                            return None;
+                        }
+                    };
+                    match failed.get(&event.id) {
+                        Some((_, retry_at)) if *retry_at > checked_at => None,
+                        _ => Some((event.id, deadline)),
+                    }
+                });
+                let (id, deadline) = match next {
+                    Some(next) => next,
+                    None => {
+                        time::sleep(std::time::Duration::from_secs(1)).await;
+                        continue;
+                    }
+                };
+
+                // Don't wait for the whole deadline since the event may get an outcome in the
+                // meantime or an event with an earlier deadline may be added.
+                let until_deadline = deadline - checked_at;
+                if until_deadline > Duration::zero() {
+                    time::sleep(until_deadline.min(Duration::seconds(1)).to_std().unwrap()).await;
+                    continue;
+                }
+
+                let (sender, waiting) = oneshot::channel();
+
+                yield Update {
+                    update: StampedOutcome {
+                        outcome: Outcome {
+                            id: id.clone(),
+                            value: false as u64,
+                            void: false,
+                        },
+                        time: now(),
+                    },
+                    processed_notifier: Some(sender),
+                };
+
+                if let Err(_) | Ok(true) = waiting.await {
+                    let failures = failed.get(&id).map(|(failures, _)| *failures).unwrap_or(0) + 1;
+                    let retry_in = deadline_retry_delay(failures);
+                    error!(
+                        logger,
+                        "processing of deadline outcome failed (will try again)";
+                        "id" => id.as_str(),
+                        "failures" => failures,
+                        "retry_in_secs" => retry_in.num_seconds()
+                    );
+                    failed.insert(id, (failures, now() + retry_in));
+                } else {
+                    failed.remove(&id);
+                }
+            }
+        }
+    }
+}
+
 async fn delay_until(until: NaiveDateTime) {
     let delta = until - now();
     if delta > Duration::zero() {
@@ -242,6 +242,95 @@ macro_rules! run_time_db_tests {
                 assert!(now() >= start + Duration::seconds(3));
                 assert!(now() < start + Duration::milliseconds(3000 + fudge));
             }
+
+            #[tokio::test]
+            async fn deadline_outcome_stream() {
+                $($init)*;
+                let start = now();
+                let deadline_event = |name: &str, deadline| AnnouncedEvent::test_unattested_instance(Event {
+                    id: EventId::from_str(&format!("/deadline/{}.before", name)).unwrap(),
+                    expected_outcome_time: Some(deadline),
+                });
+                let events = vec![
+                    deadline_event("attested", start - Duration::seconds(1)),
+                    deadline_event("missed", start),
+                    deadline_event("pending", start + Duration::seconds(60)),
+                ];
+                for event in &events {
+                    $db.insert_event(event.clone())
+                       .await
+                       .unwrap();
+                }
+                $db.complete_event(
+                    &events[0].event.id,
+                    Attestation::test_instance(&events[0].event.id),
+                )
+                   .await
+                   .unwrap();
+
+                let mut stream = Box::pin(DeadlineOutcomeStream { db: PrefixedDb::new($event_db, Path::root()), logger: logger(), ends_with: Path::root() }.start());
+
+                let missed = stream.next().await.unwrap();
+                assert_eq!(missed.update.outcome.id, events[1].event.id, "only the unattested event past its deadline");
+                assert_eq!(missed.update.outcome.outcome_string(), "false");
+                $db.complete_event(
+                    &events[1].event.id,
+                    Attestation::test_instance(&events[1].event.id),
+                )
+                   .await
+                   .unwrap();
+                missed.processed_notifier.unwrap().send(false).unwrap();
+
+                assert!(
+                    tokio::time::timeout(std::time::Duration::from_millis(1500), stream.next())
+                        .await
+                        .is_err(),
+                    "event with a deadline in the future should just block"
+                );
+            }
+
+            #[tokio::test]
+            async fn deadline_outcome_stream_moves_past_bad_events() {
+                $($init)*;
+                let start = now();
+                let deadline_event = |name: &str, deadline| AnnouncedEvent::test_unattested_instance(Event {
+                    id: EventId::from_str(&format!("/deadline/{}.before", name)).unwrap(),
+                    expected_outcome_time: deadline,
+                });
+                let events = vec![
+                    deadline_event("no-deadline", None),
+                    deadline_event("failing", Some(start - Duration::seconds(2))),
+                    deadline_event("missed", Some(start - Duration::seconds(1))),
+                ];
+                for event in &events {
+                    $db.insert_event(event.clone())
+                       .await
+                       .unwrap();
+                }
+
+                let mut stream = Box::pin(DeadlineOutcomeStream { db: PrefixedDb::new($event_db, Path::root()), logger: logger(), ends_with: Path::root() }.start());
+
+                let failing = stream.next().await.unwrap();
+                assert_eq!(failing.update.outcome.id, events[1].event.id, "events without a deadline are skipped");
+                failing.processed_notifier.unwrap().send(true).unwrap();
+
+                let missed = stream.next().await.unwrap();
+                assert_eq!(missed.update.outcome.id, events[2].event.id, "a failed event doesn't hold up the next one");
+                $db.complete_event(
+                    &events[2].event.id,
+                    Attestation::test_instance(&events[2].event.id),
+                )
+                   .await
+                   .unwrap();
+                missed.processed_notifier.unwrap().send(false).unwrap();
+
+                assert!(
+                    tokio::time::timeout(std::time::Duration::from_millis(1500), stream.next())
+                        .await
+                        .is_err(),
+                    "the failed event isn't retried straight away"
+                );
+            }
         }
     }
 }
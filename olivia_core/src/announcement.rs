@@ -1,6 +1,12 @@
-use crate::{Attestation, Descriptor, Event, EventId, Group};
+use crate::{
+    tlv::{self, OracleEventTlv, TlvError},
+    Attestation, Descriptor, Event, EventId, Group,
+};
 use chrono::NaiveDateTime;
-use core::{convert::TryFrom, marker::PhantomData};
+use core::{
+    convert::{TryFrom, TryInto},
+    marker::PhantomData,
+};
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(bound = "C: Group")]
@@ -24,11 +30,19 @@ impl<C: Group> RawOracleEvent<C> {
         oracle_public_key: &C::PublicKey,
         announcement_signature: &C::Signature,
     ) -> bool {
-        C::verify_announcement_signature(
-            oracle_public_key,
-            self.payload.as_bytes(),
-            announcement_signature,
-        )
+        use RawOracleEventEncoding::*;
+        match &self.payload {
+            Json(_) => C::verify_announcement_signature(
+                oracle_public_key,
+                self.payload.as_bytes(),
+                announcement_signature,
+            ),
+            Tlv(_) => C::verify_announcement_tlv_signature(
+                oracle_public_key,
+                self.payload.as_bytes(),
+                announcement_signature,
+            ),
+        }
     }
 
     pub fn decode(&self) -> Option<OracleEvent<C>> {
@@ -36,7 +50,11 @@ impl<C: Group> RawOracleEvent<C> {
     }
 
     pub fn sign(&self, keypair: &C::KeyPair) -> C::Signature {
-        C::sign_announcement(keypair, self.payload.as_bytes())
+        use RawOracleEventEncoding::*;
+        match &self.payload {
+            Json(_) => C::sign_announcement(keypair, self.payload.as_bytes()),
+            Tlv(_) => C::sign_announcement_tlv(keypair, self.payload.as_bytes()),
+        }
     }
 
     pub fn as_bytes(&self) -> &[u8] {
@@ -49,12 +67,26 @@ impl<C: Group> RawOracleEvent<C> {
             curve: PhantomData,
         }
     }
+
+    /// Creates a raw oracle event from the value of an `oracle_event` TLV.
+    pub fn from_tlv_bytes(bytes: Vec<u8>) -> Self {
+        Self {
+            payload: RawOracleEventEncoding::Tlv(bytes),
+            curve: PhantomData,
+        }
+    }
+
+    pub fn is_tlv(&self) -> bool {
+        matches!(self.payload, RawOracleEventEncoding::Tlv(_))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "kebab-case", tag = "encoding", content = "data")]
 enum RawOracleEventEncoding {
     Json(String),
+    /// The value of a DLC spec `oracle_event` TLV encoded as hex.
+    Tlv(#[serde(with = "crate::hex::serde")] Vec<u8>),
 }
 
 impl RawOracleEventEncoding {
@@ -62,6 +94,7 @@ impl RawOracleEventEncoding {
         use RawOracleEventEncoding::*;
         match self {
             Json(string) => serde_json::from_str(string).ok(),
+            Tlv(bytes) => OracleEventTlv::<C>::decode(bytes).ok()?.try_into().ok(),
         }
     }
 
@@ -69,6 +102,7 @@ impl RawOracleEventEncoding {
         use RawOracleEventEncoding::*;
         match self {
             Json(string) => string.as_bytes(),
+            Tlv(bytes) => &bytes[..],
         }
     }
 }
@@ -92,6 +126,9 @@ pub struct AnnouncementSchemes<C: Group> {
     pub olivia_v1: Option<announce::OliviaV1<C>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ecdsa_v1: Option<announce::EcdsaV1>,
+    /// Nonces for DLC spec attestations. These are only announced in TLV announcements.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dlc_v0: Option<announce::DlcV0<C>>,
 }
 
 pub mod announce {
@@ -105,6 +142,14 @@ pub mod announce {
     #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
     #[serde(rename_all = "kebab-case")]
     pub struct EcdsaV1 {}
+
+    /// The nonces of a DLC spec `oracle_event`. They must never be olivia-v1 nonces since
+    /// revealing both kinds of attestation with the same nonce gives away the key.
+    #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub struct DlcV0<C: Group> {
+        pub nonces: Vec<C::PublicNonce>,
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -157,13 +202,21 @@ impl<C: Group> From<OracleEvent<C>> for OracleEventWithDescriptor<C> {
 }
 
 impl<C: Group> OracleEvent<C> {
-    fn encode_json(&self) -> RawOracleEvent<C> {
+    pub fn encode_json(&self) -> RawOracleEvent<C> {
         RawOracleEvent {
             payload: RawOracleEventEncoding::Json(serde_json::to_string(self).unwrap()),
             curve: PhantomData,
         }
     }
 
+    /// Encodes the event as an `oracle_event` TLV.
+    ///
+    /// Returns `None` if the event can't be represented in the DLC spec.
+    pub fn encode_tlv(&self) -> Option<RawOracleEvent<C>> {
+        let bytes = OracleEventTlv::from(self.clone()).encode()?;
+        Some(RawOracleEvent::from_tlv_bytes(bytes))
+    }
+
     pub fn anticipate_attestations_olivia_v1(
         &self,
         public_key: &C::PublicKey,
@@ -213,21 +266,77 @@ impl<C: Group> RawAnnouncement<C> {
         }
     }
 
-    pub fn test_instance(event: Event) -> Self {
+    /// Creates an announcement whose oracle event is encoded as a DLC spec `oracle_event` TLV.
+    ///
+    /// Returns `None` if the event can't be represented in the DLC spec.
+    pub fn create_tlv(
+        event: Event,
+        keypair: &C::KeyPair,
+        schemes: AnnouncementSchemes<C>,
+    ) -> Option<Self> {
+        let oracle_event = OracleEvent::<C> { event, schemes };
+
+        let encoded_oracle_event = oracle_event.encode_tlv()?;
+        let signature = encoded_oracle_event.sign(keypair);
+        Some(Self {
+            signature,
+            oracle_event: encoded_oracle_event,
+        })
+    }
+
+    /// Encodes the announcement as a DLC spec `oracle_announcement` TLV.
+    ///
+    /// Returns `None` if the oracle event isn't TLV encoded.
+    pub fn to_tlv(&self, oracle_announcement_key: &C::PublicKey) -> Option<Vec<u8>> {
+        match &self.oracle_event.payload {
+            RawOracleEventEncoding::Tlv(oracle_event) => {
+                Some(tlv::encode_oracle_announcement::<C>(
+                    &self.signature,
+                    oracle_announcement_key,
+                    oracle_event,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Decodes a DLC spec `oracle_announcement` TLV returning the announcement and the public key
+    /// it claims to be signed under. The signature is not checked.
+    pub fn from_tlv(bytes: &[u8]) -> Result<(Self, C::PublicKey), TlvError> {
+        let decoded = tlv::decode_oracle_announcement::<C>(bytes)?;
+        Ok((
+            Self {
+                oracle_event: RawOracleEvent::from_tlv_bytes(decoded.oracle_event.to_vec()),
+                signature: decoded.signature,
+            },
+            decoded.public_key,
+        ))
+    }
+
+    fn test_nonces(event: &Event) -> Option<Vec<C::PublicNonce>> {
         let nonces: Vec<_> = (0..event.id.event_kind().n_nonces())
             .map(|_| C::test_nonce_keypair().into())
             .collect();
-        Self::create(
-            event.clone(),
-            &C::test_keypair(),
-            AnnouncementSchemes {
-                olivia_v1: match nonces.is_empty() {
-                    true => None,
-                    false => Some(announce::OliviaV1 { nonces }),
-                },
-                ecdsa_v1: Some(announce::EcdsaV1 {}),
-            },
-        )
+        Some(nonces).filter(|nonces| !nonces.is_empty())
+    }
+
+    pub fn test_instance(event: Event) -> Self {
+        let schemes = AnnouncementSchemes {
+            olivia_v1: Self::test_nonces(&event).map(|nonces| announce::OliviaV1 { nonces }),
+            ecdsa_v1: Some(announce::EcdsaV1 {}),
+            dlc_v0: None,
+        };
+        Self::create(event, &C::test_keypair(), schemes)
+    }
+
+    /// Uses the same nonces as [`test_instance`](Self::test_instance) which a real oracle must
+    /// never do.
+    pub fn test_tlv_instance(event: Event) -> Option<Self> {
+        let schemes = AnnouncementSchemes {
+            dlc_v0: Self::test_nonces(&event).map(|nonces| announce::DlcV0 { nonces }),
+            ..Default::default()
+        };
+        Self::create_tlv(event, &C::test_keypair(), schemes)
     }
 }
 
@@ -236,7 +345,13 @@ impl<C: Group> RawAnnouncement<C> {
 pub struct AnnouncedEvent<C: Group> {
     pub event: Event,
     pub announcement: RawAnnouncement<C>,
+    /// The same announcement encoded as a DLC spec `oracle_event` TLV (if the event can be).
+    #[serde(default)]
+    pub tlv_announcement: Option<RawAnnouncement<C>>,
     pub attestation: Option<Attestation<C>>,
+    /// The key epoch whose keys signed the announcement and will sign the attestation.
+    #[serde(default)]
+    pub key_epoch: u32,
 }
 
 impl<C: Group> AnnouncedEvent<C> {
@@ -244,10 +359,24 @@ impl<C: Group> AnnouncedEvent<C> {
         Self {
             event: event.clone(),
             announcement: RawAnnouncement::test_instance(event.clone()),
+            tlv_announcement: RawAnnouncement::test_tlv_instance(event.clone()),
             attestation: Some(Attestation::test_instance(&event.id)),
+            key_epoch: 0,
         }
     }
 
+    /// The attestation as a DLC spec `oracle_attestation` TLV corresponding to `tlv_announcement`.
+    ///
+    /// Returns `None` unless the attestation has dlc-v0 signatures.
+    pub fn tlv_attestation(&self, olivia_v1_key: &C::PublicKey) -> Option<Vec<u8>> {
+        let oracle_event = self.tlv_announcement.as_ref()?.oracle_event.decode()?;
+        let attestation = self
+            .attestation
+            .as_ref()?
+            .to_tlv(&oracle_event, olivia_v1_key)?;
+        Some(attestation.encode())
+    }
+
     pub fn test_unattested_instance(event: Event) -> Self {
         let mut unattested = Self::test_attested_instance(event);
         unattested.attestation = None;
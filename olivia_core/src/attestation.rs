@@ -1,4 +1,4 @@
-use crate::{EventId, Group, OracleEvent, OracleKeys, Outcome};
+use crate::{tlv::OracleAttestationTlv, EventId, Group, OracleEvent, OracleKeys, Outcome};
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(bound = "C: Group")]
@@ -6,6 +6,23 @@ pub struct Attestation<C: Group> {
     pub outcome: String,
     pub schemes: AttestationSchemes<C>,
     pub time: chrono::NaiveDateTime,
+    /// The policy that was applied if the real outcome was out of the range the nonces can attest
+    /// to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overflow: Option<OverflowPolicy>,
+}
+
+/// What to do when an outcome is out of the range the event's nonces can attest to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OverflowPolicy {
+    /// Don't attest to the outcome.
+    #[default]
+    Reject,
+    /// Attest to the closest outcome that is in range instead.
+    Clamp,
+    /// Attest to the void outcome instead.
+    Void,
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, Default)]
@@ -14,6 +31,8 @@ pub struct Attestation<C: Group> {
 pub struct AttestationSchemes<C: Group> {
     pub olivia_v1: Option<attest::OliviaV1<C>>,
     pub ecdsa_v1: Option<attest::EcdsaV1<C>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dlc_v0: Option<attest::DlcV0<C>>,
 }
 
 pub mod attest {
@@ -27,14 +46,22 @@ pub mod attest {
     pub struct EcdsaV1<C: Group> {
         pub signature: C::EcdsaSignature,
     }
+
+    /// A signature on the outcome string for each of the announcement's dlc-v0 nonces.
+    #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+    pub struct DlcV0<C: Group> {
+        pub signatures: Vec<C::Signature>,
+    }
 }
 
-#[derive(thiserror::Error, Debug, PartialEq)]
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
 pub enum AttestationInvalid {
     #[error("olivia-v1 attestation was invalid")]
     OliviaV1,
     #[error("ecdsa-v1 attestation was invalid")]
     EcdsaV1,
+    #[error("dlc-v0 attestation was invalid")]
+    DlcV0,
     #[error("outcome is invalid")]
     Outcome,
     #[error("outcome is missing")]
@@ -55,6 +82,7 @@ impl<C: crate::Group> Attestation<C> {
             outcome,
             schemes,
             time,
+            overflow: None,
         }
     }
 
@@ -69,7 +97,7 @@ impl<C: crate::Group> Attestation<C> {
                 Err(_) => return Err(AttestationInvalid::Outcome),
             };
 
-        let attestation_key = match &oracle_keys.olivia_v1 {
+        let attestation_key = match oracle_keys.olivia_v1_key_for(oracle_event.event.id.path()) {
             Some(key) => key,
             None => return Err(AttestationInvalid::MissingKey),
         };
@@ -131,6 +159,76 @@ impl<C: crate::Group> Attestation<C> {
         Ok(())
     }
 
+    /// Checks the dlc-v0 attestation against an `oracle_event` decoded from a TLV announcement.
+    pub fn verify_dlc_v0_attestation(
+        &self,
+        oracle_event: &OracleEvent<C>,
+        oracle_keys: &OracleKeys<C>,
+    ) -> Result<(), AttestationInvalid> {
+        let outcome =
+            match Outcome::try_from_id_and_outcome(oracle_event.event.id.clone(), &self.outcome) {
+                Ok(outcome) => outcome,
+                Err(_) => return Err(AttestationInvalid::Outcome),
+            };
+
+        let attestation_key = match oracle_keys.olivia_v1_key_for(oracle_event.event.id.path()) {
+            Some(key) => key,
+            None => return Err(AttestationInvalid::MissingKey),
+        };
+
+        match (&oracle_event.schemes.dlc_v0, &self.schemes.dlc_v0) {
+            (Some(ann_dlc_v0), Some(att_dlc_v0)) => {
+                let outcomes = outcome.attestation_outcome_strings();
+                if ann_dlc_v0.nonces.len() != att_dlc_v0.signatures.len()
+                    || outcomes.len() != att_dlc_v0.signatures.len()
+                {
+                    return Err(AttestationInvalid::DlcV0);
+                }
+
+                for ((nonce, outcome), signature) in ann_dlc_v0
+                    .nonces
+                    .iter()
+                    .zip(&outcomes)
+                    .zip(&att_dlc_v0.signatures)
+                {
+                    if !C::dlc_v0_verify(attestation_key, nonce, outcome.as_bytes(), signature) {
+                        return Err(AttestationInvalid::DlcV0);
+                    }
+                }
+            }
+            (Some(_), None) => return Err(AttestationInvalid::Missing),
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Converts the dlc-v0 part of the attestation into a DLC spec `oracle_attestation` TLV.
+    ///
+    /// Returns `None` if there is no dlc-v0 attestation.
+    pub fn to_tlv(
+        &self,
+        oracle_event: &OracleEvent<C>,
+        olivia_v1_key: &C::PublicKey,
+    ) -> Option<OracleAttestationTlv<C>> {
+        let outcome =
+            Outcome::try_from_id_and_outcome(oracle_event.event.id.clone(), &self.outcome).ok()?;
+        let nonces = &oracle_event.schemes.dlc_v0.as_ref()?.nonces;
+        let signatures = &self.schemes.dlc_v0.as_ref()?.signatures;
+        let outcomes = outcome.attestation_outcome_strings();
+
+        if outcomes.len() != signatures.len() || nonces.len() != signatures.len() {
+            return None;
+        }
+
+        Some(OracleAttestationTlv {
+            event_id: oracle_event.event.id.as_str().to_string(),
+            public_key: olivia_v1_key.clone(),
+            signatures: signatures.clone(),
+            outcomes,
+        })
+    }
+
     pub fn test_instance(event_id: &EventId) -> Self {
         let outcome = Outcome::test_instance(event_id);
 
@@ -140,8 +238,21 @@ impl<C: crate::Group> Attestation<C> {
 
         let schemes = AttestationSchemes {
             olivia_v1: Some(attest::OliviaV1 { scalars }),
-            ecdsa_v1: Some(attest::EcdsaV1 {
-                signature: C::ecdsa_sign(&C::test_keypair(), &outcome.to_string().as_bytes()),
+            ecdsa_v1: C::ECDSA_V1.then(|| attest::EcdsaV1 {
+                signature: C::ecdsa_sign(&C::test_keypair(), outcome.to_string().as_bytes()),
+            }),
+            dlc_v0: Some(attest::DlcV0 {
+                signatures: outcome
+                    .attestation_outcome_strings()
+                    .iter()
+                    .map(|outcome| {
+                        C::dlc_v0_sign(
+                            &C::test_keypair(),
+                            C::test_nonce_keypair(),
+                            outcome.as_bytes(),
+                        )
+                    })
+                    .collect(),
             }),
         };
 
@@ -1,14 +1,29 @@
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
 #[serde(tag = "type", rename_all = "kebab-case")]
 pub enum Descriptor {
-    Enum {
-        outcomes: Vec<String>,
-    },
+    /// The last outcome is always the void outcome.
+    Enum { outcomes: Vec<String> },
+    /// Void is attested by every nonce attesting to the index after its last digit.
     DigitDecomposition {
+        #[serde(default = "default_base")]
+        base: u16,
         is_signed: bool,
         n_digits: u8,
         unit: Option<String>,
+        /// The outcome is the attested number multiplied by `10^precision` as in the DLC spec.
+        #[serde(default)]
+        precision: i32,
+    },
+    /// Each position has its own nonce which attests to the index of the participant in that
+    /// position (or the index after the last participant if void).
+    Ranking {
+        participants: Vec<String>,
+        n_positions: u8,
     },
     /// If the DLC spec doesn't support this
     MissingDescriptor,
 }
+
+fn default_base() -> u16 {
+    2
+}
@@ -0,0 +1,132 @@
+//! Proving an oracle attested to two outcomes of the same event.
+//!
+//! An olivia-v1 attest scalar for index `c` is `s = (c + 1)r + x`. Two attest scalars for
+//! different indexes under the same nonce give away `r` and so the oracle's secret key `x`.
+use crate::{
+    Attestation, AttestationInvalid, EventId, Group, OracleKeys, Outcome, RawAnnouncement,
+};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum FraudProofError {
+    #[error("announcement is invalid")]
+    InvalidAnnouncement,
+    #[error("announcement doesn't have olivia-v1 nonces")]
+    MissingOliviaV1,
+    #[error("attestation {attestation} is invalid: {error}")]
+    InvalidAttestation {
+        attestation: usize,
+        error: AttestationInvalid,
+    },
+    #[error("both attestations are to the outcome {0}")]
+    SameOutcome(String),
+    #[error("the attestations never reveal different indexes for the same nonce")]
+    NoEquivocation,
+    #[error("couldn't recover the oracle's secret key from the attestations")]
+    Extraction,
+}
+
+/// Evidence that an oracle attested to two different outcomes of an event
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(bound = "C: Group")]
+pub struct FraudProof<C: Group> {
+    pub event_id: EventId,
+    pub outcomes: [String; 2],
+    /// The index of the nonce that was used for both outcomes
+    pub nonce_index: usize,
+    pub nonce: C::PublicNonce,
+    pub attestation_key: C::PublicKey,
+    /// The secret key of `attestation_key`
+    pub secret_key: C::SecretKey,
+}
+
+impl<C: Group> FraudProof<C> {
+    /// Checks the oracle's announcement and two of its attestations to the event and recovers its
+    /// olivia-v1 secret key if they are to different outcomes.
+    ///
+    /// `epoch_keys` are the oracle's keys in each of its key epochs. The attestations are checked
+    /// against the keys of the epoch whose announcement key signed the announcement.
+    pub fn from_attestations<'a>(
+        epoch_keys: impl IntoIterator<Item = &'a OracleKeys<C>>,
+        announcement: &RawAnnouncement<C>,
+        attestations: [&Attestation<C>; 2],
+    ) -> Result<Self, FraudProofError> {
+        let oracle_keys = epoch_keys
+            .into_iter()
+            .find(|oracle_keys| {
+                announcement
+                    .oracle_event
+                    .verify(&oracle_keys.announcement, &announcement.signature)
+            })
+            .ok_or(FraudProofError::InvalidAnnouncement)?;
+        let oracle_event = announcement
+            .oracle_event
+            .decode()
+            .ok_or(FraudProofError::InvalidAnnouncement)?;
+        let nonces = &oracle_event
+            .schemes
+            .olivia_v1
+            .as_ref()
+            .ok_or(FraudProofError::MissingOliviaV1)?
+            .nonces;
+        let event_id = oracle_event.event.id.clone();
+
+        let mut indexes = vec![];
+        let mut scalars = vec![];
+        for (i, attestation) in attestations.iter().enumerate() {
+            let invalid = |error| FraudProofError::InvalidAttestation {
+                attestation: i,
+                error,
+            };
+            attestation
+                .verify_olivia_v1_attestation(&oracle_event, oracle_keys)
+                .map_err(invalid)?;
+            let outcome = Outcome::try_from_id_and_outcome(event_id.clone(), &attestation.outcome)
+                .map_err(|_| invalid(AttestationInvalid::Outcome))?;
+            indexes.push(outcome.attestation_indexes());
+            scalars.push(
+                &attestation
+                    .schemes
+                    .olivia_v1
+                    .as_ref()
+                    .ok_or_else(|| invalid(AttestationInvalid::Missing))?
+                    .scalars,
+            );
+        }
+
+        if attestations[0].outcome == attestations[1].outcome {
+            return Err(FraudProofError::SameOutcome(
+                attestations[0].outcome.clone(),
+            ));
+        }
+
+        let nonce_index = indexes[0]
+            .iter()
+            .zip(&indexes[1])
+            .position(|(index1, index2)| index1 != index2)
+            .ok_or(FraudProofError::NoEquivocation)?;
+        let attestation_key = oracle_keys
+            .olivia_v1_key_for(event_id.path())
+            .expect("attestations were checked against the key");
+        let nonce = &nonces[nonce_index];
+
+        let secret_key = C::extract_secret_from_equivocation(
+            attestation_key,
+            nonce,
+            (indexes[0][nonce_index], &scalars[0][nonce_index]),
+            (indexes[1][nonce_index], &scalars[1][nonce_index]),
+        )
+        .ok_or(FraudProofError::Extraction)?;
+
+        Ok(FraudProof {
+            event_id,
+            outcomes: [
+                attestations[0].outcome.clone(),
+                attestations[1].outcome.clone(),
+            ],
+            nonce_index,
+            nonce: nonce.clone(),
+            attestation_key: attestation_key.clone(),
+            secret_key,
+        })
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{Descriptor, Outcome, OutcomeError, Path, PathError, PathRef, PrefixPath};
+use crate::{
+    Descriptor, Outcome, OutcomeError, Path, PathError, PathRef, PrefixPath, VOID_OUTCOME,
+};
 use chrono::NaiveDateTime;
 use core::{convert::TryFrom, fmt, str::FromStr};
 
@@ -6,14 +8,52 @@ use core::{convert::TryFrom, fmt, str::FromStr};
 pub enum EventKind {
     VsMatch(VsMatchKind),
     SingleOccurrence,
+    /// Whether something occurs before the event's `expected_outcome_time`. Unlike
+    /// `SingleOccurrence` this can be attested `false` once the deadline has passed.
+    Deadline,
+    /// A yes or no question attested with `true` or `false`.
+    Boolean,
+    /// One of a list of named outcomes.
+    Choice {
+        outcomes: Vec<String>,
+    },
     Predicate {
         inner: Box<EventKind>,
         predicate: Predicate,
     },
+    /// A boolean combination of the outcomes of other events.
+    Compound {
+        op: CompoundOp,
+        operands: Vec<EventId>,
+    },
     Price {
         /// the number of nonces the oracle will use if using nonce based attestation.
         /// Can't be more than 64.
         n_digits: u8,
+        /// The base of each digit. Only meaningful when there are nonces.
+        base: u16,
+        /// Whether the price can be negative. If so there is an extra nonce for the sign.
+        is_signed: bool,
+        /// The number of decimal places. The outcome value is the price multiplied by
+        /// `10^precision`.
+        precision: u8,
+        /// What the price is measured in e.g. "usd".
+        unit: Option<String>,
+        /// The lowest price (multiplied by `10^precision`) the nonces can attest to. The digits
+        /// attest to how far the price is above it.
+        min: u64,
+    },
+    /// The finishing order of the participants with a nonce for each position.
+    Ranking {
+        participants: Vec<String>,
+        /// How many of the top positions are attested to.
+        n_positions: u8,
+    },
+    /// The score of each side in a `LEFT_RIGHT` match.
+    Score {
+        /// The number of nonces for each side's score. Can't be more than 32.
+        n_digits: u8,
+        base: u16,
     },
 }
 
@@ -23,27 +63,85 @@ impl EventKind {
     pub fn eq_fuzzy(&self, rhs: &EventKind) -> bool {
         match (self, rhs) {
             // we don't care about the number of digits
-            (EventKind::Price { .. }, EventKind::Price { .. }) => true,
+            // nor do we care where the digits start
+            (
+                EventKind::Price {
+                    is_signed,
+                    precision,
+                    unit,
+                    ..
+                },
+                EventKind::Price {
+                    is_signed: rhs_is_signed,
+                    precision: rhs_precision,
+                    unit: rhs_unit,
+                    ..
+                },
+            ) => is_signed == rhs_is_signed && precision == rhs_precision && unit == rhs_unit,
+            (EventKind::Score { .. }, EventKind::Score { .. }) => true,
             _ => self == rhs,
         }
     }
+
+    /// Whether the outcome of events of this kind is `true` or `false` (so they can be the
+    /// operands of a compound event).
+    pub fn is_boolean(&self) -> bool {
+        matches!(
+            self,
+            EventKind::SingleOccurrence
+                | EventKind::Deadline
+                | EventKind::Boolean
+                | EventKind::Predicate { .. }
+                | EventKind::Compound { .. }
+        )
+    }
+
+    /// The number of decimal places in the outcome of a numeric event.
+    pub fn precision(&self) -> u8 {
+        match self {
+            EventKind::Price { precision, .. } => *precision,
+            _ => 0,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Predicate {
     Eq(String),
-    Bound(BoundKind, u64),
+    /// The bound is signed since the inner event may be.
+    Bound(BoundKind, i128),
+    /// The outcome is between the two values inclusive.
+    Range(i128, i128),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PredicateKind {
     Eq,
     Bound(BoundKind),
+    /// A range starting at the value and ending `width` after it.
+    Range {
+        width: u64,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum BoundKind {
     Gt,
+    Lt,
+    Ge,
+    Le,
+}
+
+impl BoundKind {
+    /// How the bound is written in an event id between the `_` and the value.
+    pub fn id_str(&self) -> &'static str {
+        match self {
+            BoundKind::Gt => "",
+            BoundKind::Lt => "lt",
+            BoundKind::Ge => "ge",
+            BoundKind::Le => "le",
+        }
+    }
 }
 
 impl Predicate {
@@ -51,13 +149,50 @@ impl Predicate {
         match self {
             Predicate::Eq(target) => (outcome == *target) as u64,
             Predicate::Bound(bound_kind, target) => {
-                let value = outcome
-                    .parse::<u64>()
-                    .expect("can't get predicate outcome for outcome that wasn't numeric");
+                let value = numeric_outcome(outcome);
+                let target = *target;
                 match bound_kind {
-                    BoundKind::Gt => (value > *target) as u64,
+                    BoundKind::Gt => (value > target) as u64,
+                    BoundKind::Lt => (value < target) as u64,
+                    BoundKind::Ge => (value >= target) as u64,
+                    BoundKind::Le => (value <= target) as u64,
                 }
             }
+            Predicate::Range(start, end) => {
+                let value = numeric_outcome(outcome);
+                (*start <= value && value <= *end) as u64
+            }
+        }
+    }
+}
+
+fn numeric_outcome(outcome: &str) -> i128 {
+    // the outcome may be negative if the inner event is signed. It always has the event's number
+    // of decimal places so without the point it's comparable to the bound.
+    outcome
+        .replace('.', "")
+        .parse::<i128>()
+        .expect("can't get predicate outcome for outcome that wasn't numeric")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CompoundOp {
+    And,
+    Or,
+}
+
+impl CompoundOp {
+    pub fn id_str(&self) -> &'static str {
+        match self {
+            CompoundOp::And => "and",
+            CompoundOp::Or => "or",
+        }
+    }
+
+    pub fn compound_outcome(&self, mut operands: impl Iterator<Item = bool>) -> u64 {
+        match self {
+            CompoundOp::And => operands.all(|operand| operand) as u64,
+            CompoundOp::Or => operands.any(|operand| operand) as u64,
         }
     }
 }
@@ -82,12 +217,28 @@ pub enum EventKindError {
     PredBoundWithNonNumericRhs,
     #[error("a bound predicate cannot be placed on a non-numeric event")]
     PredBoundOnNonNumericEvent,
+    #[error("the start of the range predicate was after its end")]
+    PredRangeBackwards,
+    #[error(
+        "the operands of a compound event must be distinct ids of events with true/false outcomes"
+    )]
+    CompoundBadOperand,
 }
 
 impl EventKind {
     pub fn n_nonces(&self) -> u8 {
-        match self {
-            &EventKind::Price { n_digits } => n_digits,
+        match *self {
+            EventKind::Price {
+                n_digits,
+                is_signed,
+                ..
+            } => match n_digits {
+                0 => 0,
+                n_digits => n_digits + is_signed as u8,
+            },
+            // one set of digits for each side
+            EventKind::Score { n_digits, .. } => n_digits * 2,
+            EventKind::Ranking { n_positions, .. } => n_positions,
             _ => 1,
         }
     }
@@ -101,25 +252,88 @@ impl fmt::Display for EventKind {
                 VsMatchKind::WinOrDraw => write!(f, "vs"),
             },
             EventKind::SingleOccurrence => write!(f, "occur"),
+            EventKind::Deadline => write!(f, "before"),
+            EventKind::Boolean => write!(f, "bool"),
+            EventKind::Choice { outcomes } => write!(f, "choice?o={}", outcomes.join(",")),
             EventKind::Predicate {
                 inner,
                 predicate: kind,
-            } => match kind {
-                Predicate::Eq(value) => write!(f, "{}={}", inner, value),
-                Predicate::Bound(bound_kind, bound) => write!(
-                    f,
-                    "{}{}{}",
-                    inner,
-                    match bound_kind {
-                        BoundKind::Gt => '_',
-                    },
-                    bound
-                ),
-            },
-            EventKind::Price { n_digits } => {
+            } => {
+                let precision = inner.precision();
+                // the predicate goes before the inner event's arguments
+                let inner = inner.to_string();
+                let (inner, inner_args) = match inner.split_once('?') {
+                    Some((inner, args)) => (inner, Some(args)),
+                    None => (inner.as_str(), None),
+                };
+                let bound = |bound: &i128| format_decimal(*bound, precision, true);
+                match kind {
+                    Predicate::Eq(value) => write!(f, "{}={}", inner, value)?,
+                    Predicate::Bound(bound_kind, value) => {
+                        write!(f, "{}_{}{}", inner, bound_kind.id_str(), bound(value))?
+                    }
+                    Predicate::Range(start, end) => {
+                        write!(f, "{}_{}..{}", inner, bound(start), bound(end))?
+                    }
+                }
+                if let Some(inner_args) = inner_args {
+                    write!(f, "?{}", inner_args)?;
+                }
+                Ok(())
+            }
+            EventKind::Compound { op, operands } => {
+                // operand ids can't contain '/' so they are written with '~' instead
+                let operands = operands
+                    .iter()
+                    .map(|operand| operand.as_str().replace('/', "~"))
+                    .collect::<Vec<_>>();
+                write!(f, "{}({})", op.id_str(), operands.join(";"))
+            }
+            EventKind::Price {
+                n_digits,
+                base,
+                is_signed,
+                precision,
+                unit,
+                min,
+            } => {
                 write!(f, "price")?;
-                if *n_digits > 0 {
-                    write!(f, "?n={}", n_digits)?;
+                let mut args = digit_args(*n_digits, *base);
+                if *is_signed {
+                    args.push("signed".to_string());
+                }
+                if *precision > 0 {
+                    args.push(format!("precision={}", precision));
+                }
+                if let Some(unit) = unit {
+                    args.push(format!("unit={}", unit));
+                }
+                if *min > 0 {
+                    args.push(format!(
+                        "min={}",
+                        format_decimal(*min as i128, *precision, true)
+                    ));
+                }
+                if !args.is_empty() {
+                    write!(f, "?{}", args.join("&"))?;
+                }
+                Ok(())
+            }
+            EventKind::Ranking {
+                participants,
+                n_positions,
+            } => {
+                write!(f, "rank?p={}", participants.join(","))?;
+                if *n_positions as usize != participants.len() {
+                    write!(f, "&n={}", n_positions)?;
+                }
+                Ok(())
+            }
+            EventKind::Score { n_digits, base } => {
+                write!(f, "score")?;
+                let args = digit_args(*n_digits, *base);
+                if !args.is_empty() {
+                    write!(f, "?{}", args.join("&"))?;
                 }
                 Ok(())
             }
@@ -127,6 +341,17 @@ impl fmt::Display for EventKind {
     }
 }
 
+fn digit_args(n_digits: u8, base: u16) -> Vec<String> {
+    let mut args = vec![];
+    if n_digits > 0 {
+        args.push(format!("n={}", n_digits));
+    }
+    if base != 2 {
+        args.push(format!("b={}", base));
+    }
+    args
+}
+
 impl FromStr for EventKind {
     type Err = EventKindError;
 
@@ -138,15 +363,65 @@ impl FromStr for EventKind {
                 Err(EventKindError::UnexpectedArgs)
             }
         }
-        let (event_kind, args) = match event_kind.find('?') {
-            Some(opener) => (
-                &event_kind[..opener],
-                event_kind[opener + 1..]
-                    .split('&')
-                    .map(|arg| arg.split_once('=').unwrap_or((arg, "")))
-                    .collect(),
-            ),
-            None => (event_kind, vec![]),
+        fn check_no_nonces(inner: &EventKind) -> Result<(), EventKindError> {
+            // the number of nonces of the predicated event is irrelevant
+            match inner {
+                EventKind::Price { n_digits, .. } | EventKind::Score { n_digits, .. }
+                    if *n_digits > 0 =>
+                {
+                    Err(EventKindError::UnexpectedArgs)
+                }
+                // nor is where the digits start
+                EventKind::Price { min, .. } if *min > 0 => Err(EventKindError::UnexpectedArgs),
+                _ => Ok(()),
+            }
+        }
+        // compound events are checked first since their operands may contain anything below
+        for op in [CompoundOp::And, CompoundOp::Or] {
+            let operands = event_kind
+                .strip_prefix(op.id_str())
+                .and_then(|rest| rest.strip_prefix('('))
+                .and_then(|rest| rest.strip_suffix(')'));
+            if let Some(operands) = operands {
+                let operands = operands
+                    .split(';')
+                    .map(|operand| {
+                        if !operand.starts_with('~') || operand.contains('/') {
+                            return Err(EventKindError::CompoundBadOperand);
+                        }
+                        let operand = EventId::from_str(&operand.replace('~', "/"))
+                            .map_err(|_| EventKindError::CompoundBadOperand)?;
+                        match operand.event_kind() {
+                            // compound events can't be nested
+                            EventKind::Compound { .. } => Err(EventKindError::CompoundBadOperand),
+                            kind if kind.is_boolean() => Ok(operand),
+                            _ => Err(EventKindError::CompoundBadOperand),
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if operands.len() < 2
+                    || (1..operands.len()).any(|i| operands[..i].contains(&operands[i]))
+                {
+                    return Err(EventKindError::CompoundBadOperand);
+                }
+                return Ok(EventKind::Compound { op, operands });
+            }
+        }
+        let (event_kind, query) = match event_kind.split_once('?') {
+            Some((event_kind, query)) => (event_kind, Some(query)),
+            None => (event_kind, None),
+        };
+        let args: Vec<(&str, &str)> = match query {
+            Some(query) => query
+                .split('&')
+                .map(|arg| arg.split_once('=').unwrap_or((arg, "")))
+                .collect(),
+            None => vec![],
+        };
+        // predicates are placed before the arguments of the event they are predicating
+        let inner_from_str = |inner: &str| match query {
+            Some(query) => Self::from_str(&format!("{}?{}", inner, query)),
+            None => Self::from_str(inner),
         };
 
         Ok(match (event_kind, args) {
@@ -162,44 +437,149 @@ impl FromStr for EventKind {
                 check_no_args(args)?;
                 EventKind::SingleOccurrence
             }
+            ("before", args) => {
+                check_no_args(args)?;
+                EventKind::Deadline
+            }
+            ("bool", args) => {
+                check_no_args(args)?;
+                EventKind::Boolean
+            }
+            ("choice", args) => {
+                let outcomes = match &args[..] {
+                    [("o", outcomes)] => parse_names(outcomes)?,
+                    _ => return Err(EventKindError::ArgsBadFormat),
+                };
+                EventKind::Choice { outcomes }
+            }
+            ("rank", args) => {
+                let mut args = args.into_iter().peekable();
+                let participants = match args.next_if(|(key, _)| *key == "p") {
+                    Some((_, participants)) => parse_names(participants)?,
+                    None => return Err(EventKindError::ArgsBadFormat),
+                };
+                let n_positions = match args.next_if(|(key, _)| *key == "n") {
+                    // ranking every participant is the default so it must be omitted
+                    Some((_, n_positions)) => u8::from_str(n_positions)
+                        .ok()
+                        .filter(|n| *n > 0 && (*n as usize) < participants.len())
+                        .ok_or(EventKindError::ArgsBadFormat)?,
+                    None => u8::try_from(participants.len())
+                        .map_err(|_| EventKindError::ArgsBadFormat)?,
+                };
+                check_no_args(args.collect())?;
+                // the outcome has a digit for each position so it must fit in a u64
+                (participants.len() as u64)
+                    .checked_pow(n_positions as u32)
+                    .ok_or(EventKindError::ArgsBadFormat)?;
+                EventKind::Ranking {
+                    participants,
+                    n_positions,
+                }
+            }
             ("price", args) => {
-                let n_digits = match &args[..] {
-                    [("n", n_digits)] => u8::from_str(n_digits)
-                        .map_err(|_| EventKindError::ArgsBadFormat)
-                        .and_then(|n_digits| {
-                            if n_digits == 0 || n_digits > 64 {
-                                Err(EventKindError::ArgsBadFormat)
-                            } else {
-                                Ok(n_digits)
-                            }
-                        })?,
-                    [] => 0,
-                    _ => return Err(EventKindError::UnexpectedArgs),
+                // arguments must be in this order so each event has a single id
+                let mut args = args.into_iter().peekable();
+                let (n_digits, base) = parse_digit_args(&mut args, 64)?;
+                let is_signed = match args.next_if(|(key, _)| *key == "signed") {
+                    Some((_, "")) => true,
+                    Some(_) => return Err(EventKindError::ArgsBadFormat),
+                    None => false,
+                };
+                let precision = match args.next_if(|(key, _)| *key == "precision") {
+                    // no precision is the default so it must be omitted
+                    Some((_, precision)) => u8::from_str(precision)
+                        .ok()
+                        .filter(|precision| *precision > 0 && *precision <= MAX_PRECISION)
+                        .ok_or(EventKindError::ArgsBadFormat)?,
+                    None => 0,
                 };
-                EventKind::Price { n_digits }
+                let unit = match args.next_if(|(key, _)| *key == "unit") {
+                    Some((_, unit)) if is_valid_name(unit) => Some(unit.to_string()),
+                    Some(_) => return Err(EventKindError::ArgsBadFormat),
+                    None => None,
+                };
+                let min = match args.next_if(|(key, _)| *key == "min") {
+                    // a signed price can already go below zero
+                    Some((_, min)) if n_digits > 0 && !is_signed => {
+                        parse_id_decimal(min, precision)
+                            .filter(|min| *min > 0)
+                            .ok_or(EventKindError::ArgsBadFormat)?
+                    }
+                    Some(_) => return Err(EventKindError::ArgsBadFormat),
+                    None => 0,
+                };
+                check_no_args(args.collect())?;
+                EventKind::Price {
+                    n_digits,
+                    base,
+                    is_signed,
+                    precision,
+                    unit,
+                    min,
+                }
             }
-            (pred, args) if pred.contains('=') => {
-                check_no_args(args)?;
+            ("score", args) => {
+                let mut args = args.into_iter().peekable();
+                let (n_digits, base) = parse_digit_args(&mut args, 32)?;
+                check_no_args(args.collect())?;
+                EventKind::Score { n_digits, base }
+            }
+            (pred, _) if pred.contains('=') => {
                 let (lhs, rhs) = pred.split_once('=').expect("we checked this already");
-                let inner = Self::from_str(lhs)?;
+                let inner = inner_from_str(lhs)?;
+                check_no_nonces(&inner)?;
                 EventKind::Predicate {
                     inner: Box::new(inner),
                     predicate: Predicate::Eq(rhs.into()),
                 }
             }
-            (pred, args) if pred.contains('_') => {
-                check_no_args(args)?;
+            (pred, _) if pred.contains('_') => {
                 let (lhs, rhs) = pred.split_once('_').expect("we checked this already");
-                let rhs = rhs
-                    .parse()
-                    .map_err(|_| EventKindError::PredBoundWithNonNumericRhs)?;
-                let inner = Self::from_str(lhs)?;
+                let inner = inner_from_str(lhs)?;
+                check_no_nonces(&inner)?;
                 if !matches!(inner, EventKind::Price { .. }) {
                     return Err(EventKindError::PredBoundOnNonNumericEvent);
                 }
+                // bounds are written with the same number of decimal places as the outcome and must
+                // be in the same range as it so they're only negative if the outcome can be
+                let is_signed = matches!(
+                    inner,
+                    EventKind::Price {
+                        is_signed: true,
+                        ..
+                    }
+                );
+                let parse_bound = |bound: &str| {
+                    parse_signed_id_decimal(bound, inner.precision())
+                        .filter(|bound| match is_signed {
+                            true => i64::try_from(*bound).is_ok(),
+                            false => u64::try_from(*bound).is_ok(),
+                        })
+                        .ok_or(EventKindError::PredBoundWithNonNumericRhs)
+                };
+                let predicate = match rhs.split_once("..") {
+                    Some((start, end)) => {
+                        let (start, end) = (parse_bound(start)?, parse_bound(end)?);
+                        if start > end {
+                            return Err(EventKindError::PredRangeBackwards);
+                        }
+                        Predicate::Range(start, end)
+                    }
+                    None => {
+                        let bound_kind = [BoundKind::Lt, BoundKind::Ge, BoundKind::Le]
+                            .into_iter()
+                            .find(|bound_kind| rhs.starts_with(bound_kind.id_str()))
+                            .unwrap_or(BoundKind::Gt);
+                        Predicate::Bound(
+                            bound_kind,
+                            parse_bound(&rhs[bound_kind.id_str().len()..])?,
+                        )
+                    }
+                };
                 EventKind::Predicate {
                     inner: Box::new(inner),
-                    predicate: Predicate::Bound(BoundKind::Gt, rhs),
+                    predicate,
                 }
             }
             _ => return Err(EventKindError::Unknown(event_kind.into())),
@@ -207,6 +587,108 @@ impl FromStr for EventKind {
     }
 }
 
+/// Parses a comma separated list of at least two unique names e.g. the outcomes of a choice event.
+fn parse_names(names: &str) -> Result<Vec<String>, EventKindError> {
+    let names = names
+        .split(',')
+        .map(|name| name.to_string())
+        .collect::<Vec<_>>();
+    if names.len() < 2 || !names.iter().all(|name| is_valid_name(name)) {
+        return Err(EventKindError::ArgsBadFormat);
+    }
+    if (1..names.len()).any(|i| names[..i].contains(&names[i])) {
+        return Err(EventKindError::ArgsBadFormat);
+    }
+    Ok(names)
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != VOID_OUTCOME
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// The most decimal places a numeric event can have so that `10^precision` fits in a u64.
+const MAX_PRECISION: u8 = 18;
+
+/// Parses a decimal like "-12.5" into an integer multiplied by `10^precision`. It can't have more
+/// decimal places than `precision`.
+pub(crate) fn parse_decimal(decimal: &str, precision: u8) -> Option<i128> {
+    let (negative, decimal) = match decimal.strip_prefix('-') {
+        Some(decimal) => (true, decimal),
+        None => (false, decimal),
+    };
+    let (int, frac) = match decimal.split_once('.') {
+        Some((int, frac)) if !frac.is_empty() && frac.len() <= precision as usize => (int, frac),
+        Some(_) => return None,
+        None => (decimal, ""),
+    };
+    if int.is_empty() || !int.chars().chain(frac.chars()).all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let value = format!("{}{:0<width$}", int, frac, width = precision as usize)
+        .parse::<i128>()
+        .ok()?;
+    Some(if negative { -value } else { value })
+}
+
+/// Writes `value / 10^precision` as a decimal. If `trim` is set trailing zeros after the point are
+/// left off.
+pub(crate) fn format_decimal(value: i128, precision: u8, trim: bool) -> String {
+    let scale = 10u128.pow(precision as u32);
+    let sign = if value < 0 { "-" } else { "" };
+    let (int, frac) = (value.unsigned_abs() / scale, value.unsigned_abs() % scale);
+    let mut frac = match precision {
+        0 => String::new(),
+        precision => format!("{:0width$}", frac, width = precision as usize),
+    };
+    if trim {
+        frac.truncate(frac.trim_end_matches('0').len());
+    }
+    match frac.is_empty() {
+        true => format!("{}{}", sign, int),
+        false => format!("{}{}.{}", sign, int, frac),
+    }
+}
+
+/// Parses a non-negative decimal in an event id. Things like "+5", "05" or "5.0" are not allowed
+/// so each event has a single id.
+fn parse_id_decimal(decimal: &str, precision: u8) -> Option<u64> {
+    parse_signed_id_decimal(decimal, precision).and_then(|value| u64::try_from(value).ok())
+}
+
+/// Like [`parse_id_decimal`] but the decimal may be negative e.g. "-5". "-0" is not allowed.
+fn parse_signed_id_decimal(decimal: &str, precision: u8) -> Option<i128> {
+    parse_decimal(decimal, precision)
+        .filter(|value| format_decimal(*value, precision, true) == decimal)
+}
+
+/// Parses the `n` and `b` arguments of events that are attested digit by digit. They must be in
+/// this order so each event has a single id.
+fn parse_digit_args<'a>(
+    args: &mut core::iter::Peekable<impl Iterator<Item = (&'a str, &'a str)>>,
+    max_digits: u8,
+) -> Result<(u8, u16), EventKindError> {
+    let n_digits = match args.next_if(|(key, _)| *key == "n") {
+        Some((_, n_digits)) => u8::from_str(n_digits)
+            .ok()
+            .filter(|n_digits| *n_digits > 0 && *n_digits <= max_digits)
+            .ok_or(EventKindError::ArgsBadFormat)?,
+        None => 0,
+    };
+    let base = match args.next_if(|(key, _)| *key == "b") {
+        // base 2 is the default so it must be omitted
+        Some((_, base)) => u16::from_str(base)
+            .ok()
+            .filter(|base| *base > 2 && n_digits > 0)
+            .ok_or(EventKindError::ArgsBadFormat)?,
+        None => 2,
+    };
+    Ok((n_digits, base))
+}
+
 #[derive(Clone, Debug, PartialEq, Hash, Eq, PartialOrd, Ord)]
 pub struct EventId(Path);
 
@@ -229,7 +711,7 @@ impl EventId {
     }
 
     pub fn parties(&self) -> Option<(&str, &str)> {
-        if let EventKind::VsMatch(_) = self.event_kind() {
+        if let EventKind::VsMatch(_) | EventKind::Score { .. } = self.event_kind() {
             let mut parties = self.path().last().split('_');
             Some((parties.next().unwrap(), parties.next().unwrap()))
         } else {
@@ -247,22 +729,41 @@ impl EventId {
             .expect("Event kind must be valid since this is a valid event id")
     }
 
-    pub fn n_outcomes_for_nonce(&self, _nonce_index: usize) -> u32 {
-        match self.event_kind() {
+    /// The number of outcomes the nonce at `nonce_index` can attest to. This includes the void
+    /// outcome which is always the last one.
+    pub fn n_outcomes_for_nonce(&self, nonce_index: usize) -> u32 {
+        let n_outcomes = match self.event_kind() {
             EventKind::VsMatch(kind) => match kind {
                 VsMatchKind::WinOrDraw => 3,
                 _ => 2,
             },
             EventKind::SingleOccurrence => 1,
-            EventKind::Predicate { .. } => 2,
-            EventKind::Price { .. } => 2,
-        }
+            EventKind::Choice { outcomes } => outcomes.len() as u32,
+            EventKind::Deadline
+            | EventKind::Boolean
+            | EventKind::Predicate { .. }
+            | EventKind::Compound { .. } => 2,
+            EventKind::Price {
+                base, is_signed, ..
+            } => match (is_signed, nonce_index) {
+                // the sign nonce
+                (true, 0) => 2,
+                _ => base as u32,
+            },
+            EventKind::Score { base, .. } => base as u32,
+            // each position's nonce attests to one of the participants
+            EventKind::Ranking { participants, .. } => participants.len() as u32,
+        };
+        n_outcomes + 1
     }
 
+    /// The number of outcomes the event can have not including the void outcome.
     pub fn n_outcomes(&self) -> u64 {
         match self.event_kind() {
-            EventKind::Price { .. } => u64::MAX,
-            _ => self.n_outcomes_for_nonce(0) as u64,
+            EventKind::Price { .. } | EventKind::Score { .. } | EventKind::Ranking { .. } => {
+                u64::MAX
+            }
+            _ => (self.n_outcomes_for_nonce(0) - 1) as u64,
         }
     }
 
@@ -271,7 +772,7 @@ impl EventId {
     }
 
     pub fn descriptor(&self) -> Descriptor {
-        match self.event_kind() {
+        let mut descriptor = match self.event_kind() {
             EventKind::VsMatch(kind) => {
                 let (left, right) = self.parties().unwrap();
                 match kind {
@@ -290,18 +791,45 @@ impl EventId {
             EventKind::SingleOccurrence => Descriptor::Enum {
                 outcomes: vec!["true".into()],
             },
-            EventKind::Price { n_digits } => match n_digits {
-                0 => Descriptor::MissingDescriptor,
-                n_digits => Descriptor::DigitDecomposition {
-                    is_signed: false,
+            EventKind::Choice { outcomes } => Descriptor::Enum { outcomes },
+            EventKind::Price {
+                n_digits,
+                base,
+                is_signed,
+                precision,
+                unit,
+                min,
+            } => match (n_digits, min) {
+                // the DLC spec has no way of describing where the digits start
+                (0, _) | (_, 1..) => Descriptor::MissingDescriptor,
+                (n_digits, _) => Descriptor::DigitDecomposition {
+                    base,
+                    is_signed,
                     n_digits,
-                    unit: None,
+                    unit,
+                    precision: -(precision as i32),
                 },
             },
-            EventKind::Predicate { .. } => Descriptor::Enum {
+            EventKind::Deadline
+            | EventKind::Boolean
+            | EventKind::Predicate { .. }
+            | EventKind::Compound { .. } => Descriptor::Enum {
                 outcomes: vec!["true".into(), "false".into()],
             },
+            // the DLC spec has no way of describing a pair of numbers
+            EventKind::Score { .. } => Descriptor::MissingDescriptor,
+            EventKind::Ranking {
+                participants,
+                n_positions,
+            } => Descriptor::Ranking {
+                participants,
+                n_positions,
+            },
+        };
+        if let Descriptor::Enum { outcomes } = &mut descriptor {
+            outcomes.push(VOID_OUTCOME.into());
         }
+        descriptor
     }
 
     pub fn from_path_and_kind(path: Path, kind: EventKind) -> Self {
@@ -326,6 +854,17 @@ impl EventId {
         Self::from_path_and_kind(Path::from_dt(dt), EventKind::SingleOccurrence)
     }
 
+    /// The number an outcome value stands for when compared against a bound. Signed prices store
+    /// their value in two's complement.
+    fn numeric_value(&self, value: u64) -> i128 {
+        match self.event_kind() {
+            EventKind::Price {
+                is_signed: true, ..
+            } => value as i64 as i128,
+            _ => value as i128,
+        }
+    }
+
     pub fn predicate(&self, predicate_kind: PredicateKind, value: u64) -> EventId {
         let mut event_kind = self.event_kind();
 
@@ -338,16 +877,50 @@ impl EventId {
                 let outcome_string = Outcome {
                     id: self.clone(),
                     value,
+                    void: false,
                 }
                 .outcome_string();
                 Predicate::Eq(outcome_string)
             }
-            PredicateKind::Bound(bound) => Predicate::Bound(bound, value),
+            PredicateKind::Bound(bound) => Predicate::Bound(bound, self.numeric_value(value)),
+            PredicateKind::Range { width } => {
+                let start = self.numeric_value(value);
+                // the range can't go past the highest outcome or the id couldn't be parsed
+                let max = Outcome {
+                    id: self.clone(),
+                    value: match event_kind {
+                        EventKind::Price {
+                            is_signed: true, ..
+                        } => i64::MAX as u64,
+                        _ => u64::MAX,
+                    },
+                    void: false,
+                };
+                let max = self.numeric_value(max.clamped().unwrap_or(max).value);
+                Predicate::Range(
+                    start,
+                    start.saturating_add(width as i128).min(max).max(start),
+                )
+            }
         };
 
-        if let EventKind::Price { ref mut n_digits } = event_kind {
+        if let EventKind::Price { ref mut min, .. } = event_kind {
+            *min = 0;
+        }
+
+        if let EventKind::Price {
+            ref mut n_digits,
+            ref mut base,
+            ..
+        }
+        | EventKind::Score {
+            ref mut n_digits,
+            ref mut base,
+        } = event_kind
+        {
             // The number of nonces is irrelevant to the predicate so set it to 0
             *n_digits = 0;
+            *base = 2;
         }
 
         self.replace_kind(EventKind::Predicate {
@@ -421,9 +994,9 @@ impl TryFrom<Path> for EventId {
         let event_kind = EventKind::from_str(event_kind)?;
 
         match event_kind {
-            EventKind::VsMatch(_) => {
+            EventKind::VsMatch(_) | EventKind::Score { .. } => {
                 let teams: Vec<_> = path.last().split('_').collect();
-                if teams.len() != 2 || teams[0] == teams[1] {
+                if teams.len() != 2 || teams[0] == teams[1] || teams.contains(&VOID_OUTCOME) {
                     return Err(EventIdError::BadFormat);
                 }
             }
@@ -433,15 +1006,36 @@ impl TryFrom<Path> for EventId {
                 predicate: kind,
             } => {
                 match kind {
+                    // whether an event is void can't be predicated on since that makes the
+                    // predicate void too
+                    Predicate::Eq(value) if value == VOID_OUTCOME => {
+                        return Err(EventIdError::Kind(EventKindError::PredEqToInvalidOutcome(
+                            OutcomeError::Invalid { outcome: value },
+                        )))
+                    }
                     Predicate::Eq(value) => {
                         let id = EventId::from_path_and_kind(path.to_path(), *inner);
-                        if let Err(e) = Outcome::try_from_id_and_outcome(id, &value) {
-                            return Err(EventIdError::Kind(
-                                EventKindError::PredEqToInvalidOutcome(e),
-                            ));
+                        match Outcome::try_from_id_and_outcome(id, &value) {
+                            // the outcome must be written the way it's attested or it would never
+                            // be equal
+                            Ok(outcome) if outcome.outcome_string() != value => {
+                                return Err(EventIdError::Kind(
+                                    EventKindError::PredEqToInvalidOutcome(OutcomeError::Invalid {
+                                        outcome: value,
+                                    }),
+                                ))
+                            }
+                            Ok(_) => {}
+                            Err(e) => {
+                                return Err(EventIdError::Kind(
+                                    EventKindError::PredEqToInvalidOutcome(e),
+                                ))
+                            }
                         }
                     }
-                    Predicate::Bound(..) => { /* validity was checked in kind parsing */ }
+                    Predicate::Bound(..) | Predicate::Range(..) => {
+                        /* validity was checked in kind parsing */
+                    }
                 }
             }
             _ => { /*everything is fine */ }
@@ -628,6 +1222,383 @@ mod test {
         assert!(EventId::from_str("/foo/bar.price?n=20_5").is_err());
         assert!(EventId::from_str("/foo/bar.winner_5").is_err());
         assert!(EventId::from_str("/foo/bar.price_foo").is_err());
+        assert!(EventId::from_str("/foo/bar.price?n=20&signed").is_ok());
+        assert!(EventId::from_str("/foo/bar.price?signed").is_ok());
+        assert!(EventId::from_str("/foo/bar.price?signed&n=20").is_err());
+        assert!(EventId::from_str("/foo/bar.price?n=20&signed=true").is_err());
+        assert!(EventId::from_str("/foo/bar.price_5?signed").is_ok());
+        assert!(EventId::from_str("/foo/bar.price=-5?signed").is_ok());
+        assert!(EventId::from_str("/foo/bar.price=-5").is_err());
+        assert!(EventId::from_str("/foo/bar.price_5?n=20&signed").is_err());
+        assert!(EventId::from_str("/foo/bar.price?n=6&b=10").is_ok());
+        assert!(EventId::from_str("/foo/bar.price?n=6&b=16&signed").is_ok());
+        assert!(EventId::from_str("/foo/bar.price?n=6&b=2").is_err());
+        assert!(EventId::from_str("/foo/bar.price?n=6&b=1").is_err());
+        assert!(EventId::from_str("/foo/bar.price?b=10").is_err());
+        assert!(EventId::from_str("/foo/bar.price?b=10&n=6").is_err());
+    }
+
+    #[test]
+    fn bound_predicate_event_ids() {
+        for (kind, predicate) in [
+            ("price_5", Predicate::Bound(BoundKind::Gt, 5)),
+            ("price_lt5", Predicate::Bound(BoundKind::Lt, 5)),
+            ("price_ge5", Predicate::Bound(BoundKind::Ge, 5)),
+            ("price_le5", Predicate::Bound(BoundKind::Le, 5)),
+            ("price_5..10", Predicate::Range(5, 10)),
+            ("price_0..0", Predicate::Range(0, 0)),
+        ] {
+            let event_kind = EventKind::from_str(kind).unwrap();
+            assert_eq!(
+                event_kind,
+                EventKind::Predicate {
+                    inner: Box::new(EventKind::from_str("price").unwrap()),
+                    predicate
+                }
+            );
+            assert_eq!(event_kind.to_string(), kind);
+        }
+        assert_eq!(
+            EventKind::from_str("price_le5?signed").unwrap().to_string(),
+            "price_le5?signed"
+        );
+        assert!(EventKind::from_str("price_10..5").is_err());
+        assert!(EventKind::from_str("price_5..").is_err());
+        assert!(EventKind::from_str("price_lt").is_err());
+        assert!(EventKind::from_str("price_gt5").is_err());
+        assert!(EventKind::from_str("price_05").is_err());
+        assert!(EventKind::from_str("price_+5").is_err());
+        assert!(EventKind::from_str("occur_lt5").is_err());
+
+        for (kind, predicate) in [
+            ("price_-5?signed", Predicate::Bound(BoundKind::Gt, -5)),
+            (
+                "price_lt-0.5?signed&precision=1",
+                Predicate::Bound(BoundKind::Lt, -5),
+            ),
+            ("price_-10..-5?signed", Predicate::Range(-10, -5)),
+            ("price_-5..5?signed", Predicate::Range(-5, 5)),
+        ] {
+            let event_kind = EventKind::from_str(kind).unwrap();
+            assert!(
+                matches!(&event_kind, EventKind::Predicate { predicate: p, .. } if *p == predicate)
+            );
+            assert_eq!(event_kind.to_string(), kind);
+        }
+        // a bound can only be negative if the inner event can be
+        assert!(EventKind::from_str("price_-5").is_err());
+        assert!(EventKind::from_str("price_-0?signed").is_err());
+        assert!(EventKind::from_str("price_-5..-10?signed").is_err());
+
+        let id = EventId::from_str("/foo/bar.price?n=20").unwrap();
+        assert_eq!(
+            id.predicate(PredicateKind::Bound(BoundKind::Le), 7)
+                .as_str(),
+            "/foo/bar.price_le7"
+        );
+        assert_eq!(
+            id.predicate(PredicateKind::Range { width: 100 }, 1000)
+                .as_str(),
+            "/foo/bar.price_1000..1100"
+        );
+        let near_max = [
+            (
+                "/foo/bar.price",
+                u64::MAX - 10,
+                "/foo/bar.price_18446744073709551605..18446744073709551615",
+            ),
+            ("/foo/bar.price?n=4&b=10", 9990, "/foo/bar.price_9990..9999"),
+            (
+                "/foo/bar.price?n=4&b=10&min=100",
+                10090,
+                "/foo/bar.price_10090..10099",
+            ),
+            (
+                "/foo/bar.price?signed",
+                i64::MAX as u64 - 10,
+                "/foo/bar.price_9223372036854775797..9223372036854775807?signed",
+            ),
+        ];
+        for (id, value, predicated) in near_max {
+            let id = EventId::from_str(id)
+                .unwrap()
+                .predicate(PredicateKind::Range { width: 100 }, value);
+            assert_eq!(id.as_str(), predicated);
+            assert_eq!(EventId::from_str(id.as_str()).unwrap(), id);
+        }
+        let id = EventId::from_str("/foo/bar.price?n=20&signed").unwrap();
+        assert_eq!(
+            id.predicate(PredicateKind::Bound(BoundKind::Gt), -7i64 as u64)
+                .as_str(),
+            "/foo/bar.price_-7?signed"
+        );
+    }
+
+    #[test]
+    fn choice_event_id() {
+        let id = EventId::from_str("/elections/2024/mayor.choice?o=red,green,blue").unwrap();
+        assert_eq!(id.n_outcomes(), 3);
+        assert_eq!(id.n_nonces(), 1);
+        assert_eq!(
+            id.descriptor(),
+            Descriptor::Enum {
+                outcomes: vec!["red".into(), "green".into(), "blue".into(), "void".into()]
+            }
+        );
+        assert_eq!(id.event_kind().to_string(), "choice?o=red,green,blue");
+        assert_eq!(
+            id.predicate(PredicateKind::Eq, 1).as_str(),
+            "/elections/2024/mayor.choice=green?o=red,green,blue"
+        );
+        assert!(EventId::from_str("/foo/bar.choice=green?o=red,green,blue").is_ok());
+        assert!(EventId::from_str("/foo/bar.choice=pink?o=red,green,blue").is_err());
+        assert!(EventId::from_str("/foo/bar.choice?o=red,green_light,light-blue").is_ok());
+        assert!(EventId::from_str("/foo/bar.choice").is_err());
+        assert!(EventId::from_str("/foo/bar.choice?o=red").is_err());
+        assert!(EventId::from_str("/foo/bar.choice?o=red,red").is_err());
+        assert!(EventId::from_str("/foo/bar.choice?o=red,,blue").is_err());
+        assert!(EventId::from_str("/foo/bar.choice?o=red,green&n=2").is_err());
+        assert!(EventId::from_str("/foo/bar.choice?o=red,gr.een").is_err());
+        assert!(EventId::from_str("/foo/bar.choice?o=red,void").is_err());
+    }
+
+    #[test]
+    fn ranking_event_id() {
+        let id = EventId::from_str("/f1/2021/monza.rank?p=HAM,VER,LEC").unwrap();
+        assert_eq!(id.n_nonces(), 3);
+        assert_eq!(
+            id.descriptor(),
+            Descriptor::Ranking {
+                participants: vec!["HAM".into(), "VER".into(), "LEC".into()],
+                n_positions: 3
+            }
+        );
+        for kind in ["rank?p=HAM,VER,LEC", "rank?p=HAM,VER,LEC&n=1"] {
+            assert_eq!(EventKind::from_str(kind).unwrap().to_string(), kind);
+        }
+        assert!(EventKind::from_str("rank?p=HAM,VER,LEC&n=3").is_err());
+        assert!(EventKind::from_str("rank?p=HAM,VER,LEC&n=0").is_err());
+        assert!(EventKind::from_str("rank?n=2&p=HAM,VER,LEC").is_err());
+        assert!(EventKind::from_str("rank?p=HAM").is_err());
+        assert!(EventKind::from_str("rank?p=HAM,HAM").is_err());
+        assert!(EventKind::from_str("rank").is_err());
+        // the outcome must fit in a u64
+        let many = (0..20).map(|i| i.to_string()).collect::<Vec<_>>().join(",");
+        assert!(EventKind::from_str(&format!("rank?p={}", many)).is_err());
+        assert!(EventKind::from_str(&format!("rank?p={}&n=3", many)).is_ok());
+        assert!(EventId::from_str("/f1/2021/monza.rank=LEC,HAM,VER?p=HAM,VER,LEC").is_ok());
+        assert!(EventId::from_str("/f1/2021/monza.rank=LEC,HAM?p=HAM,VER,LEC").is_err());
+    }
+
+    #[test]
+    fn score_event_id() {
+        let id = EventId::from_str("/s/EPL/match/2021-08-13/BRE_ARS.score?n=4").unwrap();
+        assert_eq!(id.parties(), Some(("BRE", "ARS")));
+        assert_eq!(id.n_nonces(), 8);
+        assert_eq!(id.descriptor(), Descriptor::MissingDescriptor);
+        assert_eq!(
+            id.predicate(PredicateKind::Eq, 2 << 32 | 1).as_str(),
+            "/s/EPL/match/2021-08-13/BRE_ARS.score=2-1"
+        );
+        for kind in ["score", "score?n=4", "score?n=2&b=10"] {
+            assert_eq!(EventKind::from_str(kind).unwrap().to_string(), kind);
+        }
+        assert!(EventKind::from_str("score?n=33").is_err());
+        assert!(EventKind::from_str("score?b=10").is_err());
+        assert!(EventKind::from_str("score?n=4&signed").is_err());
+        assert!(EventKind::from_str("score=2-1?n=4").is_err());
+        assert!(EventId::from_str("/s/EPL/match/2021-08-13/BRE.score").is_err());
+        assert!(EventId::from_str("/s/EPL/match/2021-08-13/BRE_ARS.score=2").is_err());
+    }
+
+    #[test]
+    fn bool_event_id() {
+        let id = EventId::from_str("/weather/London/2021-11-01/rain.bool").unwrap();
+        assert_eq!(id.event_kind(), EventKind::Boolean);
+        assert_eq!(id.n_nonces(), 1);
+        assert_eq!(
+            id.descriptor(),
+            Descriptor::Enum {
+                outcomes: vec!["true".into(), "false".into(), "void".into()]
+            }
+        );
+        assert_eq!(
+            id.predicate(PredicateKind::Eq, false as u64).as_str(),
+            "/weather/London/2021-11-01/rain.bool=false"
+        );
+        assert!(EventId::from_str("/weather/London/2021-11-01/rain.bool=yes").is_err());
+        assert!(EventKind::from_str("bool?o=yes,no").is_err());
+    }
+
+    #[test]
+    fn deadline_event_id() {
+        let id = EventId::from_str("/bitcoin/tx/abcd/confirmed.before").unwrap();
+        assert_eq!(id.event_kind(), EventKind::Deadline);
+        assert!(id.event_kind().is_boolean());
+        assert_eq!(id.n_outcomes(), 2);
+        assert_eq!(
+            id.descriptor(),
+            Descriptor::Enum {
+                outcomes: vec!["true".into(), "false".into(), "void".into()]
+            }
+        );
+        assert_eq!(
+            Outcome::try_from_id_and_outcome(id.clone(), "false")
+                .unwrap()
+                .outcome_string(),
+            "false"
+        );
+        assert!(Outcome::try_from_id_and_outcome(id, "occurred").is_err());
+        assert!(EventKind::from_str("before?n=2").is_err());
+    }
+
+    #[test]
+    fn compound_event_id() {
+        let id = EventId::from_str(
+            "/combo/2021-10-05T12:00:00.and(~x~BitMEX~BXBT~2021-10-05T12:00:00.price_50000;~x~BitMEX~ETHUSD~2021-10-05T12:00:00.price_3000)",
+        )
+        .unwrap();
+        assert_eq!(id.path().as_str(), "/combo/2021-10-05T12:00:00");
+        assert_eq!(
+            id.event_kind(),
+            EventKind::Compound {
+                op: CompoundOp::And,
+                operands: vec![
+                    EventId::from_str("/x/BitMEX/BXBT/2021-10-05T12:00:00.price_50000").unwrap(),
+                    EventId::from_str("/x/BitMEX/ETHUSD/2021-10-05T12:00:00.price_3000").unwrap(),
+                ]
+            }
+        );
+        assert_eq!(
+            id.event_kind().to_string(),
+            "and(~x~BitMEX~BXBT~2021-10-05T12:00:00.price_50000;~x~BitMEX~ETHUSD~2021-10-05T12:00:00.price_3000)"
+        );
+        assert_eq!(id.n_outcomes(), 2);
+        assert_eq!(id.n_nonces(), 1);
+
+        // operands may have arguments of their own
+        let kind = "or(~foo~bar.price_5?signed;~foo~baz.choice=red?o=red,green;~foo.occur)";
+        assert_eq!(EventKind::from_str(kind).unwrap().to_string(), kind);
+
+        assert!(EventKind::from_str("and(~foo.occur)").is_err());
+        assert!(EventKind::from_str("and(~foo.occur;~foo.occur)").is_err());
+        assert!(EventKind::from_str("and(~foo.occur;~bar.price?n=5)").is_err());
+        assert!(EventKind::from_str("and(~foo.occur;/bar.occur)").is_err());
+        assert!(EventKind::from_str("and(~foo.occur;~bar.and(~a.occur;~b.occur))").is_err());
+        assert!(EventKind::from_str("xor(~foo.occur;~bar.occur)").is_err());
+    }
+
+    #[test]
+    fn compound_outcome() {
+        assert_eq!(
+            CompoundOp::And.compound_outcome([true, true].into_iter()),
+            1
+        );
+        assert_eq!(
+            CompoundOp::And.compound_outcome([true, false].into_iter()),
+            0
+        );
+        assert_eq!(
+            CompoundOp::Or.compound_outcome([false, true].into_iter()),
+            1
+        );
+        assert_eq!(
+            CompoundOp::Or.compound_outcome([false, false].into_iter()),
+            0
+        );
+    }
+
+    #[test]
+    fn n_outcomes_for_nonce_with_base() {
+        let id = EventId::from_str("/foo/bar.price?n=6&b=10&signed").unwrap();
+        assert_eq!(id.n_nonces(), 7);
+        // each nonce has an extra outcome for void
+        assert_eq!(id.n_outcomes_for_nonce(0), 3);
+        assert_eq!(id.n_outcomes_for_nonce(1), 11);
+        assert_eq!(
+            EventId::from_str("/foo/bar.price?n=6&b=16")
+                .unwrap()
+                .n_outcomes_for_nonce(0),
+            17
+        );
+        assert_eq!(
+            id.predicate(PredicateKind::Bound(BoundKind::Gt), 5)
+                .as_str(),
+            "/foo/bar.price_5?signed"
+        );
+    }
+
+    #[test]
+    fn signed_price_event_kind_roundtrip() {
+        for kind in [
+            "price?n=20&signed",
+            "price?signed",
+            "price_5?signed",
+            "price=-5?signed",
+        ] {
+            assert_eq!(EventKind::from_str(kind).unwrap().to_string(), kind);
+        }
+        assert_eq!(
+            EventId::from_str("/foo/bar.price?n=20&signed")
+                .unwrap()
+                .predicate(PredicateKind::Bound(BoundKind::Gt), 5)
+                .as_str(),
+            "/foo/bar.price_5?signed"
+        );
+    }
+
+    #[test]
+    fn fixed_point_price_event_kind() {
+        for kind in [
+            "price?n=20&precision=2&unit=usd&min=10000",
+            "price?precision=2&unit=usd",
+            "price?n=20&precision=2&min=10000.5",
+            "price?n=10&b=10&signed&precision=8",
+            "price_100.5?precision=2",
+            "price_100..100.25?precision=2&unit=usd",
+            "price=100.50?precision=2",
+        ] {
+            assert_eq!(EventKind::from_str(kind).unwrap().to_string(), kind);
+        }
+        for kind in [
+            "price?precision=0",
+            "price?precision=19",
+            "price?unit=usd&precision=2",
+            "price?unit=",
+            "price?min=10",
+            "price?n=20&signed&min=10",
+            "price?n=20&precision=2&min=10.00",
+            "price?n=20&min=10.5",
+            "price_100.50?precision=2",
+            "price_100.5",
+        ] {
+            assert!(EventKind::from_str(kind).is_err(), "{}", kind);
+        }
+        assert!(EventId::from_str("/foo/bar.price=100.5?precision=2").is_err());
+
+        let id = EventId::from_str("/foo/bar.price?n=20&precision=2&unit=usd&min=10000").unwrap();
+        assert_eq!(
+            id.predicate(PredicateKind::Bound(BoundKind::Gt), 1_500_050)
+                .as_str(),
+            "/foo/bar.price_15000.5?precision=2&unit=usd"
+        );
+        assert_eq!(id.descriptor(), Descriptor::MissingDescriptor);
+        assert_eq!(
+            EventId::from_str("/foo/bar.price?n=20&precision=2&unit=usd")
+                .unwrap()
+                .descriptor(),
+            Descriptor::DigitDecomposition {
+                base: 2,
+                is_signed: false,
+                n_digits: 20,
+                unit: Some("usd".into()),
+                precision: -2,
+            }
+        );
+        assert_eq!(
+            Predicate::Bound(BoundKind::Gt, 1_000_050).predicate_outcome("10000.51"),
+            1
+        );
     }
 
     #[test]
@@ -644,6 +1615,13 @@ mod test {
         // price without a nonce specifer just assumes that we are not doing nonce based
         // attestations
         assert_eq!(EventId::from_str("/foo/bar.price").unwrap().n_nonces(), 0);
+        // signed prices have an extra nonce for the sign
+        assert_eq!(
+            EventId::from_str("/foo/bar.price?n=20&signed")
+                .unwrap()
+                .n_nonces(),
+            21
+        );
     }
 
     #[test]
@@ -716,5 +1694,44 @@ mod test {
             Predicate::Bound(BoundKind::Gt, 10).predicate_outcome("9"),
             false as u64
         );
+        assert_eq!(
+            Predicate::Bound(BoundKind::Gt, 10).predicate_outcome("-11"),
+            false as u64
+        );
+    }
+
+    #[test]
+    fn predicate_outcome_bounds() {
+        let cases = [
+            (BoundKind::Lt, [true, false, false]),
+            (BoundKind::Le, [true, true, false]),
+            (BoundKind::Ge, [false, true, true]),
+        ];
+        for (bound_kind, expected) in cases {
+            for (outcome, expected) in ["9", "10", "11"].iter().zip(expected) {
+                assert_eq!(
+                    Predicate::Bound(bound_kind, 10).predicate_outcome(outcome),
+                    expected as u64
+                );
+            }
+        }
+        assert_eq!(
+            Predicate::Bound(BoundKind::Lt, -10).predicate_outcome("-11"),
+            true as u64
+        );
+        assert_eq!(
+            Predicate::Bound(BoundKind::Lt, -10).predicate_outcome("-10"),
+            false as u64
+        );
+        assert_eq!(
+            Predicate::Bound(BoundKind::Gt, -10).predicate_outcome("5"),
+            true as u64
+        );
+        let range = Predicate::Range(10, 20);
+        assert_eq!(range.predicate_outcome("-15"), false as u64);
+        assert_eq!(range.predicate_outcome("9"), false as u64);
+        assert_eq!(range.predicate_outcome("10"), true as u64);
+        assert_eq!(range.predicate_outcome("20"), true as u64);
+        assert_eq!(range.predicate_outcome("21"), false as u64);
     }
 }
@@ -2,6 +2,14 @@ use core::fmt::Debug;
 
 use crate::OracleKeys;
 
+/// The fixed length binary encoding of a group object used in the DLC spec TLV messages.
+pub trait ToFromBytes: Sized {
+    /// The length of the encoding in bytes.
+    const LEN: usize;
+    fn to_bytes(&self) -> Vec<u8>;
+    fn from_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
 #[cfg(not(feature = "postgres-types"))]
 pub trait GroupObject:
     PartialEq
@@ -12,6 +20,7 @@ pub trait GroupObject:
     + core::fmt::Display
     + Send
     + Sync
+    + ToFromBytes
     + 'static
 {
 }
@@ -28,6 +37,7 @@ pub trait GroupObject:
     + Sync
     + postgres_types::FromSqlOwned
     + postgres_types::ToSql
+    + ToFromBytes
     + 'static
 {
 }
@@ -47,13 +57,26 @@ pub trait Group:
     type PublicKey: GroupObject;
     type PublicNonce: GroupObject;
     type Signature: GroupObject;
-    type AnticipatedAttestation;
+    type AnticipatedAttestation: Clone + Debug + PartialEq;
     type NonceKeyPair: Into<Self::PublicNonce> + Clone + Debug;
     type KeyPair: Into<Self::PublicKey> + Clone;
     type EcdsaSignature: GroupObject;
+    type SecretKey: PartialEq
+        + Clone
+        + Debug
+        + serde::Serialize
+        + serde::de::DeserializeOwned
+        + core::fmt::Display
+        + Send
+        + Sync
+        + 'static;
 
     const KEY_MATERIAL_LEN: usize;
 
+    /// Whether the group has ECDSA signatures for the ecdsa-v1 scheme. Oracles on a group without
+    /// them leave the scheme and its key out of what they publish.
+    const ECDSA_V1: bool = true;
+
     fn name() -> &'static str;
 
     fn reveal_attest_scalar(
@@ -69,6 +92,17 @@ pub trait Group:
         attest_scalar: &Self::AttestScalar,
     ) -> bool;
 
+    /// Recovers the secret key behind `attest_key` from two attest scalars that were revealed
+    /// against the same nonce for different indexes.
+    ///
+    /// Returns `None` if the indexes are the same or the recovered key doesn't match `attest_key`.
+    fn extract_secret_from_equivocation(
+        attest_key: &Self::PublicKey,
+        nonce_key: &Self::PublicNonce,
+        first: (u32, &Self::AttestScalar),
+        second: (u32, &Self::AttestScalar),
+    ) -> Option<Self::SecretKey>;
+
     fn verify_announcement_signature(
         public_key: &Self::PublicKey,
         message: &[u8],
@@ -81,7 +115,45 @@ pub trait Group:
         n_outcomes: u32,
     ) -> Vec<Self::AnticipatedAttestation>;
 
+    /// Adds anticipated attestations together. The result is anticipated by the sum of their
+    /// attest scalars.
+    fn combine_anticipated_attestations(
+        anticipated: &[Self::AnticipatedAttestation],
+    ) -> Self::AnticipatedAttestation;
+
+    fn combine_attest_scalars(attest_scalars: &[Self::AttestScalar]) -> Self::AttestScalar;
+
+    fn attest_scalar_matches(
+        anticipated: &Self::AnticipatedAttestation,
+        attest_scalar: &Self::AttestScalar,
+    ) -> bool;
+
     fn sign_announcement(keypair: &Self::KeyPair, announcement: &[u8]) -> Self::Signature;
+
+    /// Signs an `oracle_event` TLV the way the DLC spec expects it to be signed in an
+    /// `oracle_announcement`.
+    fn sign_announcement_tlv(keypair: &Self::KeyPair, oracle_event: &[u8]) -> Self::Signature;
+
+    fn verify_announcement_tlv_signature(
+        public_key: &Self::PublicKey,
+        oracle_event: &[u8],
+        sig: &Self::Signature,
+    ) -> bool;
+
+    /// Signs an outcome string with the nonce the way a DLC spec oracle attests to it.
+    fn dlc_v0_sign(
+        keypair: &Self::KeyPair,
+        nonce_keypair: Self::NonceKeyPair,
+        outcome: &[u8],
+    ) -> Self::Signature;
+
+    /// Checks a DLC spec attestation to `outcome` was made with `public_nonce`.
+    fn dlc_v0_verify(
+        public_key: &Self::PublicKey,
+        public_nonce: &Self::PublicNonce,
+        outcome: &[u8],
+        sig: &Self::Signature,
+    ) -> bool;
     fn keypair_from_secret_bytes(bytes: &[u8]) -> Self::KeyPair;
     fn nonce_keypair_from_secret_bytes(bytes: &[u8]) -> Self::NonceKeyPair;
     fn ecdsa_sign(keypair: &Self::KeyPair, message: &[u8]) -> Self::EcdsaSignature;
@@ -0,0 +1,88 @@
+#[derive(Debug, Clone, PartialEq)]
+pub enum HexError {
+    /// The string was not a valid hex string.
+    InvalidHex,
+    /// The string was not the right length for the target type.
+    InvalidLength,
+    /// The bytes did not encode a valid value for the target type.
+    InvalidEncoding,
+}
+
+impl core::fmt::Display for HexError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        use HexError::*;
+        match self {
+            InvalidHex => write!(f, "invalid hex string"),
+            InvalidLength => write!(f, "hex string had an invalid length"),
+            InvalidEncoding => write!(f, "hex value did not encode the expected type"),
+        }
+    }
+}
+
+impl std::error::Error for HexError {}
+
+#[doc(hidden)]
+pub fn hex_val(c: u8) -> Result<u8, HexError> {
+    match c {
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'0'..=b'9' => Ok(c - b'0'),
+        _ => Err(HexError::InvalidHex),
+    }
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    use core::fmt::Write;
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        write!(hex, "{:02x}", byte).unwrap();
+    }
+    hex
+}
+
+pub fn from_hex(hex: &str) -> Result<Vec<u8>, HexError> {
+    if hex.len() % 2 == 1 {
+        return Err(HexError::InvalidLength);
+    }
+    hex.as_bytes()
+        .chunks(2)
+        .map(|hex_byte| Ok(hex_val(hex_byte[0])? << 4 | hex_val(hex_byte[1])?))
+        .collect()
+}
+
+/// For use with `#[serde(with = "crate::hex::serde")]` on `Vec<u8>` fields.
+pub mod serde {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::to_hex(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        super::from_hex(&hex).map_err(de::Error::custom)
+    }
+}
+
+/// For use with `#[serde(with = "crate::hex::serde_option")]` on `Option<Vec<u8>>` fields.
+pub mod serde_option {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        bytes: &Option<Vec<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match bytes {
+            Some(bytes) => super::serde::serialize(bytes, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<u8>>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|hex| super::from_hex(&hex).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
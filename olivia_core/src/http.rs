@@ -1,31 +1,56 @@
-use crate::{AnnouncedEvent, Attestation, GetPath, Group, OracleKeys, RawAnnouncement};
+use crate::{AnnouncedEvent, Attestation, GetPath, Group, KeyEpoch, OracleKeys, RawAnnouncement};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
 #[serde(bound = "C: Group")]
 pub struct EventResponse<C: Group> {
     pub announcement: RawAnnouncement<C>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tlv_announcement: Option<RawAnnouncement<C>>,
     pub attestation: Option<Attestation<C>>,
+    /// The DLC spec `oracle_attestation` TLV for `tlv_announcement` once the event is attested.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::hex::serde_option"
+    )]
+    pub tlv_attestation: Option<Vec<u8>>,
+    #[serde(default)]
+    pub key_epoch: u32,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
 #[serde(bound = "C: Group", rename_all = "kebab-case")]
 pub struct RootResponse<C: Group> {
     pub public_keys: OracleKeys<C>,
+    /// Every key epoch the oracle has had including the current one.
+    #[serde(default)]
+    pub key_epochs: Vec<KeyEpoch<C>>,
     #[serde(flatten)]
     pub node: GetPath,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
-pub struct PathResponse {
+#[serde(bound = "C: Group", rename_all = "kebab-case")]
+pub struct PathResponse<C: Group> {
+    /// The current olivia-v1 key for events under this path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub olivia_v1_key: Option<C::PublicKey>,
     #[serde(flatten)]
     pub node: GetPath,
 }
 
-impl<C: Group> From<AnnouncedEvent<C>> for EventResponse<C> {
-    fn from(ann: AnnouncedEvent<C>) -> Self {
+impl<C: Group> EventResponse<C> {
+    /// `oracle_keys` must be the keys of the key epoch the event was announced in.
+    pub fn new(ann: AnnouncedEvent<C>, oracle_keys: &OracleKeys<C>) -> Self {
+        let tlv_attestation = oracle_keys
+            .olivia_v1_key_for(ann.event.id.path())
+            .and_then(|olivia_v1_key| ann.tlv_attestation(olivia_v1_key));
         EventResponse {
             announcement: ann.announcement,
+            tlv_announcement: ann.tlv_announcement,
             attestation: ann.attestation,
+            tlv_attestation,
+            key_epoch: ann.key_epoch,
         }
     }
 }
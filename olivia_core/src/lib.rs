@@ -1,26 +1,35 @@
 mod announcement;
 mod attestation;
 mod descriptor;
+mod equivocation;
 mod event;
 mod group;
+#[doc(hidden)]
+pub mod hex;
 pub mod http;
 mod macros;
 mod node;
 mod oracle_info;
 mod outcome;
 mod path;
+mod threshold;
+pub mod tlv;
 
 pub use announcement::*;
 pub use attestation::*;
 pub use descriptor::*;
+pub use equivocation::*;
 pub use event::*;
 pub use group::*;
 pub use node::*;
 pub use oracle_info::*;
 pub use outcome::*;
 pub use path::*;
+pub use threshold::*;
 
 pub use chrono;
+#[doc(hidden)]
+pub use serde;
 #[cfg(feature = "postgres-types")]
 pub use postgres_types;
 
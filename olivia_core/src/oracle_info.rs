@@ -1,4 +1,6 @@
-use crate::Group;
+use crate::{Group, Path, PathRef};
+use chrono::NaiveDateTime;
+use std::collections::BTreeMap;
 
 pub type OracleId = String;
 
@@ -17,6 +19,34 @@ pub struct OracleKeys<C: Group> {
     pub ecdsa_v1: Option<C::PublicKey>,
     pub announcement: C::PublicKey,
     pub group: C,
+    /// olivia-v1 keys that attest to the events under each path instead of `olivia_v1`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub olivia_v1_namespaces: BTreeMap<Path, C::PublicKey>,
+}
+
+impl<C: Group> OracleKeys<C> {
+    /// The olivia-v1 key for events under `path` i.e. the key of the longest namespace containing
+    /// it or `olivia_v1` if there isn't one.
+    pub fn olivia_v1_key_for(&self, path: PathRef<'_>) -> Option<&C::PublicKey> {
+        self.olivia_v1_namespaces
+            .iter()
+            .filter(|(namespace, _)| namespace.as_path_ref().is_parent_of(path))
+            .max_by_key(|(namespace, _)| namespace.as_str().len())
+            .map(|(_, key)| key)
+            .or(self.olivia_v1.as_ref())
+    }
+}
+
+/// The keys the oracle used during a key epoch. `valid_until` is `None` for the current epoch.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[serde(bound = "C: Group")]
+pub struct KeyEpoch<C: Group> {
+    pub epoch: u32,
+    pub valid_from: NaiveDateTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<NaiveDateTime>,
+    pub keys: OracleKeys<C>,
 }
 
 impl<C: Group> OracleInfo<C> {
@@ -28,6 +58,7 @@ impl<C: Group> OracleInfo<C> {
                 ecdsa_v1: Some(C::test_keypair().into()),
                 announcement: C::test_keypair().into(),
                 group: C::default(),
+                olivia_v1_namespaces: BTreeMap::new(),
             },
         }
     }
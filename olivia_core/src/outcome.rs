@@ -1,4 +1,7 @@
-use crate::{EventId, EventKind, PrefixPath, VsMatchKind};
+use crate::{
+    event::{format_decimal, parse_decimal},
+    EventId, EventKind, PrefixPath, VsMatchKind,
+};
 use chrono::NaiveDateTime;
 use core::{
     convert::{TryFrom, TryInto},
@@ -32,10 +35,16 @@ impl StampedOutcome {
     }
 }
 
+/// The outcome string every event can be attested to when it was cancelled and will never resolve.
+pub const VOID_OUTCOME: &str = "void";
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Outcome {
     pub id: EventId,
+    /// For signed price events this is the `i64` value cast to a `u64`.
     pub value: u64,
+    /// The event was cancelled. If so `value` is 0.
+    pub void: bool,
 }
 
 impl Outcome {
@@ -43,10 +52,22 @@ impl Outcome {
         Outcome {
             id: event_id.clone(),
             value: event_id.n_outcomes() - 1,
+            void: false,
+        }
+    }
+
+    pub fn void(id: EventId) -> Self {
+        Outcome {
+            id,
+            value: 0,
+            void: true,
         }
     }
 
     pub fn try_from_id_and_outcome(id: EventId, outcome: &str) -> Result<Self, OutcomeError> {
+        if outcome == VOID_OUTCOME {
+            return Ok(Self::void(id));
+        }
         let value = match id.event_kind() {
             EventKind::SingleOccurrence => {
                 if outcome == "true" {
@@ -57,6 +78,16 @@ impl Outcome {
                     });
                 }
             }
+            EventKind::Choice { outcomes } => {
+                match outcomes.iter().position(|choice| choice == outcome) {
+                    Some(index) => index as u64,
+                    None => {
+                        return Err(OutcomeError::Invalid {
+                            outcome: outcome.to_string(),
+                        })
+                    }
+                }
+            }
             EventKind::VsMatch(kind) => {
                 let (left, right) = id.parties().expect("it's a vs kind");
                 match kind {
@@ -95,19 +126,66 @@ impl Outcome {
                     }
                 }
             }
-            EventKind::Predicate { .. } => {
+            EventKind::Deadline
+            | EventKind::Boolean
+            | EventKind::Predicate { .. }
+            | EventKind::Compound { .. } => {
                 bool::from_str(outcome).map_err(|_| OutcomeError::Invalid {
                     outcome: outcome.to_string(),
                 })? as u64
             }
-            EventKind::Price { .. } => {
-                u64::from_str(outcome).map_err(|_| OutcomeError::Invalid {
+            // e.g. "101.5" is 10150 if there are two decimal places
+            EventKind::Price {
+                is_signed,
+                precision,
+                ..
+            } => parse_decimal(outcome, precision)
+                .and_then(|value| match is_signed {
+                    true => i64::try_from(value).ok().map(|value| value as u64),
+                    false => u64::try_from(value).ok(),
+                })
+                .ok_or_else(|| OutcomeError::Invalid {
                     outcome: outcome.into(),
-                })?
+                })?,
+            // e.g. "A,C" means A came first and C second
+            EventKind::Ranking {
+                participants,
+                n_positions,
+            } => {
+                let invalid = || OutcomeError::Invalid {
+                    outcome: outcome.into(),
+                };
+                let ranking = outcome
+                    .split(',')
+                    .map(|name| participants.iter().position(|p| p == name))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(invalid)?;
+                if ranking.len() != n_positions as usize
+                    || (1..ranking.len()).any(|i| ranking[..i].contains(&ranking[i]))
+                {
+                    return Err(invalid());
+                }
+                ranking.into_iter().fold(0, |value, index| {
+                    value * participants.len() as u64 + index as u64
+                })
             }
+            // e.g. "2-1". The left score goes in the top half of the value.
+            EventKind::Score { .. } => outcome
+                .split_once('-')
+                .and_then(|(left, right)| {
+                    Some((u32::from_str(left).ok()?, u32::from_str(right).ok()?))
+                })
+                .map(|(left, right)| (left as u64) << 32 | right as u64)
+                .ok_or_else(|| OutcomeError::Invalid {
+                    outcome: outcome.into(),
+                })?,
         };
 
-        Ok(Self { value, id })
+        Ok(Self {
+            value,
+            id,
+            void: false,
+        })
     }
 
     pub fn outcome_string(&self) -> String {
@@ -117,6 +195,9 @@ impl Outcome {
     }
 
     pub fn write_outcome_string(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        if self.void {
+            return write!(f, "{}", VOID_OUTCOME);
+        }
         match (self.id.event_kind(), self.value) {
             (EventKind::SingleOccurrence, o) => {
                 match Occur::try_from(o).expect("outcome value should be less than 1") {
@@ -136,36 +217,209 @@ impl Outcome {
                     Win::Right => write!(f, "{}", self.id.parties().unwrap().1),
                 }
             }
-            (EventKind::Predicate { .. }, truth) => {
+            (EventKind::Choice { outcomes }, index) => write!(
+                f,
+                "{}",
+                outcomes
+                    .get(index as usize)
+                    .expect("outcome value should be less than the number of choices")
+            ),
+            (
+                EventKind::Deadline
+                | EventKind::Boolean
+                | EventKind::Predicate { .. }
+                | EventKind::Compound { .. },
+                truth,
+            ) => {
                 assert!(truth < 2);
                 write!(f, "{}", truth != 0)
             }
-            (EventKind::Price { .. }, price) => write!(f, "{}", price),
+            (
+                EventKind::Price {
+                    is_signed,
+                    precision,
+                    ..
+                },
+                price,
+            ) => {
+                let price = match is_signed {
+                    true => price as i64 as i128,
+                    false => price as i128,
+                };
+                write!(f, "{}", format_decimal(price, precision, false))
+            }
+            (EventKind::Ranking { participants, .. }, _) => {
+                let ranking = self
+                    .attestation_indexes()
+                    .iter()
+                    .map(|index| participants[*index as usize].as_str())
+                    .collect::<Vec<_>>();
+                write!(f, "{}", ranking.join(","))
+            }
+            (EventKind::Score { .. }, score) => {
+                write!(f, "{}-{}", score >> 32, score as u32)
+            }
         }
     }
 
     pub fn attestation_indexes(&self) -> Vec<u32> {
+        if self.void {
+            // every nonce attests to its last index
+            return (0..self.id.n_nonces() as usize)
+                .map(|nonce_index| self.id.n_outcomes_for_nonce(nonce_index) - 1)
+                .collect();
+        }
         match self.id.event_kind() {
-            EventKind::Price { n_digits } => {
-                let cap = u64::MAX.checked_shr(64 - n_digits as u32).unwrap_or(0);
-                let value = self.value.min(cap);
-                (0..n_digits)
-                    .map(|i| (value & (1 << i) != 0) as u32)
-                    .rev()
+            EventKind::Price {
+                n_digits,
+                base,
+                is_signed,
+                min,
+                ..
+            } => {
+                let (sign, magnitude) = match is_signed {
+                    true => {
+                        let value = self.value as i64;
+                        (Some(value < 0), value.unsigned_abs())
+                    }
+                    false => (None, self.value.saturating_sub(min)),
+                };
+                // the sign comes first as in the DLC spec: 0 is "+" and 1 is "-"
+                sign.filter(|_| n_digits > 0)
+                    .map(|negative| negative as u32)
+                    .into_iter()
+                    .chain(digit_indexes(magnitude, base as u64, n_digits))
+                    .collect()
+            }
+            EventKind::Score { n_digits, base } => {
+                let (left, right) = self.score().expect("it's a score event");
+                digit_indexes(left as u64, base as u64, n_digits)
+                    .chain(digit_indexes(right as u64, base as u64, n_digits))
                     .collect()
             }
+            // the index of the participant in each position with first place first
+            EventKind::Ranking {
+                participants,
+                n_positions,
+            } => digit_indexes(self.value, participants.len() as u64, n_positions).collect(),
             _ => vec![self.value.try_into().unwrap()],
         }
     }
 
+    /// The outcome string for each nonce, e.g. the binary digits of a price.
+    pub fn attestation_outcome_strings(&self) -> Vec<String> {
+        if self.void {
+            return vec![VOID_OUTCOME.to_string(); self.attestation_indexes().len()];
+        }
+        match self.id.event_kind() {
+            EventKind::Price { is_signed, .. } => {
+                let indexes = self.attestation_indexes();
+                let (sign, digits) = match is_signed && !indexes.is_empty() {
+                    true => (Some(indexes[0]), &indexes[1..]),
+                    false => (None, &indexes[..]),
+                };
+                sign.map(|sign| match sign {
+                    0 => "+".to_string(),
+                    _ => "-".to_string(),
+                })
+                .into_iter()
+                .chain(digits.iter().map(|digit| digit.to_string()))
+                .collect()
+            }
+            EventKind::Score { .. } => self
+                .attestation_indexes()
+                .iter()
+                .map(|digit| digit.to_string())
+                .collect(),
+            EventKind::Ranking { participants, .. } => self
+                .attestation_indexes()
+                .iter()
+                .map(|index| participants[*index as usize].clone())
+                .collect(),
+            _ => vec![self.outcome_string()],
+        }
+    }
+
+    /// If the outcome is out of the range the event's nonces can attest to returns the closest
+    /// outcome that isn't.
+    pub fn clamped(&self) -> Option<Outcome> {
+        if self.void {
+            return None;
+        }
+        let cap = |base: u16, n_digits: u8| {
+            (base as u64)
+                .checked_pow(n_digits as u32)
+                .map(|max| max - 1)
+                .unwrap_or(u64::MAX)
+        };
+        let value = match self.id.event_kind() {
+            EventKind::Price {
+                n_digits,
+                base,
+                is_signed: true,
+                ..
+            } if n_digits > 0 => {
+                let cap = cap(base, n_digits).min(i64::MAX as u64) as i64;
+                (self.value as i64).clamp(-cap, cap) as u64
+            }
+            EventKind::Price {
+                n_digits,
+                base,
+                min,
+                ..
+            } if n_digits > 0 => self
+                .value
+                .clamp(min, min.saturating_add(cap(base, n_digits))),
+            EventKind::Score { n_digits, base } if n_digits > 0 => {
+                let cap = cap(base, n_digits).min(u32::MAX as u64) as u32;
+                let (left, right) = self.score().expect("it's a score event");
+                (left.min(cap) as u64) << 32 | right.min(cap) as u64
+            }
+            _ => return None,
+        };
+        Some(Outcome {
+            value,
+            ..self.clone()
+        })
+        .filter(|clamped| clamped.value != self.value)
+    }
+
+    /// The score of the left and right side if this is the outcome of a score event.
+    pub fn score(&self) -> Option<(u32, u32)> {
+        match self.id.event_kind() {
+            EventKind::Score { .. } if !self.void => {
+                Some(((self.value >> 32) as u32, self.value as u32))
+            }
+            _ => None,
+        }
+    }
+
     pub fn attestation_string(&self) -> Vec<u8> {
         let mut att_string = self.id.as_bytes().to_vec();
         att_string.push('!' as u8);
-        att_string.append(&mut self.value.to_be_bytes().to_vec());
+        match self.void {
+            true => att_string.extend_from_slice(VOID_OUTCOME.as_bytes()),
+            false => att_string.append(&mut self.value.to_be_bytes().to_vec()),
+        }
         att_string
     }
 }
 
+/// The digits of `value` in `base`, most significant first. Values that don't fit in `n_digits`
+/// are clamped to the maximum.
+fn digit_indexes(value: u64, base: u64, n_digits: u8) -> impl Iterator<Item = u32> {
+    let cap = base
+        .checked_pow(n_digits as u32)
+        .map(|max| max - 1)
+        .unwrap_or(u64::MAX);
+    let value = value.min(cap);
+    (0..n_digits as u32).rev().map(move |i| {
+        base.checked_pow(i)
+            .map(|place| (value / place % base) as u32)
+            .unwrap_or(0)
+    })
+}
+
 impl fmt::Display for Outcome {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}:", self.id)?;
@@ -296,6 +550,7 @@ mod test {
         let outcome = Outcome {
             id: EventId::from_str("/foo/bar.price?n=6").unwrap(),
             value: 0b100100,
+            void: false,
         };
 
         assert_eq!(outcome.attestation_indexes(), vec![1, 0, 0, 1, 0, 0]);
@@ -306,6 +561,7 @@ mod test {
         let outcome = Outcome {
             id: EventId::from_str("/foo/bar.price?n=6").unwrap(),
             value: 0b1100100,
+            void: false,
         };
 
         assert_eq!(outcome.attestation_indexes(), vec![1, 1, 1, 1, 1, 1]);
@@ -316,6 +572,7 @@ mod test {
         let outcome = Outcome {
             id: EventId::from_str("/foo/bar.price?n=64").unwrap(),
             value: u64::MAX - 1,
+            void: false,
         };
 
         assert_eq!(
@@ -330,8 +587,233 @@ mod test {
         let outcome = Outcome {
             id: EventId::from_str("/foo/bar.price").unwrap(),
             value: u64::MAX - 1,
+            void: false,
         };
 
         assert_eq!(outcome.attestation_indexes(), vec![0u32; 0]);
     }
+
+    #[test]
+    fn signed_price_attestation_indexes() {
+        let id = EventId::from_str("/foo/bar.price?n=6&signed").unwrap();
+        let negative = Outcome::try_from_id_and_outcome(id.clone(), "-36").unwrap();
+        assert_eq!(negative.outcome_string(), "-36");
+        assert_eq!(negative.attestation_indexes(), vec![1, 1, 0, 0, 1, 0, 0]);
+        assert_eq!(
+            negative.attestation_outcome_strings(),
+            ["-", "1", "0", "0", "1", "0", "0"]
+        );
+
+        let positive = Outcome::try_from_id_and_outcome(id.clone(), "36").unwrap();
+        assert_eq!(positive.attestation_indexes(), vec![0, 1, 0, 0, 1, 0, 0]);
+
+        let overflow = Outcome::try_from_id_and_outcome(id.clone(), "-100").unwrap();
+        assert_eq!(overflow.attestation_indexes(), vec![1, 1, 1, 1, 1, 1, 1]);
+
+        let min = Outcome {
+            id,
+            value: i64::MIN as u64,
+            void: false,
+        };
+        assert_eq!(min.outcome_string(), i64::MIN.to_string());
+        assert_eq!(min.attestation_indexes(), vec![1, 1, 1, 1, 1, 1, 1]);
+
+        assert!(Outcome::try_from_id_and_outcome(
+            EventId::from_str("/foo/bar.price?n=6").unwrap(),
+            "-36"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn fixed_point_price_outcomes() {
+        let id = EventId::from_str("/foo/bar.price?n=20&precision=2&unit=usd&min=10000").unwrap();
+        let outcome = Outcome::try_from_id_and_outcome(id.clone(), "10000.5").unwrap();
+        assert_eq!(outcome.value, 1_000_050);
+        assert_eq!(outcome.outcome_string(), "10000.50");
+        // the digits attest to how many cents above the min it is
+        assert_eq!(
+            outcome.attestation_indexes(),
+            digit_indexes(50, 2, 20).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Outcome::try_from_id_and_outcome(id.clone(), "9000")
+                .unwrap()
+                .attestation_indexes(),
+            vec![0; 20]
+        );
+        assert_eq!(
+            Outcome::try_from_id_and_outcome(id.clone(), "12")
+                .unwrap()
+                .outcome_string(),
+            "12.00"
+        );
+        for invalid in ["10000.505", "10000.", ".5", "-1", "1e3", "ten"] {
+            assert!(
+                Outcome::try_from_id_and_outcome(id.clone(), invalid).is_err(),
+                "{}",
+                invalid
+            );
+        }
+
+        let signed = EventId::from_str("/foo/bar.price?n=10&signed&precision=3").unwrap();
+        let outcome = Outcome::try_from_id_and_outcome(signed, "-0.25").unwrap();
+        assert_eq!(outcome.value as i64, -250);
+        assert_eq!(outcome.outcome_string(), "-0.250");
+    }
+
+    #[test]
+    fn clamped_outcomes() {
+        let clamped = |id: &str, outcome: &str| {
+            Outcome::try_from_id_and_outcome(EventId::from_str(id).unwrap(), outcome)
+                .unwrap()
+                .clamped()
+                .map(|clamped| clamped.outcome_string())
+        };
+        assert_eq!(clamped("/foo/bar.price?n=6", "63"), None);
+        assert_eq!(clamped("/foo/bar.price?n=6", "64").as_deref(), Some("63"));
+        assert_eq!(clamped("/foo/bar.price", "1000000"), None);
+        assert_eq!(
+            clamped("/foo/bar.price?n=6&signed", "-100").as_deref(),
+            Some("-63")
+        );
+        assert_eq!(
+            clamped("/foo/bar.price?n=2&b=10&precision=1&min=5", "1.5").as_deref(),
+            Some("5.0")
+        );
+        assert_eq!(
+            clamped("/foo/bar.price?n=2&b=10&precision=1&min=5", "20").as_deref(),
+            Some("14.9")
+        );
+        assert_eq!(
+            clamped("/foo/bar/FOO_BAR.score?n=1&b=10", "12-3").as_deref(),
+            Some("9-3")
+        );
+        assert_eq!(clamped("/foo/bar/FOO_BAR.score?n=1", "void"), None);
+    }
+
+    #[test]
+    fn base_10_attestation_indexes() {
+        let id = EventId::from_str("/foo/bar.price?n=6&b=10").unwrap();
+        let outcome = Outcome::try_from_id_and_outcome(id.clone(), "4213").unwrap();
+        assert_eq!(outcome.attestation_indexes(), vec![0, 0, 4, 2, 1, 3]);
+        assert_eq!(
+            outcome.attestation_outcome_strings(),
+            ["0", "0", "4", "2", "1", "3"]
+        );
+
+        let overflow = Outcome::try_from_id_and_outcome(id, "1000000").unwrap();
+        assert_eq!(overflow.attestation_indexes(), vec![9; 6]);
+
+        let outcome = Outcome::try_from_id_and_outcome(
+            EventId::from_str("/foo/bar.price?n=4&b=16&signed").unwrap(),
+            "-4096",
+        )
+        .unwrap();
+        assert_eq!(outcome.attestation_indexes(), vec![1, 1, 0, 0, 0]);
+        assert_eq!(
+            outcome.attestation_outcome_strings(),
+            ["-", "1", "0", "0", "0"]
+        );
+
+        let outcome = Outcome {
+            id: EventId::from_str("/foo/bar.price?n=64&b=10").unwrap(),
+            value: u64::MAX,
+            void: false,
+        };
+        let indexes = outcome.attestation_indexes();
+        assert_eq!(indexes.len(), 64);
+        assert_eq!(
+            indexes
+                .iter()
+                .skip_while(|digit| **digit == 0)
+                .map(|digit| digit.to_string())
+                .collect::<String>(),
+            u64::MAX.to_string()
+        );
+    }
+
+    #[test]
+    fn choice_outcomes() {
+        let id = EventId::from_str("/foo/bar.choice?o=red,green,blue").unwrap();
+        let outcome = Outcome::try_from_id_and_outcome(id.clone(), "blue").unwrap();
+        assert_eq!(outcome.value, 2);
+        assert_eq!(outcome.outcome_string(), "blue");
+        assert_eq!(outcome.attestation_indexes(), vec![2]);
+        assert!(Outcome::try_from_id_and_outcome(id, "pink").is_err());
+    }
+
+    #[test]
+    fn void_outcomes() {
+        for (id, indexes) in [
+            ("/foo/bar.occur", vec![1]),
+            ("/foo/bar/FOO_BAR.vs", vec![3]),
+            ("/foo/bar.choice?o=red,green,blue", vec![3]),
+            ("/foo/bar.price_5", vec![2]),
+            ("/foo/bar.price?n=3", vec![2, 2, 2]),
+            ("/foo/bar.price?n=2&b=10&signed", vec![2, 10, 10]),
+            ("/foo/bar.price", vec![]),
+        ] {
+            let id = EventId::from_str(id).unwrap();
+            let outcome = Outcome::try_from_id_and_outcome(id.clone(), "void").unwrap();
+            assert_eq!(outcome, Outcome::void(id));
+            assert_eq!(outcome.outcome_string(), "void");
+            assert_eq!(outcome.attestation_indexes(), indexes);
+            assert!(outcome
+                .attestation_outcome_strings()
+                .iter()
+                .all(|outcome| outcome == "void"));
+        }
+
+        let id = EventId::from_str("/foo/bar.price?n=6").unwrap();
+        assert_ne!(
+            Outcome::void(id.clone()).attestation_string(),
+            Outcome {
+                id,
+                value: 0,
+                void: false
+            }
+            .attestation_string()
+        );
+        assert!(EventId::from_str("/foo/bar/FOO_BAR.vs=void").is_err());
+        assert!(EventId::from_str("/foo/bar/FOO_VOID.winner").is_ok());
+        assert!(EventId::from_str("/foo/bar/FOO_void.winner").is_err());
+    }
+
+    #[test]
+    fn score_outcomes() {
+        let id = EventId::from_str("/s/EPL/match/2021-08-13/BRE_ARS.score?n=3&b=10").unwrap();
+        assert_eq!(id.n_nonces(), 6);
+        let outcome = Outcome::try_from_id_and_outcome(id.clone(), "2-13").unwrap();
+        assert_eq!(outcome.score(), Some((2, 13)));
+        assert_eq!(outcome.outcome_string(), "2-13");
+        assert_eq!(outcome.attestation_indexes(), vec![0, 0, 2, 0, 1, 3]);
+        assert_eq!(
+            outcome.attestation_outcome_strings(),
+            ["0", "0", "2", "0", "1", "3"]
+        );
+
+        let overflow = Outcome::try_from_id_and_outcome(id.clone(), "1000-0").unwrap();
+        assert_eq!(overflow.attestation_indexes(), vec![9, 9, 9, 0, 0, 0]);
+
+        assert!(Outcome::try_from_id_and_outcome(id.clone(), "2").is_err());
+        assert!(Outcome::try_from_id_and_outcome(id.clone(), "-1-2").is_err());
+        assert!(Outcome::try_from_id_and_outcome(id, "BRE").is_err());
+    }
+
+    #[test]
+    fn ranking_outcomes() {
+        let id = EventId::from_str("/f1/2021/monza.rank?p=HAM,VER,LEC,NOR&n=3").unwrap();
+        assert_eq!(id.n_nonces(), 3);
+        assert_eq!(id.n_outcomes_for_nonce(2), 5);
+        let outcome = Outcome::try_from_id_and_outcome(id.clone(), "NOR,HAM,VER").unwrap();
+        assert_eq!(outcome.outcome_string(), "NOR,HAM,VER");
+        assert_eq!(outcome.attestation_indexes(), vec![3, 0, 1]);
+        assert_eq!(outcome.attestation_outcome_strings(), ["NOR", "HAM", "VER"]);
+
+        assert!(Outcome::try_from_id_and_outcome(id.clone(), "NOR,HAM").is_err());
+        assert!(Outcome::try_from_id_and_outcome(id.clone(), "NOR,HAM,HAM").is_err());
+        assert!(Outcome::try_from_id_and_outcome(id.clone(), "NOR,HAM,BOT").is_err());
+        assert!(Outcome::try_from_id_and_outcome(id, "NOR,HAM,VER,LEC").is_err());
+    }
 }
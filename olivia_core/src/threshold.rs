@@ -0,0 +1,339 @@
+//! k-of-n attestation by several independent oracles.
+//!
+//! Every oracle announces the same event with its own olivia-v1 nonces. For each set of `k`
+//! oracles the anticipated attestation points of each nonce are added together so that a CET
+//! encrypted to the combined point can only be decrypted once all `k` of them attest to the
+//! outcome.
+use crate::{
+    Attestation, AttestationInvalid, EventId, Group, OracleEvent, OracleKeys, Outcome,
+    RawAnnouncement,
+};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum QuorumError {
+    #[error("threshold {threshold} is not between 1 and the number of oracles ({n_oracles})")]
+    InvalidThreshold { threshold: usize, n_oracles: usize },
+    #[error("announcement from oracle {oracle} is invalid")]
+    InvalidAnnouncement { oracle: usize },
+    #[error("oracle {oracle} didn't announce olivia-v1 nonces for the event")]
+    MissingOliviaV1 { oracle: usize },
+    #[error("{0:?} is not a set of {1} distinct oracles")]
+    InvalidQuorum(Vec<usize>, usize),
+    #[error("there is no oracle {oracle} in the quorum")]
+    UnknownOracle { oracle: usize },
+    #[error("at most {attested} distinct oracles validly attested to the same outcome but {threshold} are needed")]
+    NotEnoughAttestations {
+        attested: usize,
+        threshold: usize,
+        /// The attestations that were left out and why
+        invalid: Vec<(usize, AttestationInvalid)>,
+    },
+    #[error("combined attestation doesn't match the anticipated attestation points")]
+    Mismatch,
+}
+
+/// An oracle that announced the event along with the attestation key for it
+#[derive(Debug, Clone, PartialEq)]
+struct QuorumOracle<C: Group> {
+    keys: OracleKeys<C>,
+    attestation_key: C::PublicKey,
+    oracle_event: OracleEvent<C>,
+}
+
+/// An oracle's index and its olivia-v1 attest scalars for an outcome
+type OracleScalars<C> = (usize, Vec<<C as Group>::AttestScalar>);
+
+/// The announcements of `n` oracles for an event where any `threshold` of them must attest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quorum<C: Group> {
+    event_id: EventId,
+    threshold: usize,
+    oracles: Vec<QuorumOracle<C>>,
+}
+
+/// The attestation of a quorum of oracles to an outcome
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuorumAttestation<C: Group> {
+    pub outcome: Outcome,
+    /// The oracles whose attestations were combined
+    pub oracles: Vec<usize>,
+    /// The sum of the oracles' attest scalars for each nonce
+    pub scalars: Vec<C::AttestScalar>,
+}
+
+impl<C: Group> Quorum<C> {
+    /// Checks each oracle's announcement against `event_id`. Oracles are referred to by their
+    /// index in `announcements` from then on.
+    pub fn new(
+        event_id: EventId,
+        threshold: usize,
+        announcements: Vec<(OracleKeys<C>, RawAnnouncement<C>)>,
+    ) -> Result<Self, QuorumError> {
+        if threshold == 0 || threshold > announcements.len() {
+            return Err(QuorumError::InvalidThreshold {
+                threshold,
+                n_oracles: announcements.len(),
+            });
+        }
+
+        let oracles = announcements
+            .into_iter()
+            .enumerate()
+            .map(|(oracle, (keys, announcement))| {
+                let oracle_event = announcement
+                    .verify_against_id(&event_id, &keys.announcement)
+                    .ok_or(QuorumError::InvalidAnnouncement { oracle })?;
+                let n_nonces = oracle_event
+                    .schemes
+                    .olivia_v1
+                    .as_ref()
+                    .map(|olivia_v1| olivia_v1.nonces.len());
+                let attestation_key = keys.olivia_v1_key_for(event_id.path()).cloned();
+                match (n_nonces, attestation_key) {
+                    (Some(n_nonces), Some(attestation_key))
+                        if n_nonces == event_id.n_nonces() as usize =>
+                    {
+                        Ok(QuorumOracle {
+                            keys,
+                            attestation_key,
+                            oracle_event,
+                        })
+                    }
+                    _ => Err(QuorumError::MissingOliviaV1 { oracle }),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            event_id,
+            threshold,
+            oracles,
+        })
+    }
+
+    pub fn event_id(&self) -> &EventId {
+        &self.event_id
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn n_oracles(&self) -> usize {
+        self.oracles.len()
+    }
+
+    /// Every set of `threshold` oracles that can attest to the event
+    pub fn quorums(&self) -> Vec<Vec<usize>> {
+        combinations(self.oracles.len(), self.threshold)
+    }
+
+    fn check_quorum(&self, quorum: &[usize]) -> Result<(), QuorumError> {
+        let distinct = quorum
+            .iter()
+            .enumerate()
+            .all(|(i, oracle)| *oracle < self.oracles.len() && !quorum[..i].contains(oracle));
+        if quorum.len() != self.threshold || !distinct {
+            return Err(QuorumError::InvalidQuorum(quorum.to_vec(), self.threshold));
+        }
+        Ok(())
+    }
+
+    /// The combined anticipated attestation points of the oracles in `quorum`. The result has
+    /// an entry for each nonce which has the point for each of the nonce's outcome indexes.
+    pub fn anticipate_attestations(
+        &self,
+        quorum: &[usize],
+    ) -> Result<Vec<Vec<C::AnticipatedAttestation>>, QuorumError> {
+        self.check_quorum(quorum)?;
+        let n_nonces = self.event_id.n_nonces() as usize;
+        let per_oracle = quorum
+            .iter()
+            .map(|oracle| {
+                let oracle = &self.oracles[*oracle];
+                (0..n_nonces)
+                    .map(|nonce_index| {
+                        oracle
+                            .oracle_event
+                            .anticipate_attestations_olivia_v1(&oracle.attestation_key, nonce_index)
+                            .expect("checked the oracle has olivia-v1 nonces")
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        Ok((0..n_nonces)
+            .map(|nonce_index| {
+                let n_outcomes = self.event_id.n_outcomes_for_nonce(nonce_index) as usize;
+                (0..n_outcomes)
+                    .map(|outcome_index| {
+                        let points = per_oracle
+                            .iter()
+                            .map(|nonces| nonces[nonce_index][outcome_index].clone())
+                            .collect::<Vec<_>>();
+                        C::combine_anticipated_attestations(&points)
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// The combined anticipated attestation points of the oracles in `quorum` for `outcome`, one
+    /// for each nonce.
+    pub fn anticipate_outcome(
+        &self,
+        quorum: &[usize],
+        outcome: &Outcome,
+    ) -> Result<Vec<C::AnticipatedAttestation>, QuorumError> {
+        let anticipated = self.anticipate_attestations(quorum)?;
+        Ok(outcome
+            .attestation_indexes()
+            .iter()
+            .zip(anticipated)
+            .map(|(index, mut points)| points.swap_remove(*index as usize))
+            .collect())
+    }
+
+    /// Checks an oracle's attestation returning the outcome and the olivia-v1 scalars
+    fn check_attestation(
+        &self,
+        oracle: &QuorumOracle<C>,
+        attestation: &Attestation<C>,
+    ) -> Result<(Outcome, Vec<C::AttestScalar>), AttestationInvalid> {
+        attestation.verify_olivia_v1_attestation(&oracle.oracle_event, &oracle.keys)?;
+        let outcome = Outcome::try_from_id_and_outcome(self.event_id.clone(), &attestation.outcome)
+            .map_err(|_| AttestationInvalid::Outcome)?;
+        let scalars = attestation
+            .schemes
+            .olivia_v1
+            .as_ref()
+            .ok_or(AttestationInvalid::Missing)?
+            .scalars
+            .clone();
+        Ok((outcome, scalars))
+    }
+
+    /// Checks that at least `threshold` distinct oracles validly attested to the same outcome and
+    /// that the sum of the first `threshold` of their attestations matches the anticipated points.
+    /// Attestations are paired with the index of the oracle that made them. Invalid attestations
+    /// and those to other outcomes are left out so a faulty oracle can't stop the rest from
+    /// reaching the threshold. If more than one outcome reaches it the one with the most
+    /// attestations wins, or the one seen first if they have the same number.
+    pub fn verify_attestations(
+        &self,
+        attestations: &[(usize, Attestation<C>)],
+    ) -> Result<QuorumAttestation<C>, QuorumError> {
+        // the valid attestations to each outcome in the order the outcomes were first seen
+        let mut by_outcome: Vec<(Outcome, Vec<OracleScalars<C>>)> = vec![];
+        let mut invalid = vec![];
+        for (oracle, attestation) in attestations {
+            let quorum_oracle = self
+                .oracles
+                .get(*oracle)
+                .ok_or(QuorumError::UnknownOracle { oracle: *oracle })?;
+            let (outcome, scalars) = match self.check_attestation(quorum_oracle, attestation) {
+                Ok(checked) => checked,
+                Err(error) => {
+                    invalid.push((*oracle, error));
+                    continue;
+                }
+            };
+            let attested = match by_outcome.iter_mut().find(|(other, _)| *other == outcome) {
+                Some((_, attested)) => attested,
+                None => {
+                    by_outcome.push((outcome, vec![]));
+                    &mut by_outcome.last_mut().unwrap().1
+                }
+            };
+            if !attested.iter().any(|(other, _)| other == oracle) {
+                attested.push((*oracle, scalars));
+            }
+        }
+
+        let most_attested = by_outcome
+            .into_iter()
+            .min_by_key(|(_, attested)| core::cmp::Reverse(attested.len()));
+        let (outcome, mut attested) = match most_attested {
+            Some((outcome, attested)) if attested.len() >= self.threshold => (outcome, attested),
+            most_attested => {
+                return Err(QuorumError::NotEnoughAttestations {
+                    attested: most_attested
+                        .map(|(_, attested)| attested.len())
+                        .unwrap_or(0),
+                    threshold: self.threshold,
+                    invalid,
+                })
+            }
+        };
+        attested.truncate(self.threshold);
+        let (quorum, scalars): (Vec<_>, Vec<_>) = attested.into_iter().unzip();
+
+        let anticipated = self.anticipate_outcome(&quorum, &outcome)?;
+        let combined = (0..anticipated.len())
+            .map(|nonce_index| {
+                let nonce_scalars = scalars
+                    .iter()
+                    .map(|scalars| scalars[nonce_index].clone())
+                    .collect::<Vec<_>>();
+                C::combine_attest_scalars(&nonce_scalars)
+            })
+            .collect::<Vec<_>>();
+
+        if !anticipated
+            .iter()
+            .zip(&combined)
+            .all(|(point, scalar)| C::attest_scalar_matches(point, scalar))
+        {
+            return Err(QuorumError::Mismatch);
+        }
+
+        Ok(QuorumAttestation {
+            outcome,
+            oracles: quorum,
+            scalars: combined,
+        })
+    }
+}
+
+/// Every `k` sized subset of `0..n` in lexicographic order
+fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k == 0 || k > n {
+        return vec![];
+    }
+    let mut combinations = vec![];
+    let mut current: Vec<usize> = (0..k).collect();
+    loop {
+        combinations.push(current.clone());
+        // find the rightmost element that can still be incremented
+        let i = match (0..k).rev().find(|i| current[*i] != i + n - k) {
+            Some(i) => i,
+            None => return combinations,
+        };
+        current[i] += 1;
+        for j in i + 1..k {
+            current[j] = current[j - 1] + 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn combinations_of_oracles() {
+        assert_eq!(
+            combinations(4, 2),
+            vec![
+                vec![0, 1],
+                vec![0, 2],
+                vec![0, 3],
+                vec![1, 2],
+                vec![1, 3],
+                vec![2, 3]
+            ]
+        );
+        assert_eq!(combinations(3, 3), vec![vec![0, 1, 2]]);
+        assert_eq!(combinations(2, 3), Vec::<Vec<usize>>::new());
+    }
+}
@@ -0,0 +1,516 @@
+//! Binary encodings of oracle messages as described in the [DLC spec].
+//!
+//! [DLC spec]: https://github.com/discreetlogcontracts/dlcspecs/blob/master/Messaging.md#oracle-announcements
+use crate::{
+    announce, AnnouncementSchemes, Descriptor, Event, EventId, Group, OracleEvent, ToFromBytes,
+};
+use chrono::NaiveDateTime;
+use core::{
+    convert::{TryFrom, TryInto},
+    str::FromStr,
+};
+
+pub const ORACLE_EVENT_TYPE: u64 = 55330;
+pub const ORACLE_ANNOUNCEMENT_TYPE: u64 = 55332;
+pub const ENUM_EVENT_DESCRIPTOR_TYPE: u64 = 55302;
+pub const DIGIT_DECOMPOSITION_EVENT_DESCRIPTOR_TYPE: u64 = 55306;
+pub const ORACLE_ATTESTATION_TYPE: u64 = 55400;
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum TlvError {
+    #[error("unexpected end of TLV data")]
+    UnexpectedEnd,
+    #[error("expected TLV type {expected} but got {got}")]
+    UnexpectedType { expected: u64, got: u64 },
+    #[error("TLV value had {0} unexpected trailing bytes")]
+    TrailingBytes(usize),
+    #[error("bigsize integer was not minimally encoded")]
+    NonCanonicalBigSize,
+    #[error("invalid TLV: {0}")]
+    Invalid(String),
+}
+
+pub(crate) fn write_bigsize(out: &mut Vec<u8>, n: u64) {
+    match n {
+        0..=0xfc => out.push(n as u8),
+        0xfd..=0xffff => {
+            out.push(0xfd);
+            out.extend_from_slice(&(n as u16).to_be_bytes());
+        }
+        0x10000..=0xffff_ffff => {
+            out.push(0xfe);
+            out.extend_from_slice(&(n as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(0xff);
+            out.extend_from_slice(&n.to_be_bytes());
+        }
+    }
+}
+
+pub(crate) fn write_string(out: &mut Vec<u8>, string: &str) {
+    write_bigsize(out, string.len() as u64);
+    out.extend_from_slice(string.as_bytes());
+}
+
+/// Writes a TLV record whose value is whatever `write_value` puts into the buffer it's passed.
+pub(crate) fn write_tlv(out: &mut Vec<u8>, tlv_type: u64, write_value: impl FnOnce(&mut Vec<u8>)) {
+    let mut value = vec![];
+    write_value(&mut value);
+    write_bigsize(out, tlv_type);
+    write_bigsize(out, value.len() as u64);
+    out.extend_from_slice(&value);
+}
+
+pub(crate) struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn take(&mut self, n: usize) -> Result<&'a [u8], TlvError> {
+        if self.0.len() < n {
+            return Err(TlvError::UnexpectedEnd);
+        }
+        let (taken, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], TlvError> {
+        Ok(self.take(N)?.try_into().expect("took exactly N bytes"))
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, TlvError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, TlvError> {
+        Ok(u16::from_be_bytes(self.take_array()?))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, TlvError> {
+        Ok(u32::from_be_bytes(self.take_array()?))
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32, TlvError> {
+        Ok(i32::from_be_bytes(self.take_array()?))
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, TlvError> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(TlvError::Invalid("boolean must be 0 or 1".into())),
+        }
+    }
+
+    pub fn read_bigsize(&mut self) -> Result<u64, TlvError> {
+        let (n, min) = match self.read_u8()? {
+            0xfd => (self.read_u16()? as u64, 0xfd),
+            0xfe => (self.read_u32()? as u64, 0x10000),
+            0xff => (u64::from_be_bytes(self.take_array()?), 0x1_0000_0000),
+            n => return Ok(n as u64),
+        };
+        if n < min {
+            return Err(TlvError::NonCanonicalBigSize);
+        }
+        Ok(n)
+    }
+
+    pub fn read_string(&mut self) -> Result<String, TlvError> {
+        let len = self.read_bigsize()?;
+        let bytes = self.take(len.try_into().map_err(|_| TlvError::UnexpectedEnd)?)?;
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| TlvError::Invalid("string was not valid UTF-8".into()))
+    }
+
+    pub fn read_object<T: ToFromBytes>(&mut self, name: &str) -> Result<T, TlvError> {
+        T::from_bytes(self.take(T::LEN)?)
+            .ok_or_else(|| TlvError::Invalid(format!("{} was not validly encoded", name)))
+    }
+
+    /// Reads a TLV record of the expected type and returns a reader over its value.
+    pub fn read_tlv(&mut self, expected: u64) -> Result<Reader<'a>, TlvError> {
+        let (got, value) = self.read_any_tlv()?;
+        if got != expected {
+            return Err(TlvError::UnexpectedType { expected, got });
+        }
+        Ok(value)
+    }
+
+    pub fn read_any_tlv(&mut self) -> Result<(u64, Reader<'a>), TlvError> {
+        let tlv_type = self.read_bigsize()?;
+        let len = self.read_bigsize()?;
+        let value = self.take(len.try_into().map_err(|_| TlvError::UnexpectedEnd)?)?;
+        Ok((tlv_type, Reader(value)))
+    }
+
+    pub fn finish(self) -> Result<(), TlvError> {
+        match self.0.len() {
+            0 => Ok(()),
+            n => Err(TlvError::TrailingBytes(n)),
+        }
+    }
+}
+
+/// The value of an `oracle_event` TLV.
+///
+/// This is the wire format of [`OracleEvent`] so it can hold events that aren't valid olivia events.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OracleEventTlv<C: Group> {
+    pub nonces: Vec<C::PublicNonce>,
+    pub event_maturity_epoch: u32,
+    pub descriptor: Descriptor,
+    pub event_id: String,
+}
+
+impl<C: Group> OracleEventTlv<C> {
+    /// Encodes the value of the `oracle_event` (this is what the announcement signature covers).
+    ///
+    /// Returns `None` if the descriptor can't be represented in the DLC spec.
+    pub fn encode(&self) -> Option<Vec<u8>> {
+        let mut out = vec![];
+        out.extend_from_slice(&(self.nonces.len() as u16).to_be_bytes());
+        for nonce in &self.nonces {
+            out.extend_from_slice(&nonce.to_bytes());
+        }
+        out.extend_from_slice(&self.event_maturity_epoch.to_be_bytes());
+        write_descriptor(&mut out, &self.descriptor)?;
+        write_string(&mut out, &self.event_id);
+        Some(out)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, TlvError> {
+        let mut reader = Reader::new(bytes);
+        let n_nonces = reader.read_u16()?;
+        let nonces = (0..n_nonces)
+            .map(|_| reader.read_object("nonce"))
+            .collect::<Result<Vec<_>, _>>()?;
+        let event_maturity_epoch = reader.read_u32()?;
+        let descriptor = read_descriptor(&mut reader)?;
+        let event_id = reader.read_string()?;
+        reader.finish()?;
+
+        Ok(Self {
+            nonces,
+            event_maturity_epoch,
+            descriptor,
+            event_id,
+        })
+    }
+}
+
+fn write_descriptor(out: &mut Vec<u8>, descriptor: &Descriptor) -> Option<()> {
+    match descriptor {
+        Descriptor::Enum { outcomes } => write_tlv(out, ENUM_EVENT_DESCRIPTOR_TYPE, |out| {
+            out.extend_from_slice(&(outcomes.len() as u16).to_be_bytes());
+            for outcome in outcomes {
+                write_string(out, outcome);
+            }
+        }),
+        Descriptor::DigitDecomposition {
+            base,
+            is_signed,
+            n_digits,
+            unit,
+            precision,
+        } => write_tlv(out, DIGIT_DECOMPOSITION_EVENT_DESCRIPTOR_TYPE, |out| {
+            out.extend_from_slice(&base.to_be_bytes());
+            out.push(*is_signed as u8);
+            write_string(out, unit.as_deref().unwrap_or(""));
+            out.extend_from_slice(&precision.to_be_bytes());
+            out.extend_from_slice(&(*n_digits as u16).to_be_bytes());
+        }),
+        Descriptor::Ranking { .. } | Descriptor::MissingDescriptor => return None,
+    }
+    Some(())
+}
+
+fn read_descriptor(reader: &mut Reader<'_>) -> Result<Descriptor, TlvError> {
+    let descriptor = match reader.read_any_tlv()? {
+        (ENUM_EVENT_DESCRIPTOR_TYPE, mut value) => {
+            let n_outcomes = value.read_u16()?;
+            let outcomes = (0..n_outcomes)
+                .map(|_| value.read_string())
+                .collect::<Result<Vec<_>, _>>()?;
+            value.finish()?;
+            Descriptor::Enum { outcomes }
+        }
+        (DIGIT_DECOMPOSITION_EVENT_DESCRIPTOR_TYPE, mut value) => {
+            let base = value.read_u16()?;
+            let is_signed = value.read_bool()?;
+            let unit = value.read_string()?;
+            let precision = value.read_i32()?;
+            let n_digits = value.read_u16()?;
+            value.finish()?;
+            if base < 2 {
+                return Err(TlvError::Invalid(format!("invalid base {}", base)));
+            }
+            // olivia events can only have decimal places
+            if !(-18..=0).contains(&precision) {
+                return Err(TlvError::Invalid(format!(
+                    "unsupported precision {}",
+                    precision
+                )));
+            }
+            Descriptor::DigitDecomposition {
+                base,
+                is_signed,
+                n_digits: n_digits
+                    .try_into()
+                    .map_err(|_| TlvError::Invalid("too many digits".into()))?,
+                unit: Some(unit).filter(|unit| !unit.is_empty()),
+                precision,
+            }
+        }
+        (got, _) => {
+            return Err(TlvError::UnexpectedType {
+                expected: ENUM_EVENT_DESCRIPTOR_TYPE,
+                got,
+            })
+        }
+    };
+    Ok(descriptor)
+}
+
+impl<C: Group> From<OracleEvent<C>> for OracleEventTlv<C> {
+    fn from(oracle_event: OracleEvent<C>) -> Self {
+        let descriptor = oracle_event.event.id.descriptor();
+        OracleEventTlv {
+            nonces: oracle_event
+                .schemes
+                .dlc_v0
+                .map(|dlc_v0| dlc_v0.nonces)
+                .unwrap_or_default(),
+            event_maturity_epoch: oracle_event
+                .event
+                .expected_outcome_time
+                .and_then(|time| time.timestamp().try_into().ok())
+                .unwrap_or(0),
+            descriptor,
+            event_id: oracle_event.event.id.into(),
+        }
+    }
+}
+
+impl<C: Group> TryFrom<OracleEventTlv<C>> for OracleEvent<C> {
+    type Error = TlvError;
+
+    fn try_from(oracle_event: OracleEventTlv<C>) -> Result<Self, Self::Error> {
+        let id = EventId::from_str(&oracle_event.event_id)
+            .map_err(|e| TlvError::Invalid(format!("invalid event id: {}", e)))?;
+
+        if id.descriptor() != oracle_event.descriptor {
+            return Err(TlvError::Invalid(
+                "descriptor doesn't match event id".into(),
+            ));
+        }
+
+        if oracle_event.nonces.len() < id.n_nonces() as usize {
+            return Err(TlvError::Invalid(
+                "oracle event doesn't have enough nonces for descriptor".into(),
+            ));
+        }
+
+        let expected_outcome_time = match oracle_event.event_maturity_epoch {
+            0 => None,
+            epoch => Some(NaiveDateTime::from_timestamp(epoch as i64, 0)),
+        };
+
+        Ok(OracleEvent {
+            event: Event {
+                id,
+                expected_outcome_time,
+            },
+            // only the DLC spec's own scheme can be expressed in the TLV encoding
+            schemes: AnnouncementSchemes {
+                dlc_v0: match oracle_event.nonces.is_empty() {
+                    true => None,
+                    false => Some(announce::DlcV0 {
+                        nonces: oracle_event.nonces,
+                    }),
+                },
+                ..Default::default()
+            },
+        })
+    }
+}
+
+/// Encodes an `oracle_announcement` TLV record from its parts.
+pub fn encode_oracle_announcement<C: Group>(
+    signature: &C::Signature,
+    public_key: &C::PublicKey,
+    oracle_event: &[u8],
+) -> Vec<u8> {
+    let mut out = vec![];
+    write_tlv(&mut out, ORACLE_ANNOUNCEMENT_TYPE, |out| {
+        out.extend_from_slice(&signature.to_bytes());
+        out.extend_from_slice(&public_key.to_bytes());
+        write_tlv(out, ORACLE_EVENT_TYPE, |out| {
+            out.extend_from_slice(oracle_event)
+        });
+    });
+    out
+}
+
+/// The parts of a decoded `oracle_announcement` TLV record.
+pub struct OracleAnnouncementTlv<'a, C: Group> {
+    pub signature: C::Signature,
+    pub public_key: C::PublicKey,
+    /// The value of the `oracle_event` record.
+    pub oracle_event: &'a [u8],
+}
+
+pub fn decode_oracle_announcement<C: Group>(
+    bytes: &[u8],
+) -> Result<OracleAnnouncementTlv<'_, C>, TlvError> {
+    let mut reader = Reader::new(bytes);
+    let mut value = reader.read_tlv(ORACLE_ANNOUNCEMENT_TYPE)?;
+    reader.finish()?;
+    let signature = value.read_object("announcement signature")?;
+    let public_key = value.read_object("oracle public key")?;
+    let mut oracle_event = value.read_tlv(ORACLE_EVENT_TYPE)?;
+    value.finish()?;
+    let oracle_event_len = oracle_event.0.len();
+    Ok(OracleAnnouncementTlv {
+        signature,
+        public_key,
+        oracle_event: oracle_event.take(oracle_event_len)?,
+    })
+}
+
+/// An `oracle_attestation` TLV.
+///
+/// Each signature is the dlc-v0 signature on the outcome string for the nonce at that position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OracleAttestationTlv<C: Group> {
+    pub event_id: String,
+    pub public_key: C::PublicKey,
+    pub signatures: Vec<C::Signature>,
+    pub outcomes: Vec<String>,
+}
+
+impl<C: Group> OracleAttestationTlv<C> {
+    /// Encodes the full `oracle_attestation` TLV record.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = vec![];
+        write_tlv(&mut out, ORACLE_ATTESTATION_TYPE, |out| {
+            write_string(out, &self.event_id);
+            out.extend_from_slice(&self.public_key.to_bytes());
+            out.extend_from_slice(&(self.signatures.len() as u16).to_be_bytes());
+            for signature in &self.signatures {
+                out.extend_from_slice(&signature.to_bytes());
+            }
+            out.extend_from_slice(&(self.outcomes.len() as u16).to_be_bytes());
+            for outcome in &self.outcomes {
+                write_string(out, outcome);
+            }
+        });
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, TlvError> {
+        let mut reader = Reader::new(bytes);
+        let mut value = reader.read_tlv(ORACLE_ATTESTATION_TYPE)?;
+        reader.finish()?;
+        let event_id = value.read_string()?;
+        let public_key = value.read_object("oracle public key")?;
+        let n_signatures = value.read_u16()?;
+        let signatures = (0..n_signatures)
+            .map(|_| value.read_object("attestation signature"))
+            .collect::<Result<Vec<_>, TlvError>>()?;
+        let n_outcomes = value.read_u16()?;
+        let outcomes = (0..n_outcomes)
+            .map(|_| value.read_string())
+            .collect::<Result<Vec<_>, _>>()?;
+        value.finish()?;
+
+        if signatures.len() != outcomes.len() {
+            return Err(TlvError::Invalid(
+                "number of signatures doesn't match number of outcomes".into(),
+            ));
+        }
+
+        Ok(Self {
+            event_id,
+            public_key,
+            signatures,
+            outcomes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bigsize_roundtrip() {
+        for (n, encoded) in [
+            (0u64, &[0x00][..]),
+            (0xfc, &[0xfc]),
+            (0xfd, &[0xfd, 0x00, 0xfd]),
+            (0xffff, &[0xfd, 0xff, 0xff]),
+            (0x10000, &[0xfe, 0x00, 0x01, 0x00, 0x00]),
+            (0xffffffff, &[0xfe, 0xff, 0xff, 0xff, 0xff]),
+            (
+                0x100000000,
+                &[0xff, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00],
+            ),
+        ] {
+            let mut out = vec![];
+            write_bigsize(&mut out, n);
+            assert_eq!(out, encoded);
+            assert_eq!(Reader::new(encoded).read_bigsize(), Ok(n));
+        }
+    }
+
+    #[test]
+    fn bigsize_must_be_canonical() {
+        assert_eq!(
+            Reader::new(&[0xfd, 0x00, 0xfc]).read_bigsize(),
+            Err(TlvError::NonCanonicalBigSize)
+        );
+        assert_eq!(
+            Reader::new(&[0xfe, 0x00, 0x00, 0xff, 0xff]).read_bigsize(),
+            Err(TlvError::NonCanonicalBigSize)
+        );
+        assert_eq!(
+            Reader::new(&[0xfd, 0x00]).read_bigsize(),
+            Err(TlvError::UnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn descriptor_roundtrip() {
+        for descriptor in [
+            Descriptor::Enum {
+                outcomes: vec!["a".into(), "b".into(), "c".into()],
+            },
+            Descriptor::DigitDecomposition {
+                base: 2,
+                is_signed: false,
+                n_digits: 20,
+                unit: None,
+                precision: 0,
+            },
+            Descriptor::DigitDecomposition {
+                base: 10,
+                is_signed: true,
+                n_digits: 10,
+                unit: Some("sats/sec".into()),
+                precision: -2,
+            },
+        ] {
+            let mut out = vec![];
+            write_descriptor(&mut out, &descriptor).unwrap();
+            let mut reader = Reader::new(&out);
+            assert_eq!(read_descriptor(&mut reader), Ok(descriptor));
+            assert_eq!(reader.finish(), Ok(()));
+        }
+
+        assert!(write_descriptor(&mut vec![], &Descriptor::MissingDescriptor).is_none());
+    }
+}
@@ -3,7 +3,8 @@ extern crate alloc;
 use alloc::{string::String, vec::Vec};
 use core::str::FromStr;
 use olivia_core::{
-    BoundKind, EventId, EventKind, NodeKind, Outcome, Path, PathRef, Predicate, VsMatchKind,
+    BoundKind, CompoundOp, EventId, EventKind, NodeKind, Outcome, Path, PathRef, Predicate,
+    VsMatchKind,
 };
 
 #[cfg(feature = "wasm-bindgen")]
@@ -104,6 +105,9 @@ pub fn path_short(path: PathRef<'_>) -> Option<String> {
         ["time"] => "events that mark the passage of time".into(),
         ["time", time, ..] => format!("events that indicate when {} has passed", time),
         ["random", time, ..] => format!("events whose outcome will be randomly chosen at {}", time),
+        ["bitcoin"] => "events about the bitcoin blockchain".into(),
+        ["bitcoin", "block"] => "events about bitcoin blocks".into(),
+        ["bitcoin", "block", height] => format!("bitcoin block {}", height),
         ["x"] => "exchange rates and prices".to_string(),
         ["x", exchange] => format!("exchange rates and prices on {}", exchange),
         ["x", exchange, instrument, time] if DateTime::parse(time).is_some() => {
@@ -189,6 +193,31 @@ pub fn event_short(event_id: &EventId) -> String {
                 ),
             }
         }
+        (["s", competition, "match", date, _], EventKind::Score { .. }) => {
+            let (left, right) = event_id.parties().unwrap();
+            format!(
+                "score of {} match {} vs {} on {}",
+                lookup_competition(competition),
+                lookup_team(competition, left),
+                lookup_team(competition, right),
+                date
+            )
+        }
+        ([..], EventKind::Score { .. }) => {
+            let (left, right) = event_id.parties().unwrap();
+            format!(
+                "score of {} vs {} in {}",
+                left,
+                right,
+                event_id.path().parent().unwrap()
+            )
+        }
+        (["bitcoin", "block", height], EventKind::SingleOccurrence) => {
+            format!("mining of bitcoin block {}", height)
+        }
+        (["bitcoin", "block", height, field], EventKind::Price { .. }) => {
+            format!("{} of bitcoin block {}", block_field_desc(field), height)
+        }
         (["time", datetime], EventKind::SingleOccurrence) => {
             format!("time {} has passed", datetime)
         }
@@ -198,20 +227,40 @@ pub fn event_short(event_id: &EventId) -> String {
             datetime
         ),
         (_, EventKind::SingleOccurrence) => format!("{} has transpired", event_id.path()),
+        (_, EventKind::Boolean) => format!("answer to the question \"{}?\"", event_id.path()),
+        (_, EventKind::Deadline) => format!(
+            "assertion that {} transpired before its deadline",
+            event_id.path()
+        ),
+        (_, EventKind::Choice { outcomes }) => format!(
+            "outcome of {} out of {}",
+            event_id.path(),
+            join_or(outcomes.iter())
+        ),
         (
-            ["x", exchange, instrument, time],
-            EventKind::Price {
-                n_digits: _n_digits,
+            _,
+            EventKind::Ranking {
+                participants,
+                n_positions,
             },
         ) => {
-            format!("price of {} on {} at {}", instrument, exchange, time,)
+            let positions = match n_positions as usize == participants.len() {
+                true => "finishing order".to_string(),
+                false => format!("top {} finishing positions", n_positions),
+            };
+            format!(
+                "{} of {} out of {}",
+                positions,
+                event_id.path(),
+                join_or(participants.iter())
+            )
+        }
+        (["x", exchange, instrument, time], EventKind::Price { is_signed, .. }) => {
+            format!("price of {} on {} at {}", instrument, exchange, time,) + signed_note(is_signed)
+        }
+        ([..], EventKind::Price { is_signed, .. }) => {
+            format!("price of {}", event_id.path()) + signed_note(is_signed)
         }
-        (
-            [..],
-            EventKind::Price {
-                n_digits: _n_digits,
-            },
-        ) => format!("price of {}", event_id.path()),
         ([..], EventKind::Predicate { inner, predicate }) => {
             let inner_id = event_id.replace_kind(*inner);
             match predicate {
@@ -220,21 +269,124 @@ pub fn event_short(event_id: &EventId) -> String {
                         .expect("this will be valid since predicate is valid");
                     format!("assertion that {}", crate::outcome(&outcome).positive,)
                 }
-                Predicate::Bound(bound_kind, bound) => match bound_kind {
-                    olivia_core::BoundKind::Gt => {
-                        format!(
-                            "assertion that the {} will be greater than {}",
-                            event_short(&inner_id),
-                            bound
-                        )
-                    }
-                },
+                predicate => format!(
+                    "assertion that the {} will be {}",
+                    event_short(&inner_id),
+                    bound_desc(&predicate, |bound| bound_str(&inner_id, bound))
+                ),
             }
         }
+        ([..], EventKind::Compound { op, operands }) => {
+            format!("assertion that {}", compound_desc(op, &operands))
+        }
     };
     desc
 }
 
+/// Describes the operands of a compound event being true e.g. "a has transpired and b has transpired".
+fn compound_desc(op: CompoundOp, operands: &[EventId]) -> String {
+    operands
+        .iter()
+        .map(|operand| match operand.event_kind() {
+            EventKind::SingleOccurrence => event_short(operand),
+            _ => {
+                let outcome = Outcome::try_from_id_and_outcome(operand.clone(), "true")
+                    .expect("compound operands always have a true outcome");
+                crate::outcome(&outcome).positive
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(match op {
+            CompoundOp::And => " and ",
+            CompoundOp::Or => " or ",
+        })
+}
+
+/// Describes a bound or range predicate e.g. "greater than 10".
+fn bound_desc(predicate: &Predicate, fmt_bound: impl Fn(i128) -> String) -> String {
+    match predicate {
+        Predicate::Bound(bound_kind, bound) => format!(
+            "{} {}",
+            match bound_kind {
+                BoundKind::Gt => "greater than",
+                BoundKind::Lt => "less than",
+                BoundKind::Ge => "greater than or equal to",
+                BoundKind::Le => "less than or equal to",
+            },
+            fmt_bound(*bound)
+        ),
+        Predicate::Range(start, end) => format!(
+            "between {} and {} (inclusive)",
+            fmt_bound(*start),
+            fmt_bound(*end)
+        ),
+        Predicate::Eq(value) => format!("equal to {}", value),
+    }
+}
+
+/// Lists things like "a, b or c".
+fn join_or<T: core::fmt::Display>(items: impl ExactSizeIterator<Item = T>) -> String {
+    let len = items.len();
+    items
+        .enumerate()
+        .map(|(i, item)| match i {
+            0 => format!("{}", item),
+            i if i == len - 1 => format!(" or {}", item),
+            _ => format!(", {}", item),
+        })
+        .collect()
+}
+
+/// e.g. "1st", "2nd", "11th".
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+/// A price in the event's units e.g. "101.50 usd".
+fn price_str(id: &EventId, value: u64) -> String {
+    let amount = Outcome {
+        id: id.clone(),
+        value,
+        void: false,
+    }
+    .outcome_string();
+    match id.event_kind() {
+        EventKind::Price {
+            unit: Some(unit), ..
+        } => format!("{} {}", amount, unit),
+        _ => amount,
+    }
+}
+
+/// Writes a predicate's bound like an outcome of the inner event. Bounds of signed prices are
+/// stored in two's complement like their outcomes so truncating gives the outcome value.
+fn bound_str(id: &EventId, bound: i128) -> String {
+    price_str(id, bound as u64)
+}
+
+fn block_field_desc(field: &str) -> &str {
+    match field {
+        "time" => "timestamp",
+        "median_time" => "median time past",
+        "median_fee" => "median transaction fee",
+        field => field,
+    }
+}
+
+fn signed_note(is_signed: bool) -> &'static str {
+    match is_signed {
+        true => " (which may be negative)",
+        false => "",
+    }
+}
+
 #[cfg_attr(feature = "wasm-bindgen", wasm_bindgen)]
 pub fn event_html_str(id: &str) -> Option<String> {
     let id = EventId::from_str(id).ok()?;
@@ -254,20 +406,35 @@ pub fn event_html(id: &EventId) -> Option<String> {
 
             Some(match vs_kind {
                 VsMatchKind::WinOrDraw => format!("{} match {} vs {} on {}.",competition, left_long, right_long,  date) +
-                    &format!(" If {} wins the oracle will attest {}.", left_long, Houtcome(Outcome { value: 0, id: id.clone() })) +
-                    &format!(" If {} wins the oracle will attest {}.", right_long, Houtcome(Outcome { value: 1, id: id.clone() })) +
-                    &format!(" Otherwise the oracle will attest {}", Houtcome(Outcome { value: 2, id: id.clone() })),
+                    &format!(" If {} wins the oracle will attest {}.", left_long, Houtcome(Outcome { value: 0, id: id.clone(), void: false })) +
+                    &format!(" If {} wins the oracle will attest {}.", right_long, Houtcome(Outcome { value: 1, id: id.clone(), void: false })) +
+                    &format!(" Otherwise the oracle will attest {}", Houtcome(Outcome { value: 2, id: id.clone(), void: false })),
                 VsMatchKind::Win => format!("The winner of {} match {} vs {} on {}.", competition, left_long, right_long, date) +
-                    &format!("If {} wins then the oracle will attest {}.", left_long, Houtcome(Outcome { value: 0, id: id.clone() })) +
-                    &format!("If {} wins then the oracle will attest {}", right_long, Houtcome(Outcome { value: 1, id: id.clone() }))
+                    &format!("If {} wins then the oracle will attest {}.", left_long, Houtcome(Outcome { value: 0, id: id.clone(), void: false })) +
+                    &format!("If {} wins then the oracle will attest {}", right_long, Houtcome(Outcome { value: 1, id: id.clone(), void: false }))
             })
 
         },
-        (["x", exchange, instrument, time], EventKind::Price { .. }) => {
+        (["x", exchange, instrument, time], EventKind::Price { is_signed, .. }) => {
             Some(
-                format!("price of <b>{}</b> on <b>{}</b> at <b>{}</b>", instrument_link(exchange, instrument), exchange_link(exchange), time)
+                format!("price of <b>{}</b> on <b>{}</b> at <b>{}</b>", instrument_link(exchange, instrument), exchange_link(exchange), time) + signed_note(is_signed)
              )
         }
+        (_, EventKind::Choice { outcomes }) => Some(format!(
+            "The outcome of <b>{}</b>. The oracle will attest to one of {}.",
+            id.path(),
+            join_or((0..outcomes.len()).map(|value| Houtcome(Outcome {
+                id: id.clone(),
+                value: value as u64,
+                void: false,
+            })))
+        )),
+        (_, EventKind::Boolean) => Some(format!(
+            "The answer to the question <b>{}?</b>. The oracle will attest to {} for yes and {} for no.",
+            id.path(),
+            Houtcome(Outcome { id: id.clone(), value: true as u64, void: false }),
+            Houtcome(Outcome { id: id.clone(), value: false as u64, void: false })
+        )),
         (_, EventKind::Predicate { inner, predicate }) => {
             let inner_id = id.replace_kind(*inner);
 
@@ -277,13 +444,13 @@ pub fn event_html(id: &EventId) -> Option<String> {
                         .expect("this will be valid since predicate is valid");
                     format!("Whether {}.", crate::outcome(&outcome).positive) +
                         &format!("The oracle will attest to {} if the outcome of {} is {}. Otherwise {}",
-                                 Houtcome(Outcome { id: id.clone(), value: true as u64 }),
+                                 Houtcome(Outcome { id: id.clone(), value: true as u64, void: false }),
                                  Heventid(inner_id),
                                  Houtcome(outcome),
-                                 Houtcome(Outcome { id: id.clone(), value: false as u64 }))
+                                 Houtcome(Outcome { id: id.clone(), value: false as u64, void: false }))
                 }
-                Predicate::Bound(BoundKind::Gt, bound) => {
-                    format!("Whether the {} is greater than <b>{}</b>", event_html(&inner_id).unwrap_or(event_short(&inner_id)), bound)
+                predicate => {
+                    format!("Whether the {} is {}", event_short(&inner_id), bound_desc(&predicate, |bound| format!("<b>{}</b>", bound_str(&inner_id, bound))))
                 }
             })
         },
@@ -314,6 +481,13 @@ pub fn outcome(outcome: &Outcome) -> OutcomeDesc {
     let kind = id.event_kind();
     let outcome_str = outcome.outcome_string();
 
+    if outcome.void {
+        return OutcomeDesc {
+            positive: format!("the {} was cancelled", event_short(id)),
+            negative: format!("the {} was not cancelled", event_short(id)),
+        };
+    }
+
     match (&segments[..], kind) {
         (["s", competition, "match", date, _], EventKind::VsMatch(vs_kind)) => {
             let (left, right) = id.parties().unwrap();
@@ -356,10 +530,76 @@ pub fn outcome(outcome: &Outcome) -> OutcomeDesc {
                 ),
             }
         }
-        (_, EventKind::Price { .. }) => OutcomeDesc {
-            positive: format!("the price of {} is {}", event_short(id), outcome_str),
-            negative: format!("the price of {} is not {}", event_short(id), outcome_str),
+        (["s", competition, "match", date, _], EventKind::Score { .. }) => {
+            let (left, right) = id.parties().unwrap();
+            let (left_score, right_score) = outcome.score().unwrap();
+            let left_long = lookup_team(competition, left);
+            let right_long = lookup_team(competition, right);
+            let competition = lookup_competition(competition);
+            OutcomeDesc {
+                positive: format!(
+                    "{} {}-{} {} in their {} match on {}",
+                    left_long, left_score, right_score, right_long, competition, date
+                ),
+                negative: format!(
+                    "the score of {} vs {} in their {} match on {} is not {}-{}",
+                    left_long, right_long, competition, date, left_score, right_score
+                ),
+            }
+        }
+        (_, EventKind::Ranking { .. }) => {
+            let ranking = outcome
+                .attestation_outcome_strings()
+                .iter()
+                .enumerate()
+                .map(|(i, participant)| format!("{} {}", ordinal(i + 1), participant))
+                .collect::<Vec<_>>()
+                .join(", ");
+            OutcomeDesc {
+                positive: format!("the finishing order of {} is {}", id.path(), ranking),
+                negative: format!("the finishing order of {} is not {}", id.path(), ranking),
+            }
+        }
+        (["bitcoin", "block", height], EventKind::SingleOccurrence) => OutcomeDesc {
+            positive: format!("bitcoin block {} has been mined", height),
+            negative: format!("bitcoin block {} has not been mined", height),
         },
+        (["bitcoin", "block", ..], EventKind::Price { .. }) => {
+            let value = price_str(id, outcome.value);
+            OutcomeDesc {
+                positive: format!("the {} is {}", event_short(id), value),
+                negative: format!("the {} is not {}", event_short(id), value),
+            }
+        }
+        (_, EventKind::Boolean) => OutcomeDesc {
+            positive: format!("the {} is yes", event_short(&id)),
+            negative: format!("the {} is no", event_short(&id)),
+        }
+        .maybe_negate(outcome_str == "false"),
+        (_, EventKind::Deadline) => OutcomeDesc {
+            positive: format!("{} transpired before its deadline", id.path()),
+            negative: format!("{} did not transpire before its deadline", id.path()),
+        }
+        .maybe_negate(outcome_str == "false"),
+        (_, EventKind::Choice { .. }) => OutcomeDesc {
+            positive: format!("the outcome of {} is {}", id.path(), outcome_str),
+            negative: format!("the outcome of {} is not {}", id.path(), outcome_str),
+        },
+        (_, EventKind::Compound { op, operands }) => {
+            let desc = compound_desc(op, &operands);
+            OutcomeDesc {
+                negative: format!("it is not the case that {}", desc),
+                positive: desc,
+            }
+            .maybe_negate(outcome_str == "false")
+        }
+        (_, EventKind::Price { .. }) => {
+            let price = price_str(id, outcome.value);
+            OutcomeDesc {
+                positive: format!("the price of {} is {}", event_short(id), price),
+                negative: format!("the price of {} is not {}", event_short(id), price),
+            }
+        }
         (_, EventKind::Predicate { inner, predicate }) => {
             let inner_event_id = id.replace_kind(*inner);
             match predicate {
@@ -368,19 +608,32 @@ pub fn outcome(outcome: &Outcome) -> OutcomeDesc {
                         .expect("predicate is valid");
                     crate::outcome(&inner_outcome).maybe_negate(outcome_str == "false")
                 }
-                Predicate::Bound(BoundKind::Gt, upper_bound) => OutcomeDesc {
-                    positive: format!(
-                        "the {} is above {}",
-                        event_short(&inner_event_id),
-                        upper_bound
-                    ),
-                    negative: format!(
-                        "the {} is not above {}",
-                        event_short(&inner_event_id),
-                        upper_bound
-                    ),
+                predicate => {
+                    let position = match predicate {
+                        Predicate::Bound(bound_kind, bound) => format!(
+                            "{} {}",
+                            match bound_kind {
+                                BoundKind::Gt => "above",
+                                BoundKind::Lt => "below",
+                                BoundKind::Ge => "at or above",
+                                BoundKind::Le => "at or below",
+                            },
+                            bound_str(&inner_event_id, bound)
+                        ),
+                        predicate => {
+                            bound_desc(&predicate, |bound| bound_str(&inner_event_id, bound))
+                        }
+                    };
+                    OutcomeDesc {
+                        positive: format!("the {} is {}", event_short(&inner_event_id), position),
+                        negative: format!(
+                            "the {} is not {}",
+                            event_short(&inner_event_id),
+                            position
+                        ),
+                    }
+                    .maybe_negate(outcome_str == "false")
                 }
-                .maybe_negate(outcome_str == "false"),
             }
         }
         _ => OutcomeDesc {
@@ -567,11 +820,234 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_signed_price_event() {
+        let event_id = "/x/BitMEX/XBTUSD_FR/2021-10-05T5:00:00.price?n=20&signed";
+        assert_eq!(
+            event_short_str(event_id),
+            Some(
+                "price of XBTUSD_FR on BitMEX at 2021-10-05T5:00:00 (which may be negative)".into()
+            )
+        );
+        assert!(outcome_str(event_id, "-42").unwrap().ends_with(" is -42"));
+        assert_eq!(outcome_str(&event_id.replace("&signed", ""), "-42"), None);
+        assert_eq!(
+            event_short_str("/x/BitMEX/XBTUSD_FR/2021-10-05T5:00:00.price_5?signed"),
+            Some("assertion that the price of XBTUSD_FR on BitMEX at 2021-10-05T5:00:00 (which may be negative) will be greater than 5".into())
+        );
+        assert_eq!(
+            outcome_str("/x/BitMEX/XBTUSD_FR/2021-10-05T5:00:00.price_lt-5?signed", "true")
+                .unwrap(),
+            "the price of XBTUSD_FR on BitMEX at 2021-10-05T5:00:00 (which may be negative) is below -5"
+        );
+    }
+
+    #[test]
+    fn test_fixed_point_price_event() {
+        let event_id =
+            "/x/BitMEX/BXBT/2021-10-05T5:00:00.price?n=20&precision=2&unit=usd&min=10000";
+        assert!(outcome_str(event_id, "10000.5")
+            .unwrap()
+            .ends_with(" is 10000.50 usd"));
+        assert_eq!(
+            event_short_str("/x/BitMEX/BXBT/2021-10-05T5:00:00.price_10000..10000.5?precision=2&unit=usd"),
+            Some("assertion that the price of BXBT on BitMEX at 2021-10-05T5:00:00 will be between 10000.00 usd and 10000.50 usd (inclusive)".into())
+        );
+        assert_eq!(
+            outcome_str("/foo/bar.price_lt0.5?precision=1&unit=btc", "false").unwrap(),
+            "the price of /foo/bar is not below 0.5 btc"
+        );
+    }
+
+    #[test]
+    fn test_choice_event() {
+        let event_id = "/elections/2024/mayor.choice?o=red,green,blue";
+        assert_eq!(
+            event_short_str(event_id),
+            Some("outcome of /elections/2024/mayor out of red, green or blue".into())
+        );
+        assert_eq!(
+            outcome_str(event_id, "green").unwrap(),
+            "the outcome of /elections/2024/mayor is green"
+        );
+        assert_eq!(outcome_str(event_id, "pink"), None);
+        assert_eq!(
+            outcome_str(
+                "/elections/2024/mayor.choice=green?o=red,green,blue",
+                "false"
+            )
+            .unwrap(),
+            "the outcome of /elections/2024/mayor is not green"
+        );
+        assert_eq!(
+            event_html_str(event_id),
+            Some("The outcome of <b>/elections/2024/mayor</b>. The oracle will attest to one of <b class='oracle-outcome'>red</b>, <b class='oracle-outcome'>green</b> or <b class='oracle-outcome'>blue</b>.".into())
+        );
+    }
+
     #[test]
     fn test_bounded_price_event() {
         assert_eq!(
             event_short_str("/x/BitMEX/BXBT/2021-10-05T5:00:00.price_10000"),
             Some("assertion that the price of BXBT on BitMEX at 2021-10-05T5:00:00 will be greater than 10000".into())
         );
+        for (kind, desc) in [
+            ("price_lt10000", "less than 10000"),
+            ("price_ge10000", "greater than or equal to 10000"),
+            ("price_le10000", "less than or equal to 10000"),
+            ("price_10000..20000", "between 10000 and 20000 (inclusive)"),
+        ] {
+            assert_eq!(
+                event_short_str(&format!("/x/BitMEX/BXBT/2021-10-05T5:00:00.{}", kind)),
+                Some(format!(
+                    "assertion that the price of BXBT on BitMEX at 2021-10-05T5:00:00 will be {}",
+                    desc
+                ))
+            );
+        }
+        assert_eq!(
+            event_html_str("/foo/bar.price_10..20"),
+            Some(
+                "Whether the price of /foo/bar is between <b>10</b> and <b>20</b> (inclusive)"
+                    .into()
+            )
+        );
+        assert_eq!(
+            outcome_str("/foo/bar.price_le10", "true").unwrap(),
+            "the price of /foo/bar is at or below 10"
+        );
+        assert_eq!(
+            outcome_str("/foo/bar.price_10..20", "false").unwrap(),
+            "the price of /foo/bar is not between 10 and 20 (inclusive)"
+        );
+    }
+
+    #[test]
+    fn test_compound_event() {
+        let event_id = "/combo/2021-10-05T5:00:00.and(~x~BitMEX~BXBT~2021-10-05T5:00:00.price_50000;~foo~bar.occur)";
+        let both = "the price of BXBT on BitMEX at 2021-10-05T5:00:00 is above 50000 and /foo/bar has transpired";
+        assert_eq!(
+            event_short_str(event_id),
+            Some(format!("assertion that {}", both))
+        );
+        assert_eq!(outcome_str(event_id, "true").unwrap(), both);
+        assert_eq!(
+            outcome_str(event_id, "false").unwrap(),
+            format!("it is not the case that {}", both)
+        );
+    }
+
+    #[test]
+    fn test_void_outcome() {
+        assert_eq!(
+            outcome_str("/s/EPL/match/2021-08-13/BRE_ARS.vs", "void").unwrap(),
+            "the result of English Premier League match Brentford vs Arsenal on 2021-08-13 was cancelled"
+        );
+        assert_eq!(
+            outcome_str("/x/BitMEX/BXBT/2021-10-05T5:00:00.price?n=20", "void").unwrap(),
+            "the price of BXBT on BitMEX at 2021-10-05T5:00:00 was cancelled"
+        );
+    }
+
+    #[test]
+    fn test_bitcoin_block_event() {
+        assert_eq!(
+            event_short_str("/bitcoin/block/700000.occur").unwrap(),
+            "mining of bitcoin block 700000"
+        );
+        assert_eq!(
+            outcome_str("/bitcoin/block/700000.occur", "true").unwrap(),
+            "bitcoin block 700000 has been mined"
+        );
+        assert_eq!(
+            outcome_str(
+                "/bitcoin/block/700000/median_fee.price?n=20&unit=sat",
+                "2000"
+            )
+            .unwrap(),
+            "the median transaction fee of bitcoin block 700000 is 2000 sat"
+        );
+        assert_eq!(
+            outcome_str(
+                "/bitcoin/block/700000/difficulty.price_lt20000000000000",
+                "true"
+            )
+            .unwrap(),
+            "the difficulty of bitcoin block 700000 is below 20000000000000"
+        );
+    }
+
+    #[test]
+    fn test_bool_event() {
+        let event_id = "/weather/London/2021-11-01/rain.bool";
+        assert_eq!(
+            event_short_str(event_id).unwrap(),
+            "answer to the question \"/weather/London/2021-11-01/rain?\""
+        );
+        assert_eq!(
+            outcome_str(event_id, "false").unwrap(),
+            "the answer to the question \"/weather/London/2021-11-01/rain?\" is no"
+        );
+        assert_eq!(
+            event_short_str("/weather/London/2021-11-01/rain.bool=false").unwrap(),
+            "assertion that the answer to the question \"/weather/London/2021-11-01/rain?\" is no"
+        );
+        assert_eq!(
+            outcome_str("/weather/London/2021-11-01/rain.bool=true", "false").unwrap(),
+            "the answer to the question \"/weather/London/2021-11-01/rain?\" is no"
+        );
+        assert_eq!(
+            event_html_str(event_id),
+            Some("The answer to the question <b>/weather/London/2021-11-01/rain?</b>. The oracle will attest to <b class='oracle-outcome'>true</b> for yes and <b class='oracle-outcome'>false</b> for no.".into())
+        );
+    }
+
+    #[test]
+    fn test_deadline_event() {
+        let event_id = "/bitcoin/tx/abcd/confirmed.before";
+        assert_eq!(
+            event_short_str(event_id).unwrap(),
+            "assertion that /bitcoin/tx/abcd/confirmed transpired before its deadline"
+        );
+        assert_eq!(
+            outcome_str(event_id, "true").unwrap(),
+            "/bitcoin/tx/abcd/confirmed transpired before its deadline"
+        );
+        assert_eq!(
+            outcome_str(event_id, "false").unwrap(),
+            "/bitcoin/tx/abcd/confirmed did not transpire before its deadline"
+        );
+    }
+
+    #[test]
+    fn test_score_event() {
+        let event_id = "/s/EPL/match/2021-08-13/BRE_ARS.score?n=4";
+        assert_eq!(
+            event_short_str(event_id).unwrap(),
+            "score of English Premier League match Brentford vs Arsenal on 2021-08-13"
+        );
+        assert_eq!(
+            outcome_str(event_id, "2-0").unwrap(),
+            "Brentford 2-0 Arsenal in their English Premier League match on 2021-08-13"
+        );
+        assert_eq!(
+            outcome_str("/foo/bar/FOO_BAR.score", "2-0").unwrap(),
+            "the score of FOO vs BAR in /foo/bar is \"2-0\""
+        );
+    }
+
+    #[test]
+    fn test_ranking_event() {
+        let event_id = "/f1/2021/monza.rank?p=HAM,VER,LEC,NOR&n=3";
+        assert_eq!(
+            event_short_str(event_id).unwrap(),
+            "top 3 finishing positions of /f1/2021/monza out of HAM, VER, LEC or NOR"
+        );
+        assert_eq!(
+            outcome_str(event_id, "NOR,HAM,VER").unwrap(),
+            "the finishing order of /f1/2021/monza is 1st NOR, 2nd HAM, 3rd VER"
+        );
+        assert_eq!(ordinal(12), "12th");
+        assert_eq!(ordinal(22), "22nd");
     }
 }
@@ -0,0 +1,629 @@
+//! The ristretto255 group for oracles whose attestations are consumed outside of Bitcoin.
+//!
+//! Attest scalars and anticipated attestations work the same as in `olivia_secp256k1`. Signatures
+//! are Schnorr signatures with a SHA-512 challenge. Nothing verifies them as DLC spec signatures
+//! so they're tagged with olivia specific tags. There's no ECDSA over ristretto255 so oracles on
+//! it don't have the ecdsa-v1 scheme.
+#![allow(non_snake_case)]
+pub use curve25519_dalek;
+use curve25519_dalek::{
+    constants::RISTRETTO_BASEPOINT_POINT as G,
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+};
+use olivia_core::{GroupObject, OracleKeys, ToFromBytes};
+pub use serde;
+use sha2::{Digest, Sha256, Sha512};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Ristretto;
+
+fn decompress(bytes: &[u8]) -> Option<RistrettoPoint> {
+    CompressedRistretto::from_slice(bytes.get(..32)?).decompress()
+}
+
+fn scalar_from_bytes(bytes: &[u8]) -> Option<Scalar> {
+    Scalar::from_canonical_bytes(bytes.try_into().ok()?)
+}
+
+#[derive(PartialEq, Clone)]
+pub struct PublicKey(RistrettoPoint);
+
+olivia_core::impl_display_debug_serialize_tosql! {
+    fn to_bytes(pk: &PublicKey) -> [u8;32] {
+        pk.0.compress().to_bytes()
+    }
+}
+
+olivia_core::impl_fromstr_deserialize_fromsql! {
+    name => "ristretto255 public key",
+    fn from_bytes(bytes: [u8;32]) ->  Option<PublicKey> {
+        decompress(&bytes[..]).map(PublicKey)
+    }
+}
+
+impl GroupObject for PublicKey {}
+
+impl ToFromBytes for PublicKey {
+    const LEN: usize = 32;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.compress().to_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        decompress(bytes)
+            .filter(|_| bytes.len() == 32)
+            .map(PublicKey)
+    }
+}
+
+#[derive(PartialEq, Clone)]
+pub struct PublicNonce(RistrettoPoint);
+impl GroupObject for PublicNonce {}
+
+impl ToFromBytes for PublicNonce {
+    const LEN: usize = 32;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.compress().to_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        decompress(bytes)
+            .filter(|_| bytes.len() == 32)
+            .map(PublicNonce)
+    }
+}
+
+olivia_core::impl_display_debug_serialize_tosql! {
+    fn to_bytes(pn: &PublicNonce) -> [u8;32] {
+        pn.0.compress().to_bytes()
+    }
+}
+
+olivia_core::impl_fromstr_deserialize_fromsql! {
+    name => "ristretto255 public nonce",
+    fn from_bytes(bytes: [u8;32]) ->  Option<PublicNonce> {
+        decompress(&bytes[..]).map(PublicNonce)
+    }
+}
+
+#[derive(PartialEq, Clone)]
+pub struct AttestScalar(Scalar);
+impl GroupObject for AttestScalar {}
+
+impl ToFromBytes for AttestScalar {
+    const LEN: usize = 32;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        scalar_from_bytes(bytes).map(AttestScalar)
+    }
+}
+
+olivia_core::impl_display_debug_serialize_tosql! {
+    fn to_bytes(scalar: &AttestScalar) -> [u8;32] {
+        scalar.0.to_bytes()
+    }
+}
+
+olivia_core::impl_fromstr_deserialize_fromsql! {
+    name => "ristretto255 scalar",
+    fn from_bytes(bytes: [u8;32]) ->  Option<AttestScalar> {
+        scalar_from_bytes(&bytes[..]).map(AttestScalar)
+    }
+}
+
+#[derive(PartialEq, Clone)]
+pub struct Signature {
+    R: RistrettoPoint,
+    s: Scalar,
+}
+
+impl Signature {
+    fn to_byte_array(&self) -> [u8; 64] {
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(self.R.compress().as_bytes());
+        bytes[32..].copy_from_slice(self.s.as_bytes());
+        bytes
+    }
+
+    fn from_slice(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 64 {
+            return None;
+        }
+        Some(Signature {
+            R: decompress(&bytes[..32])?,
+            s: scalar_from_bytes(&bytes[32..])?,
+        })
+    }
+}
+
+olivia_core::impl_display_debug_serialize_tosql! {
+    fn to_bytes(sig: &Signature) -> [u8;64] {
+        sig.to_byte_array()
+    }
+}
+
+olivia_core::impl_fromstr_deserialize_fromsql! {
+    name => "ristretto255 schnorr signature",
+    fn from_bytes(bytes: [u8;64]) ->  Option<Signature> {
+        Signature::from_slice(&bytes[..])
+    }
+}
+
+impl GroupObject for Signature {}
+
+impl ToFromBytes for Signature {
+    const LEN: usize = 64;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_byte_array().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Signature::from_slice(bytes)
+    }
+}
+
+/// An olivia-v1 secret key recovered from an equivocation.
+#[derive(PartialEq, Clone)]
+pub struct SecretKey(Scalar);
+
+olivia_core::impl_display_debug_serialize! {
+    fn to_bytes(sk: &SecretKey) -> [u8;32] {
+        sk.0.to_bytes()
+    }
+}
+
+olivia_core::impl_fromstr_deserialize! {
+    name => "ristretto255 secret key",
+    fn from_bytes(bytes: [u8;32]) ->  Option<SecretKey> {
+        scalar_from_bytes(&bytes[..]).map(SecretKey)
+    }
+}
+
+impl From<SecretKey> for Scalar {
+    fn from(sk: SecretKey) -> Self {
+        sk.0
+    }
+}
+
+#[derive(Clone)]
+pub struct KeyPair {
+    secret_key: Scalar,
+    public_key: RistrettoPoint,
+}
+
+impl KeyPair {
+    pub fn new(secret_key: Scalar) -> Self {
+        Self {
+            public_key: secret_key * G,
+            secret_key,
+        }
+    }
+
+    pub fn secret_key(&self) -> &Scalar {
+        &self.secret_key
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(self.public_key)
+    }
+}
+
+impl From<KeyPair> for PublicKey {
+    fn from(kp: KeyPair) -> Self {
+        kp.public_key()
+    }
+}
+
+#[derive(Clone)]
+pub struct NonceKeyPair {
+    secret_nonce: Scalar,
+    public_nonce: RistrettoPoint,
+}
+
+impl core::fmt::Debug for NonceKeyPair {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "NonceKeyPair({})", PublicNonce(self.public_nonce))
+    }
+}
+
+impl From<NonceKeyPair> for PublicNonce {
+    fn from(kp: NonceKeyPair) -> Self {
+        PublicNonce(kp.public_nonce)
+    }
+}
+
+impl From<PublicKey> for RistrettoPoint {
+    fn from(pk: PublicKey) -> Self {
+        pk.0
+    }
+}
+
+impl From<PublicNonce> for RistrettoPoint {
+    fn from(pn: PublicNonce) -> Self {
+        pn.0
+    }
+}
+
+impl From<AttestScalar> for Scalar {
+    fn from(att_scalar: AttestScalar) -> Self {
+        att_scalar.0
+    }
+}
+
+const ANNOUNCEMENT_TAG: &[u8] = b"olivia/ristretto255/announcement";
+const ANNOUNCEMENT_TLV_TAG: &[u8] = b"olivia/ristretto255/oracle_event";
+const DLC_ATTESTATION_TAG: &[u8] = b"olivia/ristretto255/attestation";
+
+fn challenge(tag: &[u8], R: &RistrettoPoint, X: &RistrettoPoint, message: &[u8]) -> Scalar {
+    Scalar::from_hash(
+        Sha512::default()
+            .chain(tag)
+            .chain(R.compress().as_bytes())
+            .chain(X.compress().as_bytes())
+            .chain(message),
+    )
+}
+
+/// The nonce is derived from the secret key and the message like ed25519 does
+fn schnorr_sign(keypair: &KeyPair, tag: &[u8], message: &[u8]) -> Signature {
+    let r = Scalar::from_hash(
+        Sha512::default()
+            .chain(b"olivia/ristretto255/nonce")
+            .chain(keypair.secret_key.as_bytes())
+            .chain(tag)
+            .chain(message),
+    );
+    let R = r * G;
+    let c = challenge(tag, &R, &keypair.public_key, message);
+    Signature {
+        R,
+        s: r + c * keypair.secret_key,
+    }
+}
+
+fn schnorr_verify(public_key: &PublicKey, tag: &[u8], message: &[u8], sig: &Signature) -> bool {
+    let c = challenge(tag, &sig.R, &public_key.0, message);
+    sig.s * G == sig.R + c * public_key.0
+}
+
+impl olivia_core::Group for Ristretto {
+    type KeyPair = KeyPair;
+    type PublicKey = PublicKey;
+    type PublicNonce = PublicNonce;
+    type NonceKeyPair = NonceKeyPair;
+    type Signature = Signature;
+    type AttestScalar = AttestScalar;
+    type AnticipatedAttestation = RistrettoPoint;
+    type EcdsaSignature = Signature;
+    type SecretKey = SecretKey;
+    const KEY_MATERIAL_LEN: usize = 64;
+    const ECDSA_V1: bool = false;
+
+    fn name() -> &'static str {
+        "ristretto255"
+    }
+
+    fn verify_announcement_signature(
+        public_key: &Self::PublicKey,
+        message: &[u8],
+        sig: &Self::Signature,
+    ) -> bool {
+        schnorr_verify(public_key, ANNOUNCEMENT_TAG, message, sig)
+    }
+
+    fn reveal_attest_scalar(
+        signing_key: &Self::KeyPair,
+        nonce_key: Self::NonceKeyPair,
+        index: u32,
+    ) -> Self::AttestScalar {
+        let c = Scalar::from(index as u64 + 1);
+        AttestScalar(c * nonce_key.secret_nonce + signing_key.secret_key)
+    }
+
+    fn anticipate_attestations(
+        public_key: &Self::PublicKey,
+        public_nonce: &Self::PublicNonce,
+        n_outcomes: u32,
+    ) -> Vec<Self::AnticipatedAttestation> {
+        let R = public_nonce.0;
+        (0..n_outcomes)
+            .scan(public_key.0, |C, _| {
+                *C += R;
+                Some(*C)
+            })
+            .collect()
+    }
+
+    fn combine_anticipated_attestations(
+        anticipated: &[Self::AnticipatedAttestation],
+    ) -> Self::AnticipatedAttestation {
+        anticipated.iter().sum()
+    }
+
+    fn combine_attest_scalars(attest_scalars: &[Self::AttestScalar]) -> Self::AttestScalar {
+        AttestScalar(attest_scalars.iter().map(|scalar| scalar.0).sum())
+    }
+
+    fn attest_scalar_matches(
+        anticipated: &Self::AnticipatedAttestation,
+        attest_scalar: &Self::AttestScalar,
+    ) -> bool {
+        attest_scalar.0 * G == *anticipated
+    }
+
+    fn sign_announcement(keypair: &Self::KeyPair, announcement: &[u8]) -> Self::Signature {
+        schnorr_sign(keypair, ANNOUNCEMENT_TAG, announcement)
+    }
+
+    fn sign_announcement_tlv(keypair: &Self::KeyPair, oracle_event: &[u8]) -> Self::Signature {
+        let hash = Sha256::default().chain(oracle_event).finalize();
+        schnorr_sign(keypair, ANNOUNCEMENT_TLV_TAG, &hash[..])
+    }
+
+    fn verify_announcement_tlv_signature(
+        public_key: &Self::PublicKey,
+        oracle_event: &[u8],
+        sig: &Self::Signature,
+    ) -> bool {
+        let hash = Sha256::default().chain(oracle_event).finalize();
+        schnorr_verify(public_key, ANNOUNCEMENT_TLV_TAG, &hash[..], sig)
+    }
+
+    fn dlc_v0_sign(
+        keypair: &Self::KeyPair,
+        nonce_keypair: Self::NonceKeyPair,
+        outcome: &[u8],
+    ) -> Self::Signature {
+        let R = nonce_keypair.public_nonce;
+        let c = challenge(DLC_ATTESTATION_TAG, &R, &keypair.public_key, outcome);
+        Signature {
+            R,
+            s: nonce_keypair.secret_nonce + c * keypair.secret_key,
+        }
+    }
+
+    fn dlc_v0_verify(
+        public_key: &Self::PublicKey,
+        public_nonce: &Self::PublicNonce,
+        outcome: &[u8],
+        sig: &Self::Signature,
+    ) -> bool {
+        sig.R == public_nonce.0 && schnorr_verify(public_key, DLC_ATTESTATION_TAG, outcome, sig)
+    }
+
+    fn verify_attest_scalar(
+        public_key: &Self::PublicKey,
+        public_nonce: &Self::PublicNonce,
+        index: u32,
+        attest_scalar: &Self::AttestScalar,
+    ) -> bool {
+        let c = Scalar::from(index as u64 + 1);
+        attest_scalar.0 * G == c * public_nonce.0 + public_key.0
+    }
+
+    fn extract_secret_from_equivocation(
+        public_key: &Self::PublicKey,
+        public_nonce: &Self::PublicNonce,
+        (index1, attest_scalar1): (u32, &Self::AttestScalar),
+        (index2, attest_scalar2): (u32, &Self::AttestScalar),
+    ) -> Option<Self::SecretKey> {
+        if index1 == index2 {
+            return None;
+        }
+        let c1 = Scalar::from(index1 as u64 + 1);
+        let c2 = Scalar::from(index2 as u64 + 1);
+        // s1 - s2 = (c1 - c2) * r
+        let r = (attest_scalar1.0 - attest_scalar2.0) * (c1 - c2).invert();
+        let x = attest_scalar1.0 - c1 * r;
+
+        if x * G != public_key.0 || r * G != public_nonce.0 {
+            return None;
+        }
+
+        Some(SecretKey(x))
+    }
+
+    fn test_keypair() -> Self::KeyPair {
+        KeyPair::new(Scalar::from_bytes_mod_order([42u8; 32]))
+    }
+
+    fn test_nonce_keypair() -> Self::NonceKeyPair {
+        Self::nonce_keypair_from_secret_bytes(&[84u8; 64])
+    }
+
+    fn test_oracle_keys() -> OracleKeys<Self> {
+        let key = |i: u64| PublicKey(Scalar::from(i) * G);
+        OracleKeys {
+            announcement: key(13),
+            ecdsa_v1: None,
+            olivia_v1: Some(key(16)),
+            group: Ristretto,
+            olivia_v1_namespaces: Default::default(),
+        }
+    }
+
+    fn keypair_from_secret_bytes(bytes: &[u8]) -> Self::KeyPair {
+        KeyPair::new(Scalar::from_bytes_mod_order_wide(
+            bytes.try_into().expect("will be 64 bytes long"),
+        ))
+    }
+
+    fn nonce_keypair_from_secret_bytes(bytes: &[u8]) -> Self::NonceKeyPair {
+        let secret_nonce =
+            Scalar::from_bytes_mod_order_wide(bytes.try_into().expect("will be 64 bytes long"));
+        NonceKeyPair {
+            public_nonce: secret_nonce * G,
+            secret_nonce,
+        }
+    }
+
+    fn ecdsa_sign(_keypair: &Self::KeyPair, _message: &[u8]) -> Self::EcdsaSignature {
+        unreachable!("ristretto255 doesn't have the ecdsa-v1 scheme")
+    }
+
+    fn ecdsa_verify(
+        _public_key: &Self::PublicKey,
+        _message: &[u8],
+        _sig: &Self::EcdsaSignature,
+    ) -> bool {
+        false
+    }
+}
+
+olivia_core::impl_deserialize_curve!(Ristretto);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::str::FromStr;
+    use olivia_core::{
+        attest, chrono::NaiveDateTime, Attestation, AttestationSchemes, Event, EventId, Group,
+        Outcome, RawAnnouncement,
+    };
+
+    #[test]
+    fn anticipate_vs_attest() {
+        let oracle_key = Ristretto::test_keypair();
+        let nonce_key = Ristretto::test_nonce_keypair();
+        let attestation_points = Ristretto::anticipate_attestations(
+            &oracle_key.clone().into(),
+            &nonce_key.clone().into(),
+            5,
+        );
+        let expected = (0..5)
+            .map(|i| Ristretto::reveal_attest_scalar(&oracle_key, nonce_key.clone(), i).0 * G)
+            .collect::<Vec<_>>();
+        assert_eq!(attestation_points, expected);
+    }
+
+    #[test]
+    fn encodings_roundtrip() {
+        let keys = Ristretto::test_oracle_keys();
+        let json = serde_json::to_string(&keys).unwrap();
+        assert_eq!(
+            serde_json::from_str::<OracleKeys<Ristretto>>(&json).unwrap(),
+            keys
+        );
+        let sig = Ristretto::sign_announcement(&Ristretto::test_keypair(), b"hello");
+        assert_eq!(Signature::from_str(&sig.to_string()), Ok(sig.clone()));
+        assert_eq!(
+            <Signature as ToFromBytes>::from_bytes(&sig.to_bytes()),
+            Some(sig)
+        );
+        // a secp256k1 key isn't a valid ristretto255 encoding
+        assert!(PublicKey::from_str(
+            "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn announce_and_attest() {
+        for id in ["/foo/bar/FOO_BAR.vs", "/foo/bar.price?n=6"] {
+            let event = Event {
+                id: EventId::from_str(id).unwrap(),
+                expected_outcome_time: Some(
+                    NaiveDateTime::from_str("2021-10-03T00:00:00").unwrap(),
+                ),
+            };
+            let keypair = Ristretto::test_keypair();
+            let public_key = keypair.public_key();
+            for announcement in [
+                RawAnnouncement::<Ristretto>::test_instance(event.clone()),
+                RawAnnouncement::<Ristretto>::test_tlv_instance(event.clone()).unwrap(),
+            ] {
+                let oracle_event = announcement
+                    .verify_against_id(&event.id, &public_key)
+                    .expect("valid announcement");
+                assert!(announcement
+                    .verify_against_id(&event.id, &Ristretto::test_oracle_keys().announcement)
+                    .is_none());
+
+                let outcome = Outcome {
+                    id: event.id.clone(),
+                    value: 1,
+                    void: false,
+                };
+                let scalars = outcome
+                    .attestation_indexes()
+                    .into_iter()
+                    .map(|index| {
+                        Ristretto::reveal_attest_scalar(
+                            &keypair,
+                            Ristretto::test_nonce_keypair(),
+                            index,
+                        )
+                    })
+                    .collect();
+                let attestation = Attestation::<Ristretto>::new(
+                    outcome.outcome_string(),
+                    NaiveDateTime::from_str("2021-10-03T00:00:00").unwrap(),
+                    AttestationSchemes {
+                        olivia_v1: Some(attest::OliviaV1 { scalars }),
+                        ecdsa_v1: None,
+                        dlc_v0: None,
+                    },
+                );
+                let oracle_keys = OracleKeys {
+                    announcement: public_key.clone(),
+                    olivia_v1: Some(public_key.clone()),
+                    ecdsa_v1: None,
+                    group: Ristretto,
+                    olivia_v1_namespaces: Default::default(),
+                };
+                assert_eq!(
+                    attestation.verify_olivia_v1_attestation(&oracle_event, &oracle_keys),
+                    Ok(())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn extract_secret_from_equivocation() {
+        let keypair = Ristretto::test_keypair();
+        let nonce_keypair = Ristretto::test_nonce_keypair();
+        let public_key = keypair.public_key();
+        let public_nonce = nonce_keypair.clone().into();
+        let s1 = Ristretto::reveal_attest_scalar(&keypair, nonce_keypair.clone(), 1);
+        let s3 = Ristretto::reveal_attest_scalar(&keypair, nonce_keypair.clone(), 3);
+        assert_eq!(
+            Ristretto::extract_secret_from_equivocation(
+                &public_key,
+                &public_nonce,
+                (1, &s1),
+                (3, &s3)
+            ),
+            Some(SecretKey(*keypair.secret_key()))
+        );
+        assert_eq!(
+            Ristretto::extract_secret_from_equivocation(
+                &public_key,
+                &public_nonce,
+                (1, &s1),
+                (1, &s1)
+            ),
+            None
+        );
+        // scalars from a different nonce don't give away the key
+        let other_nonce = Ristretto::nonce_keypair_from_secret_bytes(&[1u8; 64]);
+        let s2 = Ristretto::reveal_attest_scalar(&keypair, other_nonce, 2);
+        assert_eq!(
+            Ristretto::extract_secret_from_equivocation(
+                &public_key,
+                &public_nonce,
+                (1, &s1),
+                (2, &s2)
+            ),
+            None
+        );
+    }
+}
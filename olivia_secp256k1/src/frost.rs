@@ -0,0 +1,676 @@
+//! FROST style threshold keys so one logical oracle can be held by several nodes.
+//!
+//! Keys and olivia-v1 nonces are generated with a Pedersen DKG where each participant proves
+//! knowledge of its polynomial's constant term. Attesting needs no interaction since the attest
+//! scalar is linear in the key and the nonce: each participant reveals `(c + 1) * r_i + x_i` and
+//! `threshold` of them are interpolated into a scalar that verifies with the usual
+//! [`verify_attest_scalar`]. Announcements are signed with two round FROST signing.
+//!
+//! Participants are indexed from 1.
+//!
+//! [`verify_attest_scalar`]: olivia_core::Group::verify_attest_scalar
+use crate::{AttestScalar, PublicKey, PublicNonce, Secp256k1, Signature, SCHNORR};
+use olivia_core::RawOracleEvent;
+use schnorr_fun::{
+    fun::{
+        g,
+        hash::{HashAdd, Tagged},
+        marker::*,
+        s, Point, Scalar, G,
+    },
+    Message,
+};
+use sha2::{Digest, Sha256};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum FrostError {
+    #[error("threshold {threshold} is not more than half of and at most the number of participants ({n})")]
+    InvalidThreshold { threshold: usize, n: usize },
+    #[error("participant {index}'s polynomial commitment has the wrong degree")]
+    InvalidCommitment { index: u32 },
+    #[error("participant {index}'s proof of knowledge is invalid")]
+    InvalidProof { index: u32 },
+    #[error("share from participant {from} doesn't match its commitment")]
+    InvalidShare { from: u32 },
+    #[error("expected {expected} shares but got {got}")]
+    WrongNumberOfShares { expected: usize, got: usize },
+    #[error("{0:?} is not a set of threshold many distinct participants")]
+    InvalidSigners(Vec<u32>),
+    #[error("polynomial coefficient {index} is zero")]
+    ZeroCoefficient { index: usize },
+}
+
+/// Checks `threshold` is a majority of the `n` participants. Otherwise two disjoint sets of
+/// participants could each attest to a different outcome and reveal the key without any of them
+/// equivocating.
+pub fn check_threshold(threshold: usize, n: usize) -> Result<(), FrostError> {
+    if threshold > n || 2 * threshold <= n {
+        return Err(FrostError::InvalidThreshold { threshold, n });
+    }
+    Ok(())
+}
+
+fn index_scalar(index: u32) -> Scalar<Public> {
+    Scalar::from(index)
+        .mark::<Public>()
+        .mark::<NonZero>()
+        .expect("participant indexes start at 1")
+}
+
+/// A participant's secret polynomial of degree `threshold - 1`
+#[derive(Clone)]
+pub struct SecretPolynomial(Vec<Scalar>);
+
+impl SecretPolynomial {
+    /// The coefficients are reduced mod the curve order. They should be derived from a secret
+    /// seed so the participant can recreate the polynomial. Fails if any of them reduce to zero.
+    pub fn from_coefficient_bytes(coefficients: Vec<[u8; 32]>) -> Result<Self, FrostError> {
+        coefficients
+            .into_iter()
+            .enumerate()
+            .map(|(index, bytes)| {
+                Scalar::from_bytes_mod_order(bytes)
+                    .mark::<NonZero>()
+                    .ok_or(FrostError::ZeroCoefficient { index })
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+
+    pub fn commitment(&self) -> PolyCommitment {
+        PolyCommitment(
+            self.0
+                .iter()
+                .map(|coefficient| g!(coefficient * G).mark::<Normal>())
+                .collect(),
+        )
+    }
+
+    /// The share of the constant term for participant `index`
+    pub fn share_for(&self, index: u32) -> Scalar<Secret, Zero> {
+        let x = index_scalar(index);
+        self.0
+            .iter()
+            .rev()
+            .fold(Scalar::zero().mark::<Secret>(), |acc, coefficient| {
+                s!(acc * x + coefficient)
+            })
+    }
+
+    /// The commitment to the polynomial with a proof of knowledge of its constant term bound to
+    /// the DKG's `context` and the participant's `index`.
+    pub fn contribution(&self, context: &[u8], index: u32) -> KeygenContribution {
+        let commitment = self.commitment();
+        let a0 = &self.0[0];
+        let r = Scalar::from_hash(
+            Sha256::default()
+                .tagged(b"olivia/frost/pok-nonce")
+                .add(a0)
+                .add(context)
+                .add(&index.to_be_bytes()[..]),
+        )
+        .mark::<NonZero>()
+        .expect("hash output will not be zero");
+        let R = g!(r * G).mark::<Normal>();
+        let c = pok_challenge(context, index, &commitment.0[0], &R);
+        let s = s!(r + c * a0).mark::<Public>();
+        KeygenContribution {
+            commitment,
+            proof: ProofOfKnowledge { R, s },
+        }
+    }
+}
+
+fn pok_challenge(
+    context: &[u8],
+    index: u32,
+    A: &Point<Normal, Public, NonZero>,
+    R: &Point<Normal, Public, NonZero>,
+) -> Scalar<Public, Zero> {
+    Scalar::from_hash(
+        Sha256::default()
+            .tagged(b"olivia/frost/pok")
+            .add(context)
+            .add(&index.to_be_bytes()[..])
+            .add(A)
+            .add(R),
+    )
+    .mark::<Public>()
+    .mark::<Zero>()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolyCommitment(pub Vec<Point<Normal, Public, NonZero>>);
+
+impl PolyCommitment {
+    /// The image of the polynomial at `index`
+    pub fn eval(&self, index: u32) -> Point<Jacobian, Public, Zero> {
+        let x = index_scalar(index);
+        self.0
+            .iter()
+            .rev()
+            .fold(Point::zero().mark::<Jacobian>(), |acc, coefficient| {
+                g!(x * acc + coefficient)
+            })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProofOfKnowledge {
+    pub R: Point<Normal, Public, NonZero>,
+    pub s: Scalar<Public, Zero>,
+}
+
+/// What each participant broadcasts in the DKG
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeygenContribution {
+    pub commitment: PolyCommitment,
+    pub proof: ProofOfKnowledge,
+}
+
+/// The public result of a DKG. If the sum of the contributions has an odd y-coordinate the key
+/// and every share is negated so the key can be used as an x-only key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedKey {
+    threshold: usize,
+    public_key: Point<EvenY, Public, NonZero>,
+    negated: bool,
+    commitments: Vec<PolyCommitment>,
+}
+
+impl SharedKey {
+    /// Checks the contributions from participants `1..=n` (in order) made in `context`.
+    pub fn new(context: &[u8], contributions: Vec<KeygenContribution>) -> Result<Self, FrostError> {
+        let n = contributions.len();
+        let threshold = contributions
+            .first()
+            .map(|contribution| contribution.commitment.0.len())
+            .unwrap_or(0);
+        check_threshold(threshold, n)?;
+
+        for (i, contribution) in contributions.iter().enumerate() {
+            let index = i as u32 + 1;
+            if contribution.commitment.0.len() != threshold {
+                return Err(FrostError::InvalidCommitment { index });
+            }
+            let A = &contribution.commitment.0[0];
+            let ProofOfKnowledge { R, s } = &contribution.proof;
+            let c = pok_challenge(context, index, A, R);
+            if g!(s * G) != g!(R + c * A) {
+                return Err(FrostError::InvalidProof { index });
+            }
+        }
+
+        let public_key = contributions
+            .iter()
+            .fold(Point::zero().mark::<Jacobian>(), |acc, contribution| {
+                g!(acc + { &contribution.commitment.0[0] })
+            })
+            .mark::<Normal>()
+            .mark::<NonZero>()
+            .expect("the key is a sum of independent random points");
+        let (public_key, negated) = public_key.into_point_with_even_y();
+
+        Ok(Self {
+            threshold,
+            public_key,
+            negated,
+            commitments: contributions
+                .into_iter()
+                .map(|contribution| contribution.commitment)
+                .collect(),
+        })
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn n_participants(&self) -> usize {
+        self.commitments.len()
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(self.public_key.to_xonly())
+    }
+
+    /// The key as an olivia-v1 nonce when the DKG was run for an event's nonce
+    pub fn public_nonce(&self) -> PublicNonce {
+        PublicNonce(self.public_key.to_xonly())
+    }
+
+    /// The image of participant `index`'s secret share
+    pub fn verification_share(&self, index: u32) -> Point<Jacobian, Public, Zero> {
+        let share = self
+            .commitments
+            .iter()
+            .fold(Point::zero().mark::<Jacobian>(), |acc, commitment| {
+                g!(acc + { commitment.eval(index) })
+            });
+        share.conditional_negate(self.negated)
+    }
+
+    /// Checks the shares participant `index` received from participants `1..=n` (in order) and
+    /// sums them into its secret share.
+    pub fn secret_share(
+        &self,
+        index: u32,
+        shares: &[Scalar<Secret, Zero>],
+    ) -> Result<Scalar<Secret, Zero>, FrostError> {
+        if shares.len() != self.commitments.len() {
+            return Err(FrostError::WrongNumberOfShares {
+                expected: self.commitments.len(),
+                got: shares.len(),
+            });
+        }
+        let mut secret_share = Scalar::zero().mark::<Secret>();
+        for (i, (share, commitment)) in shares.iter().zip(&self.commitments).enumerate() {
+            if g!(share * G) != commitment.eval(index) {
+                return Err(FrostError::InvalidShare { from: i as u32 + 1 });
+            }
+            secret_share = s!(secret_share + share);
+        }
+        secret_share.conditional_negate(self.negated);
+        Ok(secret_share)
+    }
+
+    fn check_signers(&self, signers: &[u32]) -> Result<(), FrostError> {
+        let valid = signers.len() == self.threshold
+            && signers.iter().enumerate().all(|(i, index)| {
+                *index >= 1
+                    && *index as usize <= self.n_participants()
+                    && !signers[..i].contains(index)
+            });
+        match valid {
+            true => Ok(()),
+            false => Err(FrostError::InvalidSigners(signers.to_vec())),
+        }
+    }
+}
+
+/// The coefficient to multiply participant `index`'s share by to interpolate the secret from
+/// the shares of `signers`.
+pub fn lagrange_coefficient(index: u32, signers: &[u32]) -> Scalar<Public, Zero> {
+    let i = index_scalar(index);
+    let (num, den) = signers.iter().filter(|j| **j != index).fold(
+        (Scalar::one().mark::<Zero>(), Scalar::one().mark::<Zero>()),
+        |(num, den), j| {
+            let j = index_scalar(*j);
+            (s!(num * j), s!(den * (j - i)))
+        },
+    );
+    let den = den
+        .mark::<NonZero>()
+        .expect("signers are distinct")
+        .invert();
+    s!(num * den).mark::<Public>()
+}
+
+/// Participant's share of the olivia-v1 attest scalar for outcome `index`. A participant must
+/// never reveal shares for two different outcomes with the same `nonce_share`.
+pub fn partial_attest_scalar(
+    key_share: &Scalar<Secret, Zero>,
+    nonce_share: &Scalar<Secret, Zero>,
+    index: u32,
+) -> Scalar<Public, Zero> {
+    let c = Scalar::from(index);
+    s!((c + 1) * nonce_share + key_share).mark::<Public>()
+}
+
+pub fn verify_partial_attest_scalar(
+    key: &SharedKey,
+    nonce: &SharedKey,
+    participant: u32,
+    index: u32,
+    partial: &Scalar<Public, Zero>,
+) -> bool {
+    let c = Scalar::from(index);
+    let X_i = key.verification_share(participant);
+    let R_i = nonce.verification_share(participant);
+    g!(partial * G) == g!((c + 1) * R_i + X_i)
+}
+
+/// Interpolates the attest scalar from `threshold` participants' partial attest scalars
+pub fn combine_partial_attest_scalars(partials: &[(u32, Scalar<Public, Zero>)]) -> AttestScalar {
+    let signers = partials.iter().map(|(index, _)| *index).collect::<Vec<_>>();
+    AttestScalar(
+        partials
+            .iter()
+            .fold(Scalar::zero().mark::<Public>(), |acc, (index, partial)| {
+                let lambda = lagrange_coefficient(*index, &signers);
+                s!(acc + lambda * partial).mark::<Public>()
+            }),
+    )
+}
+
+/// A participant's secret nonces for one signing session. They must only ever be used once.
+pub struct SigningNonce {
+    d: Scalar,
+    e: Scalar,
+}
+
+impl SigningNonce {
+    /// The bytes must be fresh randomness
+    pub fn new(d: [u8; 32], e: [u8; 32]) -> Self {
+        let scalar = |bytes| {
+            Scalar::from_bytes_mod_order(bytes)
+                .mark::<NonZero>()
+                .expect("random bytes will not be zero")
+        };
+        Self {
+            d: scalar(d),
+            e: scalar(e),
+        }
+    }
+
+    pub fn commitment(&self) -> SigningCommitment {
+        SigningCommitment {
+            D: g!({ &self.d } * G).mark::<Normal>(),
+            E: g!({ &self.e } * G).mark::<Normal>(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SigningCommitment {
+    pub D: Point<Normal, Public, NonZero>,
+    pub E: Point<Normal, Public, NonZero>,
+}
+
+/// FROST signing of an announcement by `threshold` participants
+#[derive(Debug, Clone)]
+pub struct SigningSession {
+    commitments: Vec<(u32, SigningCommitment)>,
+    binding_factors: Vec<Scalar<Public, Zero>>,
+    R: Point<EvenY, Public, NonZero>,
+    nonce_negated: bool,
+    challenge: Scalar<Public, Zero>,
+}
+
+impl SigningSession {
+    /// Starts signing `oracle_event` with the commitments of the participants taking part.
+    pub fn new(
+        key: &SharedKey,
+        mut commitments: Vec<(u32, SigningCommitment)>,
+        oracle_event: &RawOracleEvent<Secp256k1>,
+    ) -> Result<Self, FrostError> {
+        commitments.sort_by_key(|(index, _)| *index);
+        let signers = commitments
+            .iter()
+            .map(|(index, _)| *index)
+            .collect::<Vec<_>>();
+        key.check_signers(&signers)?;
+
+        let tlv_hash;
+        let message = match oracle_event.is_tlv() {
+            true => {
+                tlv_hash = Sha256::default().chain(oracle_event.as_bytes()).finalize();
+                Message::<Public>::raw(&tlv_hash[..])
+            }
+            false => Message::<Public>::plain("DLC/announcement", oracle_event.as_bytes()),
+        };
+
+        let binding_hash = commitments.iter().fold(
+            Sha256::default()
+                .tagged(b"olivia/frost/binding")
+                .add(key.public_key.to_xonly())
+                .add(message),
+            |hash, (index, commitment)| {
+                hash.add(&index.to_be_bytes()[..])
+                    .add(commitment.D)
+                    .add(commitment.E)
+            },
+        );
+        let binding_factors = signers
+            .iter()
+            .map(|index| {
+                Scalar::from_hash(binding_hash.clone().add(&index.to_be_bytes()[..]))
+                    .mark::<Public>()
+                    .mark::<Zero>()
+            })
+            .collect::<Vec<_>>();
+
+        let R = commitments.iter().zip(&binding_factors).fold(
+            Point::zero().mark::<Jacobian>(),
+            |acc, ((_, commitment), binding)| {
+                g!(acc + { &commitment.D } + binding * { &commitment.E })
+            },
+        );
+        let (R, nonce_negated) = R
+            .mark::<Normal>()
+            .mark::<NonZero>()
+            .expect("nonce is a sum of random points")
+            .into_point_with_even_y();
+        let challenge = SCHNORR.challenge(R.to_xonly(), key.public_key.to_xonly(), message);
+
+        Ok(Self {
+            commitments,
+            binding_factors,
+            R,
+            nonce_negated,
+            challenge,
+        })
+    }
+
+    fn signer(&self, index: u32) -> Option<(&SigningCommitment, &Scalar<Public, Zero>)> {
+        let position = self.commitments.iter().position(|(i, _)| *i == index)?;
+        Some((
+            &self.commitments[position].1,
+            &self.binding_factors[position],
+        ))
+    }
+
+    fn signers(&self) -> Vec<u32> {
+        self.commitments.iter().map(|(index, _)| *index).collect()
+    }
+
+    /// Participant `index`'s signature share. Consumes the nonce so it can't be used again.
+    pub fn sign(
+        &self,
+        index: u32,
+        secret_share: &Scalar<Secret, Zero>,
+        nonce: SigningNonce,
+    ) -> Option<Scalar<Public, Zero>> {
+        let (_, binding) = self.signer(index)?;
+        let lambda = lagrange_coefficient(index, &self.signers());
+        let SigningNonce { d, e } = nonce;
+        let mut k = s!(d + binding * e);
+        k.conditional_negate(self.nonce_negated);
+        let c = &self.challenge;
+        Some(s!(k + c * lambda * secret_share).mark::<Public>())
+    }
+
+    pub fn verify_signature_share(
+        &self,
+        key: &SharedKey,
+        index: u32,
+        signature_share: &Scalar<Public, Zero>,
+    ) -> bool {
+        let (commitment, binding) = match self.signer(index) {
+            Some(signer) => signer,
+            None => return false,
+        };
+        let lambda = lagrange_coefficient(index, &self.signers());
+        let X_i = key.verification_share(index);
+        let R_i = g!({ &commitment.D } + binding * { &commitment.E })
+            .conditional_negate(self.nonce_negated);
+        let c = &self.challenge;
+        g!(signature_share * G) == g!(R_i + (c * lambda) * X_i)
+    }
+
+    pub fn combine(&self, signature_shares: &[Scalar<Public, Zero>]) -> Signature {
+        let s = signature_shares
+            .iter()
+            .fold(Scalar::zero().mark::<Public>(), |acc, share| {
+                s!(acc + share).mark::<Public>()
+            });
+        Signature(schnorr_fun::Signature {
+            R: self.R.to_xonly(),
+            s,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use olivia_core::{Group, RawOracleEvent};
+
+    fn dkg(
+        context: &[u8],
+        threshold: usize,
+        n: u32,
+        seed: u8,
+    ) -> (SharedKey, Vec<Scalar<Secret, Zero>>) {
+        let polys = (1..=n)
+            .map(|i| {
+                SecretPolynomial::from_coefficient_bytes(
+                    (0..threshold)
+                        .map(|j| [seed ^ (i as u8 * 16 + j as u8); 32])
+                        .collect(),
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let contributions = polys
+            .iter()
+            .enumerate()
+            .map(|(i, poly)| poly.contribution(context, i as u32 + 1))
+            .collect();
+        let key = SharedKey::new(context, contributions).unwrap();
+        let shares = (1..=n)
+            .map(|i| {
+                let received = polys
+                    .iter()
+                    .map(|poly| poly.share_for(i))
+                    .collect::<Vec<_>>();
+                key.secret_share(i, &received).unwrap()
+            })
+            .collect();
+        (key, shares)
+    }
+
+    #[test]
+    fn threshold_attestation_verifies() {
+        let (key, key_shares) = dkg(b"key", 2, 3, 1);
+        let (nonce, nonce_shares) = dkg(b"nonce", 2, 3, 2);
+        for i in 1..=3 {
+            assert_eq!(
+                g!({ &key_shares[i as usize - 1] } * G),
+                key.verification_share(i)
+            );
+        }
+
+        for signers in [[1u32, 2], [1, 3], [3, 2]] {
+            let partials = signers
+                .iter()
+                .map(|i| {
+                    let i_ = *i as usize - 1;
+                    let partial = partial_attest_scalar(&key_shares[i_], &nonce_shares[i_], 1);
+                    assert!(verify_partial_attest_scalar(&key, &nonce, *i, 1, &partial));
+                    assert!(!verify_partial_attest_scalar(&key, &nonce, *i, 0, &partial));
+                    (*i, partial)
+                })
+                .collect::<Vec<_>>();
+            let attest_scalar = combine_partial_attest_scalars(&partials);
+            assert!(Secp256k1::verify_attest_scalar(
+                &key.public_key(),
+                &nonce.public_nonce(),
+                1,
+                &attest_scalar
+            ));
+        }
+    }
+
+    #[test]
+    fn dkg_rejects_bad_proofs_and_shares() {
+        let polys = (1..=3u8)
+            .map(|i| SecretPolynomial::from_coefficient_bytes(vec![[i; 32], [i + 10; 32]]).unwrap())
+            .collect::<Vec<_>>();
+        let mut contributions = polys
+            .iter()
+            .enumerate()
+            .map(|(i, poly)| poly.contribution(b"context", i as u32 + 1))
+            .collect::<Vec<_>>();
+        assert!(SharedKey::new(b"other context", contributions.clone()).is_err());
+        let key = SharedKey::new(b"context", contributions.clone()).unwrap();
+        let mut shares = polys
+            .iter()
+            .map(|poly| poly.share_for(1))
+            .collect::<Vec<_>>();
+        shares.swap(1, 2);
+        assert_eq!(
+            key.secret_share(1, &shares),
+            Err(FrostError::InvalidShare { from: 2 })
+        );
+
+        contributions[2].proof = contributions[1].proof.clone();
+        assert_eq!(
+            SharedKey::new(b"context", contributions),
+            Err(FrostError::InvalidProof { index: 3 })
+        );
+    }
+
+    #[test]
+    fn dkg_rejects_minority_thresholds() {
+        let contributions = (1..=3u8)
+            .map(|i| {
+                SecretPolynomial::from_coefficient_bytes(vec![[i; 32]])
+                    .unwrap()
+                    .contribution(b"context", i as u32)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            SharedKey::new(b"context", contributions),
+            Err(FrostError::InvalidThreshold { threshold: 1, n: 3 })
+        );
+        assert!(check_threshold(2, 3).is_ok());
+        assert!(check_threshold(3, 4).is_ok());
+        assert!(check_threshold(2, 4).is_err());
+        assert!(check_threshold(4, 3).is_err());
+    }
+
+    #[test]
+    fn zero_coefficient_is_rejected() {
+        assert_eq!(
+            SecretPolynomial::from_coefficient_bytes(vec![[1; 32], [0; 32]]).err(),
+            Some(FrostError::ZeroCoefficient { index: 1 })
+        );
+    }
+
+    #[test]
+    fn threshold_announcement_signature_verifies() {
+        let (key, key_shares) = dkg(b"announcement", 2, 3, 3);
+        for oracle_event in [
+            RawOracleEvent::<Secp256k1>::from_json_bytes(b"{\"hello\":\"world\"}".to_vec()),
+            RawOracleEvent::<Secp256k1>::from_tlv_bytes(vec![1, 2, 3]),
+        ] {
+            let nonces = [(1u32, [7u8; 32], [8u8; 32]), (3, [9u8; 32], [10u8; 32])];
+            let commitments = nonces
+                .iter()
+                .map(|(i, d, e)| (*i, SigningNonce::new(*d, *e).commitment()))
+                .collect();
+            let session = SigningSession::new(&key, commitments, &oracle_event).unwrap();
+            let shares = nonces
+                .iter()
+                .map(|(i, d, e)| {
+                    let share = session
+                        .sign(*i, &key_shares[*i as usize - 1], SigningNonce::new(*d, *e))
+                        .unwrap();
+                    assert!(session.verify_signature_share(&key, *i, &share));
+                    assert!(!session.verify_signature_share(&key, 2, &share));
+                    share
+                })
+                .collect::<Vec<_>>();
+            let signature = session.combine(&shares);
+            assert!(oracle_event.verify(&key.public_key(), &signature));
+        }
+
+        assert!(SigningSession::new(
+            &key,
+            vec![(1, SigningNonce::new([1; 32], [2; 32]).commitment())],
+            &RawOracleEvent::<Secp256k1>::from_tlv_bytes(vec![]),
+        )
+        .is_err());
+    }
+}
@@ -1,8 +1,6 @@
 #![allow(non_snake_case)]
 pub use ecdsa_fun;
-use olivia_core::{GroupObject, OracleKeys};
-#[doc(hidden)]
-pub use schnorr_fun::fun::hex;
+use olivia_core::{GroupObject, OracleKeys, ToFromBytes};
 pub use schnorr_fun::{self, fun, KeyPair};
 use schnorr_fun::{
     fun::{g, marker::*, nonce::Deterministic, s, Point, Scalar, XOnly, G},
@@ -10,7 +8,7 @@ use schnorr_fun::{
 };
 pub use serde;
 use sha2::{Digest, Sha256};
-mod macros;
+pub mod frost;
 
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Secp256k1;
@@ -18,13 +16,13 @@ pub struct Secp256k1;
 #[derive(PartialEq, Clone)]
 pub struct PublicKey(XOnly);
 
-crate::impl_display_debug_serialize_tosql! {
+olivia_core::impl_display_debug_serialize_tosql! {
     fn to_bytes(pk: &PublicKey) -> &[u8;32] {
         pk.0.as_bytes()
     }
 }
 
-crate::impl_fromstr_deserialize_fromsql! {
+olivia_core::impl_fromstr_deserialize_fromsql! {
     name => "secp256k1 xonly public key",
     fn from_bytes(bytes: [u8;32]) ->  Option<PublicKey> {
         XOnly::from_bytes(bytes).map(PublicKey)
@@ -33,17 +31,41 @@ crate::impl_fromstr_deserialize_fromsql! {
 
 impl GroupObject for PublicKey {}
 
+impl ToFromBytes for PublicKey {
+    const LEN: usize = 32;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.as_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        XOnly::from_bytes(bytes.try_into().ok()?).map(PublicKey)
+    }
+}
+
 #[derive(PartialEq, Clone)]
 pub struct PublicNonce(XOnly);
 impl GroupObject for PublicNonce {}
 
-crate::impl_display_debug_serialize_tosql! {
+impl ToFromBytes for PublicNonce {
+    const LEN: usize = 32;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.as_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        XOnly::from_bytes(bytes.try_into().ok()?).map(PublicNonce)
+    }
+}
+
+olivia_core::impl_display_debug_serialize_tosql! {
     fn to_bytes(pn: &PublicNonce) -> &[u8;32] {
         pn.0.as_bytes()
     }
 }
 
-crate::impl_fromstr_deserialize_fromsql! {
+olivia_core::impl_fromstr_deserialize_fromsql! {
     name => "secp256k1 xonly public nonce",
     fn from_bytes(bytes: [u8;32]) ->  Option<PublicNonce> {
         XOnly::from_bytes(bytes).map(PublicNonce)
@@ -54,13 +76,25 @@ crate::impl_fromstr_deserialize_fromsql! {
 pub struct AttestScalar(Scalar<Public, Zero>);
 impl GroupObject for AttestScalar {}
 
-crate::impl_display_debug_serialize_tosql! {
+impl ToFromBytes for AttestScalar {
+    const LEN: usize = 32;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Scalar::from_bytes(bytes.try_into().ok()?).map(|s| AttestScalar(s.mark::<Public>()))
+    }
+}
+
+olivia_core::impl_display_debug_serialize_tosql! {
     fn to_bytes(scalar: &AttestScalar) -> [u8;32] {
         scalar.0.to_bytes()
     }
 }
 
-crate::impl_fromstr_deserialize_fromsql! {
+olivia_core::impl_fromstr_deserialize_fromsql! {
     name => "secp256k1 scalar",
     fn from_bytes(bytes: [u8;32]) ->  Option<AttestScalar> {
         Scalar::from_bytes(bytes).map(|s| AttestScalar(s.mark::<Public>()))
@@ -70,13 +104,13 @@ crate::impl_fromstr_deserialize_fromsql! {
 #[derive(PartialEq, Clone)]
 pub struct Signature(schnorr_fun::Signature);
 
-crate::impl_display_debug_serialize_tosql! {
+olivia_core::impl_display_debug_serialize_tosql! {
     fn to_bytes(sig: &Signature) -> [u8;64] {
         sig.0.to_bytes()
     }
 }
 
-crate::impl_fromstr_deserialize_fromsql! {
+olivia_core::impl_fromstr_deserialize_fromsql! {
     name => "bip340 schnorr signature",
     fn from_bytes(bytes: [u8;64]) ->  Option<Signature> {
         schnorr_fun::Signature::from_bytes(bytes).map(Signature)
@@ -85,16 +119,28 @@ crate::impl_fromstr_deserialize_fromsql! {
 
 impl GroupObject for Signature {}
 
+impl ToFromBytes for Signature {
+    const LEN: usize = 64;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        schnorr_fun::Signature::from_bytes(bytes.try_into().ok()?).map(Signature)
+    }
+}
+
 #[derive(PartialEq, Clone)]
 pub struct EcdsaSignature(ecdsa_fun::Signature);
 
-crate::impl_display_debug_serialize_tosql! {
+olivia_core::impl_display_debug_serialize_tosql! {
     fn to_bytes(sig: &EcdsaSignature) -> [u8;64] {
         sig.0.to_bytes()
     }
 }
 
-crate::impl_fromstr_deserialize_fromsql! {
+olivia_core::impl_fromstr_deserialize_fromsql! {
     name => "ecdsa signature",
     fn from_bytes(bytes: [u8;64]) ->  Option<EcdsaSignature> {
         ecdsa_fun::Signature::from_bytes(bytes).map(EcdsaSignature)
@@ -103,6 +149,41 @@ crate::impl_fromstr_deserialize_fromsql! {
 
 impl GroupObject for EcdsaSignature {}
 
+impl ToFromBytes for EcdsaSignature {
+    const LEN: usize = 64;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        ecdsa_fun::Signature::from_bytes(bytes.try_into().ok()?).map(EcdsaSignature)
+    }
+}
+
+/// An olivia-v1 secret key recovered from an equivocation.
+#[derive(PartialEq, Clone)]
+pub struct SecretKey(Scalar);
+
+olivia_core::impl_display_debug_serialize! {
+    fn to_bytes(sk: &SecretKey) -> [u8;32] {
+        sk.0.to_bytes()
+    }
+}
+
+olivia_core::impl_fromstr_deserialize! {
+    name => "secp256k1 secret key",
+    fn from_bytes(bytes: [u8;32]) ->  Option<SecretKey> {
+        Scalar::from_bytes(bytes).and_then(|s| s.mark::<NonZero>()).map(SecretKey)
+    }
+}
+
+impl From<SecretKey> for Scalar {
+    fn from(sk: SecretKey) -> Self {
+        sk.0
+    }
+}
+
 lazy_static::lazy_static! {
     pub static ref SCHNORR: Schnorr<Sha256, Deterministic<Sha256>> = Schnorr::new(Deterministic::<Sha256>::default());
     pub static ref ECDSA: ecdsa_fun::ECDSA<Deterministic<Sha256>> = ecdsa_fun::ECDSA::<Deterministic<Sha256>>::default();
@@ -159,6 +240,7 @@ impl olivia_core::Group for Secp256k1 {
     type Signature = Signature;
     type AttestScalar = AttestScalar;
     type AnticipatedAttestation = Point<Jacobian, Public, Zero>;
+    type SecretKey = SecretKey;
     const KEY_MATERIAL_LEN: usize = 32;
 
     fn name() -> &'static str {
@@ -170,11 +252,10 @@ impl olivia_core::Group for Secp256k1 {
         message: &[u8],
         sig: &Self::Signature,
     ) -> bool {
-        let public_key = public_key.0.clone();
-        let verification_key = public_key.to_point();
+        let verification_key = public_key.0.to_point();
         SCHNORR.verify(
             &verification_key,
-            Message::<Public>::plain("DLC/announcement", &message[..]),
+            Message::<Public>::plain("DLC/announcement", message),
             &sig.0,
         )
     }
@@ -205,6 +286,33 @@ impl olivia_core::Group for Secp256k1 {
             .collect()
     }
 
+    fn combine_anticipated_attestations(
+        anticipated: &[Self::AnticipatedAttestation],
+    ) -> Self::AnticipatedAttestation {
+        anticipated
+            .iter()
+            .fold(Point::zero().mark::<Jacobian>(), |sum, point| {
+                g!(sum + point)
+            })
+    }
+
+    fn combine_attest_scalars(attest_scalars: &[Self::AttestScalar]) -> Self::AttestScalar {
+        AttestScalar(
+            attest_scalars
+                .iter()
+                .fold(Scalar::zero().mark::<Public>(), |sum, scalar| {
+                    s!(sum + { &scalar.0 }).mark::<Public>()
+                }),
+        )
+    }
+
+    fn attest_scalar_matches(
+        anticipated: &Self::AnticipatedAttestation,
+        attest_scalar: &Self::AttestScalar,
+    ) -> bool {
+        g!({ &attest_scalar.0 } * G) == *anticipated
+    }
+
     fn sign_announcement(keypair: &Self::KeyPair, announcement: &[u8]) -> Self::Signature {
         Signature(SCHNORR.sign(
             keypair,
@@ -212,6 +320,52 @@ impl olivia_core::Group for Secp256k1 {
         ))
     }
 
+    fn sign_announcement_tlv(keypair: &Self::KeyPair, oracle_event: &[u8]) -> Self::Signature {
+        let hash = Sha256::default().chain(oracle_event).finalize();
+        Signature(SCHNORR.sign(keypair, Message::<Public>::raw(&hash[..])))
+    }
+
+    fn verify_announcement_tlv_signature(
+        public_key: &Self::PublicKey,
+        oracle_event: &[u8],
+        sig: &Self::Signature,
+    ) -> bool {
+        let hash = Sha256::default().chain(oracle_event).finalize();
+        SCHNORR.verify(
+            &public_key.0.to_point(),
+            Message::<Public>::raw(&hash[..]),
+            &sig.0,
+        )
+    }
+
+    fn dlc_v0_sign(
+        keypair: &Self::KeyPair,
+        nonce_keypair: Self::NonceKeyPair,
+        outcome: &[u8],
+    ) -> Self::Signature {
+        let (r, R) = nonce_keypair;
+        let hash = Sha256::default().chain(outcome).finalize();
+        let c = SCHNORR.challenge(R, keypair.public_key(), Message::<Public>::raw(&hash[..]));
+        let x = keypair.secret_key();
+        let s = s!(r + c * x).mark::<Public>();
+        Signature(schnorr_fun::Signature { R, s })
+    }
+
+    fn dlc_v0_verify(
+        public_key: &Self::PublicKey,
+        public_nonce: &Self::PublicNonce,
+        outcome: &[u8],
+        sig: &Self::Signature,
+    ) -> bool {
+        let hash = Sha256::default().chain(outcome).finalize();
+        sig.0.R == public_nonce.0
+            && SCHNORR.verify(
+                &public_key.0.to_point(),
+                Message::<Public>::raw(&hash[..]),
+                &sig.0,
+            )
+    }
+
     fn verify_attest_scalar(
         public_key: &Self::PublicKey,
         public_nonce: &Self::PublicNonce,
@@ -225,6 +379,28 @@ impl olivia_core::Group for Secp256k1 {
         g!(s * G) == g!((c + 1) * R + X)
     }
 
+    fn extract_secret_from_equivocation(
+        public_key: &Self::PublicKey,
+        public_nonce: &Self::PublicNonce,
+        (index1, attest_scalar1): (u32, &Self::AttestScalar),
+        (index2, attest_scalar2): (u32, &Self::AttestScalar),
+    ) -> Option<Self::SecretKey> {
+        let (s1, s2) = (&attest_scalar1.0, &attest_scalar2.0);
+        let (c1, c2) = (Scalar::from(index1), Scalar::from(index2));
+        // s1 - s2 = (c1 - c2) * r
+        let c_diff = s!(c1 - c2).mark::<NonZero>()?;
+        let r = s!((s1 - s2) * { c_diff.invert() });
+        let x = s!(s1 - (c1 + 1) * r).mark::<NonZero>()?;
+        let X = public_key.0.to_point();
+        let R = public_nonce.0.to_point();
+
+        if g!(x * G) != X || g!(r * G) != R {
+            return None;
+        }
+
+        Some(SecretKey(x.mark::<Secret>()))
+    }
+
     fn test_keypair() -> Self::KeyPair {
         SCHNORR.new_keypair(
             Scalar::from_bytes_mod_order([42u8; 32])
@@ -247,6 +423,7 @@ impl olivia_core::Group for Secp256k1 {
             ecdsa_v1: Some(PublicKey(XOnly::from_bytes([14u8; 32]).unwrap())),
             olivia_v1: Some(PublicKey(XOnly::from_bytes([16u8; 32]).unwrap())),
             group: Secp256k1,
+            olivia_v1_namespaces: Default::default(),
         }
     }
 
@@ -265,7 +442,7 @@ impl olivia_core::Group for Secp256k1 {
             .mark::<NonZero>()
             .expect("will not be zero");
 
-        let R = XOnly::from_scalar_mul(&G, &mut r);
+        let R = XOnly::from_scalar_mul(G, &mut r);
         (r, R)
     }
 
@@ -301,7 +478,15 @@ olivia_core::impl_deserialize_curve!(Secp256k1);
 #[cfg(test)]
 mod test {
     use super::*;
-    use olivia_core::Group;
+    use core::str::FromStr;
+    use olivia_core::{
+        attest,
+        chrono::NaiveDateTime,
+        tlv::{OracleAttestationTlv, OracleEventTlv},
+        Attestation, AttestationSchemes, Descriptor, Event, EventId, Group, Outcome,
+        RawAnnouncement,
+    };
+    use schnorr_fun::fun::hex;
     #[test]
     fn anticipate_vs_attest() {
         let oracle_key = Secp256k1::test_keypair();
@@ -326,4 +511,420 @@ mod test {
     fn test_oracle_keys() {
         let _ = Secp256k1::test_oracle_keys();
     }
+
+    // announcements from rust-dlc's test inputs
+    const ENUM_ANNOUNCEMENT: &[&str] = &[
+        "fdd8249d909a7dbba1180d50f73f0e595b20a5120a6da0aa461c81e50c54273aba6f46f7a661ffa6",
+        "d3a164ed503ecbf9256f7c9fb265ca7c0bb8485ba3737e1b99a8fec673fca9bb2fb07f932a522cad",
+        "c5ef3795f6bdc1624341dd342115e54aa016a3fafdd8223900010689b7bc48b55b02ff9fb29e569c",
+        "02a67f4c1523eacf515e1504d31f6b6890fd60bf0bb0fdd8060a0004016101620163016404546573",
+        "74",
+    ];
+
+    const DIGIT_DECOMPOSITION_ANNOUNCEMENT: &[&str] = &[
+        "fdd824fd01c7751bc676adfc3aa9855a411e5a7097537b67784c97e0a7128d56cddb6efaf87852fc",
+        "bcf90a9cc8154a07f51607dd9b3584301ee9b2cf43cdf76df3fd0241c06fb395df6d3b8cb48e4524",
+        "52ef4479f2198b555acaca6cd22bc183d38bd3e7cfd1fdd822fd0161000a18c9cc2a169096863b6b",
+        "0342bd8871708e45b1aa0d779c1fc7eadbe09ff64d96ab532fba44d2ed03ac6a7d71068d3361f531",
+        "dfdb2c2d6fb3652421418970979b2997ed2014d127d553cedd50f996a910f15c37830a90ab7e3886",
+        "05400964338578505cd545e5b183f556a69b2d296794fd88e7ee1efb9fd0f676b25113d0caf9c7f2",
+        "c221181e701e6088ddbc38828bebbc2482c3b671918f95d070545405895b461e7ed8f29f84c8b99c",
+        "6aba48bb86a4b8ca50a3c2da2eb78dfe2c5afbb4ba4c84036ba82584dc58a33b100b99644bcd3543",
+        "310649dd0714781178b787b94325550f10c452c49a0ef3c8c7834fb507fd887914fcdd0cb37351aa",
+        "1301ed0535cc3e52b88c9618bb52b193f6bc9183e577b2f6f884c81816236f0141d6a8d3808a0500",
+        "4deecc0dccfe42b4344b80c74b641d61b9fc2af690b81170a608f45dd36960bf0bb0fdd80a120002",
+        "0008736174732f73656300000000000a0454657374",
+    ];
+
+    fn decode_test_vector(
+        test_vector: &[&str],
+    ) -> (
+        Vec<u8>,
+        RawAnnouncement<Secp256k1>,
+        PublicKey,
+        OracleEventTlv<Secp256k1>,
+    ) {
+        let bytes = hex::decode(&test_vector.concat()).unwrap();
+        let (announcement, public_key) = RawAnnouncement::<Secp256k1>::from_tlv(&bytes).unwrap();
+        assert!(announcement
+            .oracle_event
+            .verify(&public_key, &announcement.signature));
+        assert_eq!(announcement.to_tlv(&public_key), Some(bytes.clone()));
+        let oracle_event = OracleEventTlv::decode(announcement.oracle_event.as_bytes()).unwrap();
+        assert_eq!(
+            oracle_event.encode().as_deref(),
+            Some(announcement.oracle_event.as_bytes())
+        );
+        (bytes, announcement, public_key, oracle_event)
+    }
+
+    #[test]
+    fn decode_enum_announcement_tlv() {
+        let (_, announcement, _, oracle_event) = decode_test_vector(ENUM_ANNOUNCEMENT);
+        assert_eq!(oracle_event.nonces.len(), 1);
+        assert_eq!(oracle_event.event_maturity_epoch, 1623133104);
+        assert_eq!(oracle_event.event_id, "Test");
+        assert_eq!(
+            oracle_event.descriptor,
+            Descriptor::Enum {
+                outcomes: vec!["a".into(), "b".into(), "c".into(), "d".into()]
+            }
+        );
+        // "Test" isn't an olivia event id
+        assert!(announcement.oracle_event.decode().is_none());
+    }
+
+    #[test]
+    fn decode_digit_decomposition_announcement_tlv() {
+        let (_, _, _, oracle_event) = decode_test_vector(DIGIT_DECOMPOSITION_ANNOUNCEMENT);
+        assert_eq!(oracle_event.nonces.len(), 10);
+        assert_eq!(
+            oracle_event.descriptor,
+            Descriptor::DigitDecomposition {
+                base: 2,
+                is_signed: false,
+                n_digits: 10,
+                unit: Some("sats/sec".into()),
+                precision: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn tlv_announcement_roundtrip() {
+        for id in [
+            "/foo/bar/FOO_BAR.vs",
+            "/foo/bar.price?n=20",
+            "/foo/bar.price?n=20&signed",
+            "/foo/bar.price?n=6&b=10",
+        ] {
+            let event = Event {
+                id: EventId::from_str(id).unwrap(),
+                expected_outcome_time: Some(
+                    NaiveDateTime::from_str("2021-10-03T00:00:00").unwrap(),
+                ),
+            };
+            let keypair = Secp256k1::test_keypair();
+            let public_key = PublicKey::from(keypair.clone());
+            let announcement = RawAnnouncement::<Secp256k1>::test_tlv_instance(event.clone())
+                .expect("event can be TLV encoded");
+            let bytes = announcement.to_tlv(&public_key).unwrap();
+            let (decoded, decoded_public_key) =
+                RawAnnouncement::<Secp256k1>::from_tlv(&bytes).unwrap();
+            assert_eq!(decoded, announcement);
+            assert_eq!(decoded_public_key, public_key);
+            let oracle_event = decoded
+                .verify_against_id(&event.id, &public_key)
+                .expect("valid announcement");
+            assert_eq!(oracle_event.event, event);
+        }
+    }
+
+    #[test]
+    fn tlv_attestation_roundtrip() {
+        let event = Event {
+            id: EventId::from_str("/foo/bar.price?n=6").unwrap(),
+            expected_outcome_time: None,
+        };
+        let keypair = Secp256k1::test_keypair();
+        let public_key = PublicKey::from(keypair.clone());
+        let oracle_event = RawAnnouncement::<Secp256k1>::test_tlv_instance(event.clone())
+            .unwrap()
+            .oracle_event
+            .decode()
+            .unwrap();
+        let outcome = Outcome {
+            id: event.id.clone(),
+            value: 23,
+            void: false,
+        };
+        let signatures = outcome
+            .attestation_outcome_strings()
+            .iter()
+            .map(|outcome| {
+                Secp256k1::dlc_v0_sign(
+                    &keypair,
+                    Secp256k1::test_nonce_keypair(),
+                    outcome.as_bytes(),
+                )
+            })
+            .collect();
+        let attestation = Attestation::<Secp256k1>::new(
+            outcome.outcome_string(),
+            NaiveDateTime::from_str("2021-10-03T00:00:00").unwrap(),
+            AttestationSchemes {
+                dlc_v0: Some(attest::DlcV0 { signatures }),
+                ..Default::default()
+            },
+        );
+        let oracle_keys = OracleKeys {
+            olivia_v1: Some(public_key.clone()),
+            ..Secp256k1::test_oracle_keys()
+        };
+        assert_eq!(
+            attestation.verify_dlc_v0_attestation(&oracle_event, &oracle_keys),
+            Ok(())
+        );
+
+        let tlv = attestation.to_tlv(&oracle_event, &public_key).unwrap();
+        assert_eq!(tlv.outcomes, ["0", "1", "0", "1", "1", "1"]);
+        let decoded = OracleAttestationTlv::<Secp256k1>::decode(&tlv.encode()).unwrap();
+        assert_eq!(decoded, tlv);
+        assert_eq!(decoded.event_id, "/foo/bar.price?n=6");
+
+        let nonces = &oracle_event.schemes.dlc_v0.as_ref().unwrap().nonces;
+        for ((signature, outcome), nonce) in
+            decoded.signatures.iter().zip(&decoded.outcomes).zip(nonces)
+        {
+            assert!(Secp256k1::dlc_v0_verify(
+                &decoded.public_key,
+                nonce,
+                outcome.as_bytes(),
+                signature
+            ));
+            // an olivia-v1 attestation to the same outcome doesn't verify as dlc-v0
+            assert!(!Secp256k1::dlc_v0_verify(
+                &decoded.public_key,
+                nonce,
+                b"2",
+                signature
+            ));
+        }
+    }
+
+    #[test]
+    fn two_of_three_quorum() {
+        use olivia_core::{announce, AttestationInvalid, Quorum, QuorumError};
+        let id = EventId::from_str("/foo/red_blue.winner").unwrap();
+        let oracles = (1u8..=3)
+            .map(|i| {
+                let keypair = Secp256k1::keypair_from_secret_bytes(&[i; 32]);
+                let nonce_keypair = Secp256k1::nonce_keypair_from_secret_bytes(&[i + 100; 32]);
+                let keys = OracleKeys {
+                    announcement: keypair.clone().into(),
+                    olivia_v1: Some(keypair.clone().into()),
+                    ..Secp256k1::test_oracle_keys()
+                };
+                let announcement = RawAnnouncement::create(
+                    Event::from(id.clone()),
+                    &keypair,
+                    olivia_core::AnnouncementSchemes {
+                        olivia_v1: Some(announce::OliviaV1 {
+                            nonces: vec![nonce_keypair.clone().into()],
+                        }),
+                        ecdsa_v1: None,
+                        dlc_v0: None,
+                    },
+                );
+                (keypair, nonce_keypair, keys, announcement)
+            })
+            .collect::<Vec<_>>();
+        let quorum = Quorum::new(
+            id.clone(),
+            2,
+            oracles
+                .iter()
+                .map(|(_, _, keys, announcement)| (keys.clone(), announcement.clone()))
+                .collect(),
+        )
+        .unwrap();
+        assert_eq!(quorum.quorums(), vec![vec![0, 1], vec![0, 2], vec![1, 2]]);
+
+        let attest = |oracle: usize, outcome: &str| {
+            let (keypair, nonce_keypair, _, _) = &oracles[oracle];
+            let outcome = Outcome::try_from_id_and_outcome(id.clone(), outcome).unwrap();
+            let scalars = outcome
+                .attestation_indexes()
+                .into_iter()
+                .map(|index| Secp256k1::reveal_attest_scalar(keypair, nonce_keypair.clone(), index))
+                .collect();
+            (
+                oracle,
+                Attestation::<Secp256k1>::new(
+                    outcome.outcome_string(),
+                    NaiveDateTime::from_str("2021-10-03T00:00:00").unwrap(),
+                    AttestationSchemes {
+                        olivia_v1: Some(attest::OliviaV1 { scalars }),
+                        ecdsa_v1: None,
+                        dlc_v0: None,
+                    },
+                ),
+            )
+        };
+
+        let attested = quorum
+            .verify_attestations(&[attest(2, "blue"), attest(2, "blue"), attest(0, "blue")])
+            .unwrap();
+        assert_eq!(attested.oracles, vec![2, 0]);
+        assert_eq!(attested.outcome.outcome_string(), "blue");
+        let anticipated = quorum
+            .anticipate_outcome(&[0, 2], &attested.outcome)
+            .unwrap();
+        assert!(Secp256k1::attest_scalar_matches(
+            &anticipated[0],
+            &attested.scalars[0]
+        ));
+        // the quorum's point for "red" can't be unlocked with the attestation to "blue"
+        let red = Outcome::try_from_id_and_outcome(id.clone(), "red").unwrap();
+        assert!(!Secp256k1::attest_scalar_matches(
+            &quorum.anticipate_outcome(&[0, 2], &red).unwrap()[0],
+            &attested.scalars[0]
+        ));
+
+        assert_eq!(
+            quorum.verify_attestations(&[attest(1, "blue")]),
+            Err(QuorumError::NotEnoughAttestations {
+                attested: 1,
+                threshold: 2,
+                invalid: vec![]
+            })
+        );
+        assert_eq!(
+            quorum.verify_attestations(&[attest(1, "blue"), attest(0, "red")]),
+            Err(QuorumError::NotEnoughAttestations {
+                attested: 1,
+                threshold: 2,
+                invalid: vec![]
+            })
+        );
+        assert_eq!(
+            quorum.verify_attestations(&[(3, attest(1, "blue").1)]),
+            Err(QuorumError::UnknownOracle { oracle: 3 })
+        );
+
+        // an oracle attesting to a different outcome doesn't stop the other two wherever it is
+        for attestations in [
+            [attest(0, "red"), attest(1, "blue"), attest(2, "blue")],
+            [attest(1, "blue"), attest(0, "red"), attest(2, "blue")],
+            [attest(1, "blue"), attest(2, "blue"), attest(0, "red")],
+        ] {
+            let attested = quorum.verify_attestations(&attestations).unwrap();
+            assert_eq!(attested.outcome.outcome_string(), "blue");
+            assert_eq!(attested.oracles, vec![1, 2]);
+        }
+
+        // nor does an invalid attestation
+        let (_, mut forged) = attest(0, "blue");
+        forged.schemes = attest(1, "blue").1.schemes;
+        let attested = quorum
+            .verify_attestations(&[(0, forged.clone()), attest(1, "blue"), attest(2, "blue")])
+            .unwrap();
+        assert_eq!(attested.oracles, vec![1, 2]);
+        assert_eq!(
+            quorum.verify_attestations(&[attest(1, "blue"), (0, forged)]),
+            Err(QuorumError::NotEnoughAttestations {
+                attested: 1,
+                threshold: 2,
+                invalid: vec![(0, AttestationInvalid::OliviaV1)]
+            })
+        );
+
+        let mut bad_announcements = oracles
+            .iter()
+            .map(|(_, _, keys, announcement)| (keys.clone(), announcement.clone()))
+            .collect::<Vec<_>>();
+        bad_announcements[1].1 = oracles[0].3.clone();
+        assert_eq!(
+            Quorum::new(id, 2, bad_announcements),
+            Err(QuorumError::InvalidAnnouncement { oracle: 1 })
+        );
+    }
+
+    #[test]
+    fn equivocation_fraud_proof() {
+        use olivia_core::{announce, FraudProof, FraudProofError};
+        let id = EventId::from_str("/foo/bar.price?n=6").unwrap();
+        let keypair = Secp256k1::keypair_from_secret_bytes(&[7u8; 32]);
+        let nonce_keypairs = (0..6u8)
+            .map(|i| Secp256k1::nonce_keypair_from_secret_bytes(&[i + 1; 32]))
+            .collect::<Vec<_>>();
+        let keys = OracleKeys {
+            announcement: keypair.clone().into(),
+            olivia_v1: Some(keypair.clone().into()),
+            ..Secp256k1::test_oracle_keys()
+        };
+        let announcement = RawAnnouncement::create(
+            Event::from(id.clone()),
+            &keypair,
+            olivia_core::AnnouncementSchemes {
+                olivia_v1: Some(announce::OliviaV1 {
+                    nonces: nonce_keypairs.iter().cloned().map(Into::into).collect(),
+                }),
+                ecdsa_v1: None,
+                dlc_v0: None,
+            },
+        );
+        let attest = |outcome: &str| {
+            let outcome = Outcome::try_from_id_and_outcome(id.clone(), outcome).unwrap();
+            let scalars = outcome
+                .attestation_indexes()
+                .into_iter()
+                .zip(nonce_keypairs.iter().cloned())
+                .map(|(index, nonce_keypair)| {
+                    Secp256k1::reveal_attest_scalar(&keypair, nonce_keypair, index)
+                })
+                .collect();
+            Attestation::<Secp256k1>::new(
+                outcome.outcome_string(),
+                NaiveDateTime::from_str("2021-10-03T00:00:00").unwrap(),
+                AttestationSchemes {
+                    olivia_v1: Some(attest::OliviaV1 { scalars }),
+                    ecdsa_v1: None,
+                    dlc_v0: None,
+                },
+            )
+        };
+
+        let (five, seven) = (attest("5"), attest("7"));
+        let proof = FraudProof::from_attestations([&keys], &announcement, [&five, &seven]).unwrap();
+        assert_eq!(proof.secret_key, SecretKey(keypair.secret_key().clone()));
+        assert_eq!(proof.outcomes, ["5".to_string(), "7".to_string()]);
+        let differs = |i: usize| {
+            let index = |outcome| {
+                Outcome::try_from_id_and_outcome(id.clone(), outcome)
+                    .unwrap()
+                    .attestation_indexes()[i]
+            };
+            index("5") != index("7")
+        };
+        assert!(differs(proof.nonce_index));
+        assert!((0..proof.nonce_index).all(|i| !differs(i)));
+
+        assert_eq!(
+            FraudProof::from_attestations([&keys], &announcement, [&five, &five]),
+            Err(FraudProofError::SameOutcome("5".into()))
+        );
+        let mut forged = seven.clone();
+        forged.outcome = "6".into();
+        assert!(matches!(
+            FraudProof::from_attestations([&keys], &announcement, [&five, &forged]),
+            Err(FraudProofError::InvalidAttestation { attestation: 1, .. })
+        ));
+        let other_keys = Secp256k1::test_oracle_keys();
+        assert_eq!(
+            FraudProof::from_attestations([&other_keys], &announcement, [&five, &seven]),
+            Err(FraudProofError::InvalidAnnouncement)
+        );
+
+        // after the keys are rotated the event is still checked against the keys it was
+        // announced with
+        let rotated =
+            FraudProof::from_attestations([&keys, &other_keys], &announcement, [&five, &seven])
+                .unwrap();
+        assert_eq!(rotated, proof);
+
+        let scalar = |attestation: &Attestation<Secp256k1>| {
+            attestation.schemes.olivia_v1.as_ref().unwrap().scalars[0].clone()
+        };
+        // the same index under the same nonce gives nothing away
+        assert_eq!(
+            Secp256k1::extract_secret_from_equivocation(
+                &keys.announcement,
+                &nonce_keypairs[0].clone().into(),
+                (0, &scalar(&five)),
+                (0, &scalar(&five)),
+            ),
+            None
+        );
+    }
 }